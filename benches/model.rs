@@ -0,0 +1,86 @@
+//! Compares memory and throughput of owned `Midi` model against borrowed `MidiRef` view.
+//! 
+//! Run with `cargo bench --bench model`, optionally passing midi files to benchmark:
+//! `cargo bench --bench model -- ./midis/test2.mid`
+
+use std::{
+  alloc::{GlobalAlloc, Layout, System},
+  sync::atomic::{AtomicUsize, Ordering},
+  time::{Duration, Instant},
+};
+
+use rmidirs::{parser::MidiParser, model::core::midi_ref::MidiRef};
+
+/// Allocator counting the live and peak allocated bytes.
+struct CountingAlloc;
+
+static LIVE : AtomicUsize = AtomicUsize::new(0);
+static PEAK : AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+    PEAK.fetch_max(live, Ordering::Relaxed);
+    System.alloc(layout)
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    System.dealloc(ptr, layout)
+  }
+}
+
+#[global_allocator]
+static ALLOC : CountingAlloc = CountingAlloc;
+
+const ITERATIONS : u32 = 20;
+
+/// Runs `f` ITERATIONS times, returns the average time and peak bytes allocated by single run.
+fn measure(mut f : impl FnMut() -> usize) -> (Duration, usize) {
+  // warm up, also loads the event schemas
+  f();
+  let mut peak = 0;
+  let start = Instant::now();
+  for _ in 0..ITERATIONS {
+    let live = LIVE.load(Ordering::Relaxed);
+    PEAK.store(live, Ordering::Relaxed);
+    std::hint::black_box(f());
+    peak = peak.max(PEAK.load(Ordering::Relaxed) - live);
+  }
+  (start.elapsed() / ITERATIONS, peak)
+}
+
+fn report(name : &str, size : usize, (time, peak) : (Duration, usize)) {
+  let throughput = size as f64 / time.as_secs_f64() / 1_000_000.0;
+  println!("  {name:<24} {time:>12.2?} {throughput:>10.2} MB/s {peak:>12} bytes peak");
+}
+
+fn main() {
+  let mut paths = std::env::args().skip(1).filter(|arg| !arg.starts_with('-')).collect::<Vec<_>>();
+  if paths.is_empty() {
+    paths = vec!["./midis/test.mid".to_string(), "./midis/test2.mid".to_string()];
+  }
+
+  for path in paths {
+    let buf = std::fs::read(&path).expect("midi file should be readable");
+    println!("{path} ({} bytes)", buf.len());
+
+    report("owned Midi", buf.len(), measure(|| {
+      let midi = MidiParser::parse(&buf).unwrap();
+      midi.tracks().len()
+    }));
+
+    report("borrowed MidiRef", buf.len(), measure(|| {
+      let midi = MidiRef::parse(&buf).unwrap();
+      midi.tracks()
+        .iter()
+        .map(|track| track.events().filter(|event| matches!(event, Ok(event) if event.is_note_on_event())).count())
+        .sum()
+    }));
+
+    report("MidiRef to owned Midi", buf.len(), measure(|| {
+      let midi = MidiRef::parse(&buf).unwrap().to_midi().unwrap();
+      midi.tracks().len()
+    }));
+  }
+}
@@ -0,0 +1,167 @@
+use std::{collections::HashMap, fmt::Write as _};
+
+use crate::{
+  primitive::{Word, FloatWord, FractionWord},
+  model::{note_seq::NoteSeq, core::midi_event::meta_message::KeySignature}
+};
+
+/// bars written on each line of body
+const BARS_PER_LINE : usize = 4;
+
+/// Writes quantized monophonic note sequence as ABC tune, see [`abc`](super).
+///
+/// Header has `M`, `L`, `Q` and `K` fields from the first time signature, tempo and key signature,
+/// unit note length is 1/16 for meters shorter than 3/4 and 1/8 otherwise.
+/// Notes are quantized to quarter of unit note length, when notes start together only the highest is kept
+/// and note overlapping next onset is cut at it. Notes crossing barlines are split and tied.
+/// Time signature, key and tempo changes are written as inline fields at the bar they fall in.
+pub fn to_abc(note_seq : &NoteSeq) -> String {
+  let time_signature = note_seq.time_signatures().first().map_or((4, 4), |time_signature| *time_signature.element());
+  let unit_denominator = if 4 * time_signature.0 < 3 * time_signature.1 { 16 } else { 8 };
+  // grid of quarter of unit note length, in quarter notes
+  let step = 1.0 / unit_denominator as FloatWord;
+
+  let mut abc = String::from("X:1\n");
+  let _ = writeln!(abc, "M:{}/{}", time_signature.0, time_signature.1);
+  let _ = writeln!(abc, "L:1/{}", unit_denominator);
+  let _ = writeln!(abc, "Q:1/4={}", note_seq.tempos().first().map_or(120, |tempo| tempo.element().round() as Word));
+  let mut key = key_at(note_seq, 0.0);
+  let _ = writeln!(abc, "K:{}", key_field(&key));
+
+  let spans = spans(note_seq, step);
+  let end = spans.last().map_or(0, |span| span.1);
+
+  let (mut bar_start, mut bars, mut meter, mut tempo) = (0, 0, time_signature, note_seq.tempos().first().map(|tempo| tempo.element().round() as Word));
+  let mut line = String::new();
+  while bar_start < end {
+    let quarters = bar_start as FloatWord * step;
+    let seconds = note_seq.time_at(quarters);
+
+    let bar_meter = note_seq.time_signatures().iter().rev().find(|time_signature| time_signature.at() <= seconds + 1e-3)
+      .map_or(meter, |time_signature| *time_signature.element());
+    if bar_meter != meter {
+      let _ = write!(line, "[M:{}/{}] ", bar_meter.0, bar_meter.1);
+      meter = bar_meter;
+    }
+    let bar_key = key_at(note_seq, seconds);
+    if bar_key.sf() != key.sf() || bar_key.is_minor() != key.is_minor() {
+      let _ = write!(line, "[K:{}] ", key_field(&bar_key));
+      key = bar_key;
+    }
+    let bar_tempo = note_seq.tempos().iter().rev().find(|tempo| tempo.at() <= seconds + 1e-3).map(|tempo| tempo.element().round() as Word);
+    if bar_tempo != tempo {
+      let _ = write!(line, "[Q:1/4={}] ", bar_tempo.unwrap_or(120));
+      tempo = bar_tempo;
+    }
+
+    let bar_length = ((4.0 * meter.0 as FloatWord / meter.1.max(1) as FloatWord) / step).round().max(1.0) as u64;
+    let bar_end = bar_start + bar_length;
+    let mut accidentals : HashMap<(char, i32), i32> = HashMap::new();
+    for (start, stop, pitch) in spans.iter().filter(|span| span.0 < bar_end && span.1 > bar_start) {
+      let length = fraction(stop.min(&bar_end) - start.max(&bar_start));
+      match pitch {
+        None => { let _ = write!(line, "z{}", length); },
+        Some(pitch) => {
+          let _ = write!(line, "{}{}", note(&key, *pitch, &mut accidentals), length);
+          if *stop > bar_end { line.push('-'); }
+        },
+      }
+    }
+
+    bar_start = bar_end;
+    bars += 1;
+    match bar_start >= end {
+      true => line.push_str("|]"),
+      false => line.push_str(" | "),
+    }
+    if bars % BARS_PER_LINE == 0 || bar_start >= end {
+      abc.push_str(line.trim_end());
+      abc.push('\n');
+      line.clear();
+    }
+  }
+  abc
+}
+
+/// Quantizes notes to single voice of (start, end, pitch) in grid steps, `None` pitch for rests, covering time from 0 to last note end.
+fn spans(note_seq : &NoteSeq, step : FloatWord) -> Vec<(u64, u64, Option<Word>)> {
+  let grid = |time : FloatWord| (note_seq.beat_at(time) / step).round().max(0.0) as u64;
+  let mut notes : Vec<(u64, u64, Word)> = note_seq.notes()
+    .iter()
+    .map(|note| {
+      let start = grid(note.start_time());
+      (start, grid(note.end_time()).max(start + 1), note.pitch())
+    })
+    .collect();
+  // highest pitch first, so it is kept among notes starting together
+  notes.sort_by(|a, b| a.0.cmp(&b.0).then(b.2.cmp(&a.2)));
+  notes.dedup_by_key(|note| note.0);
+
+  let mut spans = Vec::new();
+  let mut time = 0;
+  for (index, (start, end, pitch)) in notes.iter().enumerate() {
+    if time < *start {
+      spans.push((time, *start, None));
+    }
+    let end = notes.get(index + 1).map_or(*end, |next| next.0.min(*end));
+    spans.push((*start, end, Some(*pitch)));
+    time = end;
+  }
+  spans
+}
+
+/// key signature at `seconds`, C major when the name is not known
+fn key_at(note_seq : &NoteSeq, seconds : FloatWord) -> KeySignature {
+  note_seq.key_signatures().iter().rev()
+    .find(|key| key.at() <= seconds + 1e-3)
+    .and_then(|key| KeySignature::from_name(key.element()))
+    .unwrap_or(KeySignature::new(0, false))
+}
+
+/// key of `K` field, e.g. `G`, `F#m`, `Bb`
+fn key_field(key : &KeySignature) -> String {
+  let name = key.name();
+  let tonic = name.split('-').next().unwrap_or("c");
+  let mut field = tonic[..1].to_uppercase() + &tonic[1..];
+  if key.is_minor() { field.push('m'); }
+  field
+}
+
+/// Note name with accidental and octave marks, accidental is written when it differs from key or previous note of the bar.
+fn note(key : &KeySignature, pitch : Word, accidentals : &mut HashMap<(char, i32), i32>) -> String {
+  let (step, alter, octave) = key.spell(pitch);
+  let current = accidentals.get(&(step, octave)).copied().unwrap_or_else(|| key.alter(step));
+  let mut note = String::new();
+  if current != alter {
+    note.push_str(match alter { 2 => "^^", 1 => "^", -1 => "_", -2 => "__", _ => "=" });
+    accidentals.insert((step, octave), alter);
+  }
+  match octave {
+    octave if octave >= 5 => {
+      note.push(step.to_ascii_lowercase());
+      (5 .. octave).for_each(|_| note.push('\''));
+    },
+    octave => {
+      note.push(step);
+      (octave .. 4).for_each(|_| note.push(','));
+    },
+  }
+  note
+}
+
+/// length in grid steps as ABC length in unit note lengths, e.g. 8 steps is `2`, 2 steps is `/2`, 6 steps is `3/2`
+fn fraction(steps : u64) -> String {
+  let (numerator, denominator) = reduce((steps as Word, 4));
+  match (numerator, denominator) {
+    (1, 1) => String::new(),
+    (numerator, 1) => numerator.to_string(),
+    (1, denominator) => format!("/{}", denominator),
+    (numerator, denominator) => format!("{}/{}", numerator, denominator),
+  }
+}
+
+fn reduce((numerator, denominator) : FractionWord) -> FractionWord {
+  let (mut a, mut b) = (numerator, denominator);
+  while b != 0 { (a, b) = (b, a % b); }
+  (numerator / a.max(1), denominator / a.max(1))
+}
@@ -0,0 +1,577 @@
+use std::{collections::HashMap, mem};
+
+use crate::{
+  primitive::{Word, FloatWord, FractionWord},
+  model::core::midi_event::meta_message::KeySignature
+};
+
+use super::{AbcTune, AbcParseError};
+
+/// semitones of note letters above C
+const STEPS : [(char, Word); 7] = [('C', 0), ('D', 2), ('E', 4), ('F', 5), ('G', 7), ('A', 9), ('B', 11)];
+
+/// tonics on the line of fifths, from C
+const TONICS : [(char, i32); 7] = [('F', -1), ('C', 0), ('G', 1), ('D', 2), ('A', 3), ('E', 4), ('B', 5)];
+
+/// modes with their shift on the line of fifths from major, and whether key signature is named minor
+const MODES : [(&str, i32, bool); 10] = [
+  ("", 0, false), ("maj", 0, false), ("ion", 0, false), ("m", -3, true), ("min", -3, true), ("aeo", -3, true),
+  ("mix", -1, false), ("dor", -2, false), ("phr", -4, false), ("lyd", 1, false),
+];
+
+/// shorthand decorations, skipped like `!trill!`
+const DECORATIONS : &str = ".~HIJKLMNOPQRSTUVWhijklmnopqrstuvwy";
+
+/// Imports the first tune of ABC text, see [`abc`](super).
+pub fn from_abc(text : &str) -> Result<AbcTune, AbcParseError> {
+  from_abc_all(text)
+    .into_iter()
+    .next()
+    .unwrap_or_else(|| Err(AbcParseError::new(1, String::from("no tune found"))))
+}
+
+/// Imports every tune of ABC text, each starting at `X` field, so bad tunes of collection can be skipped.
+///
+/// Text before the first `X` field is file header and is skipped, text without any `X` field is read as single tune.
+pub fn from_abc_all(text : &str) -> Vec<Result<AbcTune, AbcParseError>> {
+  let lines : Vec<&str> = text.lines().collect();
+  let mut starts : Vec<usize> = lines.iter().enumerate().filter(|(_, line)| line.starts_with("X:")).map(|(index, _)| index).collect();
+  if starts.is_empty() && lines.iter().any(|line| !line.trim().is_empty()) {
+    starts.push(0);
+  }
+
+  starts.iter().enumerate().map(|(index, start)| {
+    let end = starts.get(index + 1).copied().unwrap_or(lines.len());
+    let mut parser = Parser::default();
+    for (line_no, line) in lines.iter().enumerate().take(end).skip(*start) {
+      if parser.ended { break; }
+      parser.line(line).map_err(|message| AbcParseError::new(line_no + 1, message))?;
+    }
+    parser.finish().map_err(|message| AbcParseError::new(end.max(1), message))
+  }).collect()
+}
+
+/// raw header fields, resolved when `K` field ends the header, since `L` and `Q` depend on `M`
+#[derive(Default)]
+struct Header {
+  meter : Option<String>,
+  unit : Option<String>,
+  tempo : Option<String>,
+}
+
+/// Parses single tune line by line, times are in quarter notes.
+struct Parser {
+  tune : AbcTune,
+  header : Option<Header>,
+  /// blank line after body ends the tune
+  ended : bool,
+  voice : Option<String>,
+
+  /// unit note length in quarter notes
+  unit : FloatWord,
+  /// length of bar in quarter notes, used for default unit note length
+  meter : FloatWord,
+  key : KeySignature,
+  time : FloatWord,
+  /// accidentals given in the bar, by note letter and octave
+  accidentals : HashMap<(char, i32), i32>,
+
+  /// notes of last note or chord, and its start, lengthened or shortened by broken rhythm
+  last : Option<(Vec<usize>, FloatWord)>,
+  /// notes tied to the next note of same pitch
+  ties : Vec<usize>,
+  /// factor for next note, set by broken rhythm
+  broken : FloatWord,
+  /// factor and number of notes left in tuplet
+  tuplet : Option<(FloatWord, usize)>,
+
+  /// first note and time of repeated section
+  repeat_start : (usize, FloatWord),
+  /// time at start of first ending
+  first_ending : Option<FloatWord>,
+}
+
+impl Default for Parser {
+  fn default() -> Self {
+    Self {
+      tune : AbcTune {
+        reference : 0,
+        title : String::new(),
+        notes : Vec::new(),
+        tempos : Vec::new(),
+        time_signatures : Vec::new(),
+        key_signatures : Vec::new(),
+      },
+      header : Some(Header::default()),
+      ended : false,
+      voice : None,
+      unit : 0.5,
+      meter : 4.0,
+      key : KeySignature::new(0, false),
+      time : 0.0,
+      accidentals : HashMap::new(),
+      last : None,
+      ties : Vec::new(),
+      broken : 1.0,
+      tuplet : None,
+      repeat_start : (0, 0.0),
+      first_ending : None,
+    }
+  }
+}
+
+impl Parser {
+  fn line(&mut self, line : &str) -> Result<(), String> {
+    if line.starts_with("%%") { return Ok(()); }
+    let line = line.split('%').next().unwrap_or_default().trim_end();
+    if line.trim().is_empty() {
+      if self.header.is_none() { self.ended = true; }
+      return Ok(());
+    }
+
+    let bytes = line.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+      return self.field(bytes[0] as char, line[2..].trim());
+    }
+    if self.header.is_some() {
+      return Err(String::from("body before K field, K field must end the header"));
+    }
+    self.body(line)
+  }
+
+  fn finish(mut self) -> Result<AbcTune, String> {
+    if self.header.is_some() {
+      return Err(String::from("K field is missing"));
+    }
+    self.tune.notes.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.2.cmp(&b.2)));
+    Ok(self.tune)
+  }
+
+  fn field(&mut self, name : char, value : &str) -> Result<(), String> {
+    let header = match self.header.as_mut() {
+      Some(header) => header,
+      None => return self.body_field(name, value),
+    };
+    match name {
+      'X' => self.tune.reference = value.parse().map_err(|_| format!("expected reference number, found '{}'", value))?,
+      'T' if self.tune.title.is_empty() => self.tune.title = value.to_string(),
+      'M' => header.meter = Some(value.to_string()),
+      'L' => header.unit = Some(value.to_string()),
+      'Q' => header.tempo = Some(value.to_string()),
+      'V' => self.voice = Some(voice(value)),
+      'K' => {
+        let header = self.header.take().unwrap_or_default();
+        let time_signature = header.meter.as_deref().map(meter).transpose()?.flatten();
+        self.meter = time_signature.map_or(4.0, |(numerator, denominator)| 4.0 * numerator as FloatWord / denominator as FloatWord);
+        self.unit = match header.unit.as_deref() {
+          Some(unit) => unit_length(unit)?,
+          None if self.meter < 3.0 => 0.25,
+          None => 0.5,
+        };
+        let tempo = header.tempo.as_deref().map(|tempo| self.tempo(tempo)).transpose()?;
+        self.tune.time_signatures.push((0.0, time_signature.unwrap_or((4, 4))));
+        self.tune.tempos.push((0.0, tempo.unwrap_or(120)));
+        self.key = key(value)?;
+        self.tune.key_signatures.push((0.0, self.key.name()));
+      },
+      _ => {},
+    }
+    Ok(())
+  }
+
+  /// field inside body, on its own line or inline like `[K:D]`, changes apply from current time
+  fn body_field(&mut self, name : char, value : &str) -> Result<(), String> {
+    match name {
+      'M' => if let Some(time_signature) = meter(value)? {
+        self.meter = 4.0 * time_signature.0 as FloatWord / time_signature.1 as FloatWord;
+        set(&mut self.tune.time_signatures, self.time, time_signature);
+      },
+      'L' => self.unit = unit_length(value)?,
+      'Q' => {
+        let tempo = self.tempo(value)?;
+        set(&mut self.tune.tempos, self.time, tempo);
+      },
+      'K' => {
+        self.key = key(value)?;
+        set(&mut self.tune.key_signatures, self.time, self.key.name());
+      },
+      'V' => {
+        let voice = voice(value);
+        match &self.voice {
+          Some(current) if *current != voice => return Err(format!("multiple voices are not supported, found voice {} after {}", voice, current)),
+          _ => self.voice = Some(voice),
+        }
+      },
+      _ => {},
+    }
+    Ok(())
+  }
+
+  /// Tempo of `Q` field in qbpm, e.g. `1/4=120`, `3/8=40` or `"Allegro" 1/4=120`.
+  /// Tempo without beat, e.g. `120`, counts unit note lengths.
+  fn tempo(&self, value : &str) -> Result<Word, String> {
+    let value : String = value.split('"').step_by(2).collect();
+    let (beat, bpm) = match value.split_once('=') {
+      Some((beat, bpm)) => {
+        let mut quarters = 0.0;
+        for part in beat.split_whitespace() {
+          quarters += 4.0 * fraction(part).ok_or_else(|| format!("expected beat like 1/4, found '{}'", part))?;
+        }
+        (quarters, bpm)
+      },
+      None => (self.unit, value.as_str()),
+    };
+    let bpm : FloatWord = bpm.trim().parse().map_err(|_| format!("expected tempo, found '{}'", bpm.trim()))?;
+    Ok(((bpm * beat).round() as Word).max(1))
+  }
+
+  fn body(&mut self, line : &str) -> Result<(), String> {
+    let chars : Vec<char> = line.chars().collect();
+    let mut index = 0;
+    while index < chars.len() {
+      let char = chars[index];
+      let next = chars.get(index + 1).copied();
+      match char {
+        ' ' | '\t' | '`' | '\\' | ')' => index += 1,
+        '"' => index = skip_to(&chars, index, '"')?,
+        '!' | '+' => index = skip_to(&chars, index, char)?,
+        '{' => index = skip_to(&chars, index, '}')?,
+        '&' => return Err(String::from("voice overlay & is not supported")),
+        '(' if next.is_some_and(|next| next.is_ascii_digit()) => index = self.tuplet_start(&chars, index + 1),
+        '(' => index += 1,
+        '-' => {
+          self.ties.extend(self.last.iter().flat_map(|(notes, _)| notes.iter().copied()));
+          index += 1;
+        },
+        '>' | '<' => index = self.broken_rhythm(&chars, index),
+        '[' if next.is_some_and(|next| next.is_ascii_alphabetic()) && chars.get(index + 2) == Some(&':') => {
+          let end = skip_to(&chars, index, ']')?;
+          let field : String = chars[index + 3 .. end - 1].iter().collect();
+          self.body_field(next.unwrap_or_default(), field.trim())?;
+          index = end;
+        },
+        '[' if next.is_some_and(|next| next.is_ascii_digit()) => {
+          index = self.ending(&chars, index + 1);
+        },
+        '[' if next == Some('|') => index = self.bar(&chars, index),
+        '[' => index = self.chord(&chars, index + 1)?,
+        '|' | ':' => index = self.bar(&chars, index),
+        'z' | 'x' => {
+          let (length, end) = self.length(&chars, index + 1);
+          self.rest(length);
+          index = end;
+        },
+        'Z' => {
+          let (bars, end) = number(&chars, index + 1);
+          self.rest(bars.unwrap_or(1) as FloatWord * self.meter / self.unit);
+          index = end;
+        },
+        _ if DECORATIONS.contains(char) => index += 1,
+        '^' | '_' | '=' | 'A' ..= 'G' | 'a' ..= 'g' => {
+          let waiting = mem::take(&mut self.ties);
+          let (length, note, end) = self.note(&chars, index, &waiting)?;
+          let length = self.scale(length);
+          self.close(vec![note], length);
+          index = end;
+        },
+        _ => return Err(format!("unexpected character '{}'", char)),
+      }
+    }
+    Ok(())
+  }
+
+  /// Parses note at `index`, adds it at current time, or continues note of `waiting` tied to it.
+  /// Returns its length in unit note lengths, index of the note and index after it.
+  fn note(&mut self, chars : &[char], mut index : usize, waiting : &[usize]) -> Result<(FloatWord, usize, usize), String> {
+    let mut alter = None;
+    while let Some(char) = chars.get(index).filter(|char| matches!(char, '^' | '_' | '=')) {
+      alter = Some(alter.unwrap_or(0) + match char { '^' => 1, '_' => -1, _ => 0 });
+      index += 1;
+    }
+    let letter = match chars.get(index) {
+      Some(letter) if matches!(letter.to_ascii_uppercase(), 'A' ..= 'G') => *letter,
+      found => return Err(format!("expected note after accidental, found {:?}", found)),
+    };
+    index += 1;
+    let mut octave = if letter.is_ascii_lowercase() { 5 } else { 4 };
+    while let Some(mark) = chars.get(index).filter(|char| matches!(char, '\'' | ',')) {
+      octave += if *mark == '\'' { 1 } else { -1 };
+      index += 1;
+    }
+    let (length, index) = self.length(chars, index);
+
+    let step = letter.to_ascii_uppercase();
+    let alter = match alter {
+      Some(alter) => { self.accidentals.insert((step, octave), alter); alter },
+      None => self.accidentals.get(&(step, octave)).copied().unwrap_or_else(|| self.key.alter(step)),
+    };
+    let semitone = STEPS.iter().find(|(letter, _)| *letter == step).map_or(0, |(_, semitone)| *semitone as i32);
+    let pitch = 12 * (octave + 1) + semitone + alter;
+    if !(0 ..= 127).contains(&pitch) {
+      return Err(format!("note {} is out of MIDI range", pitch));
+    }
+
+    let start = self.time;
+    let tied = waiting.iter().copied().find(|tied| self.tune.notes[*tied].2 == pitch as Word && (self.tune.notes[*tied].1 - start).abs() < 1e-4);
+    let note = tied.unwrap_or_else(|| {
+      self.tune.notes.push((start, start, pitch as Word));
+      self.tune.notes.len() - 1
+    });
+    Ok((length, note, index))
+  }
+
+  /// Parses chord after `[`, its length is the length of first note times the length after `]`.
+  fn chord(&mut self, chars : &[char], mut index : usize) -> Result<usize, String> {
+    let waiting = mem::take(&mut self.ties);
+    let mut notes = Vec::new();
+    let mut ties = Vec::new();
+    let mut chord_length = None;
+    loop {
+      match chars.get(index) {
+        Some(']') => break,
+        Some(' ') => index += 1,
+        Some('-') => {
+          ties.extend(notes.last().copied());
+          index += 1;
+        },
+        Some(_) => {
+          let (length, note, end) = self.note(chars, index, &waiting)?;
+          chord_length.get_or_insert(length);
+          notes.push(note);
+          index = end;
+        },
+        None => return Err(String::from("chord is not closed with ]")),
+      }
+    }
+    let (factor, index) = self.length(chars, index + 1);
+    let length = self.scale(chord_length.unwrap_or(0.0) * factor);
+    self.close(notes, length);
+    self.ties = ties;
+    Ok(index)
+  }
+
+  /// length of rest, in unit note lengths
+  fn rest(&mut self, length : FloatWord) {
+    let length = self.scale(length);
+    self.ties.clear();
+    self.last = None;
+    self.time += length;
+  }
+
+  /// Applies broken rhythm and tuplet to `length` in unit note lengths, returns length in quarter notes.
+  fn scale(&mut self, length : FloatWord) -> FloatWord {
+    let mut length = length * self.unit * mem::replace(&mut self.broken, 1.0);
+    if let Some((factor, left)) = self.tuplet {
+      length *= factor;
+      self.tuplet = (left > 1).then_some((factor, left - 1));
+    }
+    length
+  }
+
+  /// Ends `notes` of note or chord after `length` quarter notes from current time, and moves time past them.
+  fn close(&mut self, notes : Vec<usize>, length : FloatWord) {
+    let start = self.time;
+    notes.iter().for_each(|note| self.tune.notes[*note].1 = start + length);
+    self.last = Some((notes, start));
+    self.time = start + length;
+  }
+
+  /// `>` lengthens previous note by half and shortens next note by half, `>>` by three quarters etc.
+  fn broken_rhythm(&mut self, chars : &[char], index : usize) -> usize {
+    let char = chars[index];
+    let count = chars[index..].iter().take_while(|next| **next == char).count();
+    let short = 0.5_f32.powi(count as i32);
+    let (previous, next) = if char == '>' { (2.0 - short, short) } else { (short, 2.0 - short) };
+    if let Some((notes, start)) = self.last.take() {
+      let length = (self.time - start) * previous;
+      self.time = start;
+      self.close(notes, length);
+    }
+    self.broken = next;
+    index + count
+  }
+
+  /// `(p`, `(p:q` or `(p:q:r`, puts p notes in time of q for next r notes
+  fn tuplet_start(&mut self, chars : &[char], index : usize) -> usize {
+    let (p, mut index) = number(chars, index);
+    let p = p.unwrap_or(3);
+    let mut q = None;
+    let mut r = None;
+    if chars.get(index) == Some(&':') {
+      (q, index) = number(chars, index + 1);
+      if chars.get(index) == Some(&':') {
+        (r, index) = number(chars, index + 1);
+      }
+    }
+    let compound = self.tune.time_signatures.last().is_some_and(|(_, (numerator, _))| numerator % 3 == 0 && *numerator > 3);
+    let q = q.unwrap_or(match p { 2 | 4 | 8 => 3, 3 | 6 => 2, _ if compound => 3, _ => 2 });
+    self.tuplet = Some((q as FloatWord / p.max(1) as FloatWord, r.unwrap_or(p) as usize));
+    index
+  }
+
+  /// Parses bar line, with repeats `|:`, `:|`, `::` and endings `|1`, `:|2`.
+  fn bar(&mut self, chars : &[char], mut index : usize) -> usize {
+    let start = index;
+    if chars[index] == '[' { index += 1; }
+    while chars.get(index).is_some_and(|char| matches!(char, '|' | ':')) {
+      index += 1;
+    }
+    if chars.get(index) == Some(&']') && chars[index - 1] == '|' { index += 1; }
+    let bar : String = chars[start..index].iter().collect();
+    let bar = bar.trim_matches(|char| char == '[' || char == ']');
+
+    self.accidentals.clear();
+    let end_repeat = bar.starts_with(':');
+    let start_repeat = bar.ends_with(':') && bar.len() > 1;
+    if end_repeat { self.end_repeat(); }
+    if start_repeat {
+      self.repeat_start = (self.tune.notes.len(), self.time);
+      self.first_ending = None;
+    }
+
+    match chars.get(index) {
+      Some(digit) if digit.is_ascii_digit() => self.ending(chars, index),
+      _ => index,
+    }
+  }
+
+  /// ending number at `index`, like `1` or `1,3`, first ending is skipped when section is repeated
+  fn ending(&mut self, chars : &[char], mut index : usize) -> usize {
+    if chars[index] == '1' {
+      self.first_ending = Some(self.time);
+    }
+    while chars.get(index).is_some_and(|char| char.is_ascii_digit() || matches!(char, ',' | '-')) {
+      index += 1;
+    }
+    index
+  }
+
+  /// Plays repeated section again, without its first ending.
+  fn end_repeat(&mut self) {
+    let (first, start) = self.repeat_start;
+    let end = self.first_ending.take().unwrap_or(self.time);
+    let offset = self.time - start;
+    let repeated : Vec<(FloatWord, FloatWord, Word)> = self.tune.notes[first.min(self.tune.notes.len())..]
+      .iter()
+      .filter(|note| note.0 < end - 1e-4)
+      .map(|note| (note.0 + offset, note.1.min(end) + offset, note.2))
+      .collect();
+    self.tune.notes.extend(repeated);
+    self.time += end - start;
+    self.ties.clear();
+    self.last = None;
+    self.repeat_start = (self.tune.notes.len(), self.time);
+  }
+
+  /// Length after note, e.g. `2`, `/2`, `3/2`, `//`, in unit note lengths, and index after it.
+  fn length(&self, chars : &[char], index : usize) -> (FloatWord, usize) {
+    let (numerator, mut index) = number(chars, index);
+    let mut length = numerator.unwrap_or(1) as FloatWord;
+    while chars.get(index) == Some(&'/') {
+      let (denominator, end) = number(chars, index + 1);
+      length /= denominator.unwrap_or(2).max(1) as FloatWord;
+      index = end;
+    }
+    (length, index)
+  }
+}
+
+/// digits at `index` as number, and index after them
+fn number(chars : &[char], index : usize) -> (Option<u32>, usize) {
+  let digits : String = chars[index.min(chars.len())..].iter().take_while(|char| char.is_ascii_digit()).collect();
+  (digits.parse().ok(), index + digits.len())
+}
+
+/// index after the next `close` following `index`
+fn skip_to(chars : &[char], index : usize, close : char) -> Result<usize, String> {
+  chars[index + 1..]
+    .iter()
+    .position(|char| *char == close)
+    .map(|position| index + position + 2)
+    .ok_or_else(|| format!("'{}' is not closed with '{}'", chars[index], close))
+}
+
+/// sets `value` at `time`, replacing value set at the same time
+fn set<T>(values : &mut Vec<(FloatWord, T)>, time : FloatWord, value : T) {
+  values.retain(|(at, _)| (at - time).abs() > 1e-4);
+  values.push((time, value));
+}
+
+/// fraction like `1/8`, as number
+fn fraction(value : &str) -> Option<FloatWord> {
+  let (numerator, denominator) = value.trim().split_once('/').unwrap_or((value.trim(), "1"));
+  let (numerator, denominator) : (FloatWord, FloatWord) = (numerator.trim().parse().ok()?, denominator.trim().parse().ok()?);
+  (denominator > 0.0).then_some(numerator / denominator)
+}
+
+/// unit note length of `L` field, in quarter notes
+fn unit_length(value : &str) -> Result<FloatWord, String> {
+  fraction(value)
+    .filter(|length| *length > 0.0)
+    .map(|length| length * 4.0)
+    .ok_or_else(|| format!("expected unit note length like 1/8, found '{}'", value))
+}
+
+/// Time signature of `M` field, `C` is 4/4, `C|` is 2/2 and `2+3/8` is 5/8, `None` for free meter.
+fn meter(value : &str) -> Result<Option<FractionWord>, String> {
+  let error = || format!("expected meter like 6/8, found '{}'", value);
+  match value.trim() {
+    "none" | "" => Ok(None),
+    "C" => Ok(Some((4, 4))),
+    "C|" => Ok(Some((2, 2))),
+    value => {
+      let (numerator, denominator) = value.split_once('/').ok_or_else(error)?;
+      let numerator = numerator.trim_matches(|char| char == '(' || char == ')')
+        .split('+')
+        .map(|part| part.trim().parse::<Word>())
+        .sum::<Result<Word, _>>()
+        .map_err(|_| error())?;
+      let denominator : Word = denominator.trim().parse().map_err(|_| error())?;
+      match numerator > 0 && denominator.is_power_of_two() {
+        true => Ok(Some((numerator, denominator))),
+        false => Err(error()),
+      }
+    }
+  }
+}
+
+/// Key signature of `K` field, e.g. `G`, `F#m`, `Bb`, `D mix`, `A dorian`, `none` is C major.
+/// Modes other than major and minor are named by the major key with the same signature.
+fn key(value : &str) -> Result<KeySignature, String> {
+  let mut chars = value.trim().chars().peekable();
+  let tonic = match chars.next() {
+    None => return Ok(KeySignature::new(0, false)),
+    Some(_) if value.trim().starts_with("none") || value.trim().starts_with("HP") || value.trim().starts_with("Hp") => {
+      return Ok(KeySignature::new(0, false));
+    },
+    Some(tonic) => tonic,
+  };
+  let mut fifths = TONICS.iter()
+    .find(|(letter, _)| *letter == tonic)
+    .map(|(_, fifths)| *fifths)
+    .ok_or_else(|| format!("expected key like G or F#m, found '{}'", value))?;
+  match chars.peek() {
+    Some('#') => { fifths += 7; chars.next(); },
+    Some('b') => { fifths -= 7; chars.next(); },
+    _ => {},
+  }
+
+  let rest : String = chars.collect();
+  let mode = rest.split_whitespace().next().filter(|word| !word.contains('=')).unwrap_or_default().to_lowercase();
+  let mode : String = mode.chars().take(3).collect();
+  let (shift, minor) = MODES.iter()
+    .find(|(name, _, _)| *name == mode)
+    .map(|(_, shift, minor)| (*shift, *minor))
+    .ok_or_else(|| format!("unknown mode '{}' in key '{}'", mode, value))?;
+
+  let fifths = fifths + shift;
+  if !(-7 ..= 7).contains(&fifths) {
+    return Err(format!("key '{}' has more than 7 sharps or flats", value));
+  }
+  Ok(KeySignature::new(fifths as i8, minor))
+}
+
+/// voice id of `V` field, without its properties
+fn voice(value : &str) -> String {
+  value.split_whitespace().next().unwrap_or_default().to_string()
+}
@@ -0,0 +1,181 @@
+//! ABC notation of folk tunes, imported to [`NoteSeq`] and [`Midi`], and exported from quantized monophonic NoteSeq.
+//!
+//! ```text
+//! X:1
+//! T:Speed the Plough
+//! M:4/4
+//! L:1/8
+//! Q:1/4=120
+//! K:G
+//! |:GABc dedB|dedB dedB|c2ec B2dB|1 A2F2 G4:|2 A2F2 G2z2|]
+//! ```
+//!
+//! Import reads header fields `X`, `T`, `M`, `L`, `Q` and `K`, other fields are skipped.
+//! Body may have notes with accidentals (`^`, `^^`, `_`, `__`, `=`) and octave marks (`'`, `,`),
+//! durations (`2`, `/2`, `3/2`, `//`), rests (`z`, `x`), ties (`-`), broken rhythm (`>`, `<`), tuplets (`(3`),
+//! simple chords (`[CEG]`), bars, repeats (`|:`, `:|`, `::`) with first and second endings, and inline fields (`[K:D]`).
+//! Repeats are expanded, so notes are in the order they are played.
+//! Decorations, annotations, chord symbols and grace notes are skipped, multiple voices are refused.
+//!
+//! Export writes one note at a time, see [`to_abc`].
+
+use std::{fmt, error};
+
+use crate::{
+  primitive::{Word, FloatWord, FractionWord, m3byte},
+  model::{
+    note_seq::{NoteSeq, Note},
+    core::{
+      midi::Midi,
+      midi_builder::MidiBuilder,
+      midi_header::{MidiFormat, MidiDivision, DEFAULT_DIVISION},
+      midi_track::MidiTrack,
+      midi_event::{MidiEvent, MidiMessage, delta_time::DeltaTime, meta_message::{MetaMessage, TextEvent, Tempo, TimeSignature, KeySignature}}
+    }
+  }
+};
+
+pub use self::{import::{from_abc, from_abc_all}, export::to_abc};
+
+mod import;
+mod export;
+
+/// velocity of imported notes, ABC has no dynamics without decorations
+const VELOCITY : Word = 80;
+
+/// Error in ABC tune, with 1 based line number where it was found.
+#[derive(Debug, Clone)]
+pub struct AbcParseError {
+  line : usize,
+  message : String,
+}
+
+impl AbcParseError {
+  pub fn new(line : usize, message : String) -> Self {
+    Self { line, message }
+  }
+
+  /// line number, starting at 1
+  pub fn line(&self) -> usize { self.line }
+
+  pub fn message(&self) -> &str { &self.message }
+}
+
+impl fmt::Display for AbcParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "line {}: {}", self.line, self.message)
+  }
+}
+
+impl error::Error for AbcParseError {}
+
+/// Tune imported from ABC, times are in quarter notes from start of tune.
+#[derive(Debug, Clone)]
+pub struct AbcTune {
+  reference : Word,
+  title : String,
+  /// as (start, end, pitch), sorted by start
+  notes : Vec<(FloatWord, FloatWord, Word)>,
+  /// in qbpm
+  tempos : Vec<(FloatWord, Word)>,
+  time_signatures : Vec<(FloatWord, FractionWord)>,
+  /// names in the form of [`KeySignature::name`], e.g. `g-major`
+  key_signatures : Vec<(FloatWord, String)>,
+}
+
+impl AbcTune {
+  /// reference number of `X` field
+  pub fn reference(&self) -> Word { self.reference }
+
+  /// first `T` field, empty if tune has no title
+  pub fn title(&self) -> &str { &self.title }
+
+  pub fn notes(&self) -> &[(FloatWord, FloatWord, Word)] { &self.notes }
+
+  pub fn tempos(&self) -> &[(FloatWord, Word)] { &self.tempos }
+
+  pub fn time_signatures(&self) -> &[(FloatWord, FractionWord)] { &self.time_signatures }
+
+  pub fn key_signatures(&self) -> &[(FloatWord, String)] { &self.key_signatures }
+
+  /// Note sequence of tune, quarter notes are converted to seconds following the tempos.
+  pub fn to_note_seq(&self) -> NoteSeq {
+    let mut note_seq = NoteSeq::default();
+    for (at, qbpm) in &self.tempos {
+      let time = note_seq.time_at(*at);
+      note_seq.set_tempo(time, *qbpm as FloatWord);
+    }
+    for (at, time_signature) in &self.time_signatures {
+      note_seq.set_time_signature(note_seq.time_at(*at), *time_signature);
+    }
+    for (at, key) in &self.key_signatures {
+      note_seq.set_key_signature(note_seq.time_at(*at), key.clone());
+    }
+    for (start, end, pitch) in &self.notes {
+      note_seq.add_note(Note::new(*pitch, VELOCITY, note_seq.time_at(*start), note_seq.time_at(*end)));
+    }
+    note_seq
+  }
+
+  /// Single track midi of tune, with title as track name and tempo, time and key signatures, notes are on channel 0.
+  pub fn to_midi(&self) -> Midi {
+    let ticks = |quarters : FloatWord| (quarters * DEFAULT_DIVISION as FloatWord).round().max(0.0) as u64;
+    let mut track = MidiTrack::default();
+    if !self.title.is_empty() {
+      track.insert_at_tick(0, MidiMessage::MetaMessage(MetaMessage::TrackName(TextEvent::new(&self.title))));
+    }
+    for (at, qbpm) in &self.tempos {
+      let tempo = Tempo::new(m3byte!(60_000_000 / (*qbpm).max(1)));
+      track.insert_at_tick(ticks(*at), MidiMessage::MetaMessage(MetaMessage::Tempo(tempo)));
+    }
+    for (at, (numerator, denominator)) in &self.time_signatures {
+      if let Some(time_signature) = TimeSignature::new(*numerator as u8, *denominator as u8, 24, 8) {
+        track.insert_at_tick(ticks(*at), MidiMessage::MetaMessage(MetaMessage::TimeSignature(time_signature)));
+      }
+    }
+    for (at, key) in &self.key_signatures {
+      if let Some(key_signature) = KeySignature::from_name(key) {
+        track.insert_at_tick(ticks(*at), MidiMessage::MetaMessage(MetaMessage::KeySignature(key_signature)));
+      }
+    }
+
+    // note offs go before note ons at the same tick, so repeated pitches are not cut
+    let mut notes : Vec<(u64, bool, Word)> = self.notes
+      .iter()
+      .flat_map(|(start, end, pitch)| [(ticks(*start), true, *pitch), (ticks(*end), false, *pitch)])
+      .collect();
+    notes.sort();
+    for (tick, on, pitch) in notes {
+      let status = if on { 0x90 } else { 0x80 };
+      let velocity = if on { VELOCITY as u8 } else { 0 };
+      track.insert_at_tick(tick, MidiMessage::from((status, &[pitch as u8, velocity][..])));
+    }
+    let end = track.end_tick();
+    let last = self.notes.iter().map(|note| ticks(note.1)).max().unwrap_or(0);
+    track.add_event(MidiEvent::new(DeltaTime::from((last.max(end) - end) as u32), MidiMessage::MetaMessage(MetaMessage::EndOfTrack)));
+
+    MidiBuilder::new(MidiFormat::SingleTracksMultiChannel, MidiDivision::MetricTime(DEFAULT_DIVISION))
+      .track(track)
+      .build()
+      .expect("tune has single track ending with End Of Track")
+  }
+}
+
+impl From<&AbcTune> for NoteSeq {
+  fn from(tune : &AbcTune) -> Self {
+    tune.to_note_seq()
+  }
+}
+
+impl From<&AbcTune> for Midi {
+  fn from(tune : &AbcTune) -> Self {
+    tune.to_midi()
+  }
+}
+
+impl NoteSeq {
+  /// ABC notation of quantized monophonic note sequence, see [`abc::to_abc`](crate::abc::to_abc)
+  pub fn to_abc(&self) -> String {
+    to_abc(self)
+  }
+}
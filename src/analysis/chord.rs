@@ -0,0 +1,152 @@
+use crate::{
+  primitive::{Word, FloatWord},
+  model::note_seq::{NoteSeq, Node}
+};
+
+use super::{Resolution, segments, pitch_class_profile, PITCH_CLASS_NAMES};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChordQuality {
+  Major,
+  Minor,
+  Diminished,
+  Augmented,
+  Dominant7,
+  Major7,
+  Minor7,
+  HalfDiminished7,
+  Diminished7,
+  Major6,
+  Minor6,
+}
+
+impl ChordQuality {
+  pub const ALL : [ChordQuality; 11] = [
+    ChordQuality::Major, ChordQuality::Minor, ChordQuality::Diminished, ChordQuality::Augmented,
+    ChordQuality::Dominant7, ChordQuality::Major7, ChordQuality::Minor7, ChordQuality::HalfDiminished7, ChordQuality::Diminished7,
+    ChordQuality::Major6, ChordQuality::Minor6
+  ];
+
+  /// Intervals of chord tones from root, in semitones.
+  pub fn intervals(&self) -> &'static [Word] {
+    match self {
+      ChordQuality::Major           => &[0, 4, 7],
+      ChordQuality::Minor           => &[0, 3, 7],
+      ChordQuality::Diminished      => &[0, 3, 6],
+      ChordQuality::Augmented       => &[0, 4, 8],
+      ChordQuality::Dominant7       => &[0, 4, 7, 10],
+      ChordQuality::Major7          => &[0, 4, 7, 11],
+      ChordQuality::Minor7          => &[0, 3, 7, 10],
+      ChordQuality::HalfDiminished7 => &[0, 3, 6, 10],
+      ChordQuality::Diminished7     => &[0, 3, 6, 9],
+      ChordQuality::Major6          => &[0, 4, 7, 9],
+      ChordQuality::Minor6          => &[0, 3, 7, 9],
+    }
+  }
+
+  pub fn suffix(&self) -> &'static str {
+    match self {
+      ChordQuality::Major           => "",
+      ChordQuality::Minor           => "m",
+      ChordQuality::Diminished      => "dim",
+      ChordQuality::Augmented       => "aug",
+      ChordQuality::Dominant7       => "7",
+      ChordQuality::Major7          => "maj7",
+      ChordQuality::Minor7          => "m7",
+      ChordQuality::HalfDiminished7 => "m7b5",
+      ChordQuality::Diminished7     => "dim7",
+      ChordQuality::Major6          => "6",
+      ChordQuality::Minor6          => "m6",
+    }
+  }
+}
+
+/// Chord sounding in segment, root and bass are stored as pitch class (0 = C, 11 = B).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chord {
+  root : Word,
+  quality : ChordQuality,
+  bass : Word,
+}
+
+impl Chord {
+  pub fn new(root : Word, quality : ChordQuality, bass : Word) -> Self {
+    Self { root : root % 12, quality, bass : bass % 12 }
+  }
+
+  pub fn root(&self) -> Word { self.root }
+
+  pub fn quality(&self) -> ChordQuality { self.quality }
+
+  pub fn bass(&self) -> Word { self.bass }
+
+  /// Pitch classes of chord tones, starting from root.
+  pub fn pitch_classes(&self) -> Vec<Word> {
+    self.quality.intervals().iter().map(|interval| (self.root + interval) % 12).collect()
+  }
+
+  /// 0 for root position, 1 for first inversion, 2 for second and 3 for third inversion.
+  /// Bass outside of chord tones is treated as root position.
+  pub fn inversion(&self) -> Word {
+    self.pitch_classes().iter().position(|pitch_class| *pitch_class == self.bass).unwrap_or(0) as Word
+  }
+
+  /// Chord symbol, e.g. `C`, `Am7`, `G7/B`
+  pub fn name(&self) -> String {
+    let name = format!("{}{}", PITCH_CLASS_NAMES[self.root as usize], self.quality.suffix());
+    match self.inversion() {
+      0 => name,
+      _ => format!("{}/{}", name, PITCH_CLASS_NAMES[self.bass as usize]),
+    }
+  }
+}
+
+/// Estimates chord from pitch class profile by matching chord templates, and bass pitch (or pitch class).
+/// 
+/// Each template is scored by weight of chord tones present minus weight of non chord tones, 
+/// every missing chord tone is penalized. Returns None if less than 2 chord tones are sounding.
+pub fn estimate_chord_from_profile(profile : &[FloatWord; 12], bass : Word) -> Option<Chord> {
+  let total : FloatWord = profile.iter().sum();
+  if total == 0.0 { return None; }
+
+  let mut best : Option<(FloatWord, Chord)> = None;
+  for root in 0..12 {
+    for quality in ChordQuality::ALL {
+      let tones = quality.intervals().iter().map(|interval| ((root + interval) % 12) as usize).collect::<Vec<_>>();
+      let present = tones.iter().filter(|tone| profile[**tone] > 0.0).count();
+      if present < 2 { continue; }
+
+      let matched : FloatWord = tones.iter().map(|tone| profile[*tone]).sum();
+      let missing = (tones.len() - present) as FloatWord;
+      // root in bass is preferred, so inversions are only picked when needed
+      let root_bonus = if root == bass % 12 { 0.1 * total } else { 0.0 };
+      let score = 2.0 * matched - total - missing * total / tones.len() as FloatWord + root_bonus;
+
+      if !matches!(best, Some((best_score, _)) if score <= best_score) {
+        best = Some((score, Chord::new(root, quality, bass)));
+      }
+    }
+  }
+  best.map(|(_, chord)| chord)
+}
+
+/// Estimates chord sounding in every segment of `resolution`, segments without chord are skipped.
+/// Consecutive segments with same chord are merged in single node.
+pub fn estimate_chords(note_seq : &NoteSeq, resolution : Resolution) -> Vec<Node<Chord>> {
+  let mut chords : Vec<Node<Chord>> = Vec::new();
+
+  for (start, end) in segments(note_seq, resolution) {
+    let bass = note_seq.notes()
+      .iter()
+      .filter(|note| note.start_time() < end && note.end_time() > start)
+      .map(|note| note.pitch())
+      .min();
+    let Some(bass) = bass else { continue };
+
+    let Some(chord) = estimate_chord_from_profile(&pitch_class_profile(note_seq.notes(), start, end), bass) else { continue };
+    if !matches!(chords.last(), Some(last) if **last == chord) {
+      chords.push(Node::new(start, chord));
+    }
+  }
+  chords
+}
@@ -0,0 +1,177 @@
+use crate::{
+  primitive::{Word, FloatWord},
+  model::note_seq::{NoteSeq, TrackSeq, Node}
+};
+
+use super::{Resolution, segments, pitch_class_profile, PITCH_CLASS_NAMES};
+
+/// Krumhansl-Kessler major key profile, starting from tonic.
+const MAJOR_PROFILE : [FloatWord; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+
+/// Krumhansl-Kessler minor key profile, starting from tonic.
+const MINOR_PROFILE : [FloatWord; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+  Major,
+  Minor
+}
+
+/// Musical key, tonic is stored as pitch class (0 = C, 11 = B).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+  tonic : Word,
+  mode : Mode,
+}
+
+impl Key {
+  pub fn new(tonic : Word, mode : Mode) -> Self {
+    Self { tonic : tonic % 12, mode }
+  }
+
+  pub fn tonic(&self) -> Word { self.tonic }
+
+  pub fn mode(&self) -> Mode { self.mode }
+
+  /// Relative major of minor key, or relative minor of major key.
+  pub fn relative(&self) -> Key {
+    match self.mode {
+      Mode::Major => Key::new(self.tonic + 9, Mode::Minor),
+      Mode::Minor => Key::new(self.tonic + 3, Mode::Major),
+    }
+  }
+
+  /// Name of key in the format used by key signatures of [`NoteSeq`], e.g. `c-major`, `f#-minor`
+  pub fn name(&self) -> String {
+    let mode = match self.mode { Mode::Major => "major", Mode::Minor => "minor" };
+    format!("{}-{}", PITCH_CLASS_NAMES[self.tonic as usize].to_lowercase(), mode)
+  }
+
+  /// Parses key name in the format used by key signatures of [`NoteSeq`], e.g. `c-major`, `f#-minor`, `bb-major`
+  pub fn from_name(name : &str) -> Option<Key> {
+    let (tonic, mode) = name.split_once('-')?;
+    let mode = match mode { "major" => Mode::Major, "minor" => Mode::Minor, _ => return None };
+
+    let mut chars = tonic.chars();
+    let step = match chars.next()?.to_ascii_lowercase() {
+      'c' => 0, 'd' => 2, 'e' => 4, 'f' => 5, 'g' => 7, 'a' => 9, 'b' => 11,
+      _ => return None
+    };
+    let alter : i32 = chars.map(|c| match c { '#' => 1, 'b' => -1, _ => 0 }).sum();
+    Some(Key::new((step + alter).rem_euclid(12) as Word, mode))
+  }
+}
+
+/// Key estimated by Krumhansl-Schmuckler algorithm, with correlation of pitch class profile and key profile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyEstimate {
+  pub key : Key,
+  pub correlation : FloatWord,
+}
+
+/// Key signature declared in MIDI which doesn't match key estimated from notes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyMismatch {
+  pub declared : Key,
+  pub estimated : KeyEstimate,
+  /// true if estimated key is relative key of declared, i.e. both share the key signature.
+  pub relative : bool,
+}
+
+fn correlation(x : &[FloatWord; 12], y : &[FloatWord; 12]) -> FloatWord {
+  let mean_x = x.iter().sum::<FloatWord>() / 12.0;
+  let mean_y = y.iter().sum::<FloatWord>() / 12.0;
+  let (mut xy, mut xx, mut yy) = (0.0, 0.0, 0.0);
+  for i in 0..12 {
+    xy += (x[i] - mean_x) * (y[i] - mean_y);
+    xx += (x[i] - mean_x).powi(2);
+    yy += (y[i] - mean_y).powi(2);
+  }
+  if xx == 0.0 || yy == 0.0 { 0.0 } else { xy / (xx * yy).sqrt() }
+}
+
+/// Estimates key of pitch class profile, returns None if profile is empty.
+pub fn estimate_key_from_profile(profile : &[FloatWord; 12]) -> Option<KeyEstimate> {
+  if profile.iter().all(|weight| *weight == 0.0) { return None; }
+
+  let mut best : Option<KeyEstimate> = None;
+  for tonic in 0..12 {
+    for (mode, key_profile) in [(Mode::Major, &MAJOR_PROFILE), (Mode::Minor, &MINOR_PROFILE)] {
+      let mut rotated = [0.0; 12];
+      for i in 0..12 {
+        rotated[(i + tonic) % 12] = key_profile[i];
+      }
+      let correlation = correlation(profile, &rotated);
+      if !matches!(best, Some(best) if correlation <= best.correlation) {
+        best = Some(KeyEstimate { key : Key::new(tonic as Word, mode), correlation });
+      }
+    }
+  }
+  best
+}
+
+/// Estimates global key of note sequence.
+pub fn estimate_key(note_seq : &NoteSeq) -> Option<KeyEstimate> {
+  estimate_key_from_profile(&pitch_class_profile(note_seq.notes(), 0.0, note_seq.total_time()))
+}
+
+/// Estimates local key for every window of `resolution`, windows without notes are skipped.
+/// Consecutive windows with same key are merged in single node.
+pub fn estimate_local_keys(note_seq : &NoteSeq, resolution : Resolution) -> Vec<Node<KeyEstimate>> {
+  let mut keys : Vec<Node<KeyEstimate>> = Vec::new();
+  for (start, end) in segments(note_seq, resolution) {
+    let Some(estimate) = estimate_key_from_profile(&pitch_class_profile(note_seq.notes(), start, end)) else { continue };
+    if !matches!(keys.last(), Some(last) if last.key == estimate.key) {
+      keys.push(Node::new(start, estimate));
+    }
+  }
+  keys
+}
+
+/// Compares key signatures declared in note sequence with key estimated from notes sounding until next key signature.
+/// Returns the mismatches placed at time of declared key signature, assumed C Major is not compared.
+pub fn key_mismatches(note_seq : &NoteSeq) -> Vec<Node<KeyMismatch>> {
+  mismatches(std::slice::from_ref(note_seq))
+}
+
+/// Same as [`key_mismatches`], with key signatures and notes of all tracks merged,
+/// e.g. format 1 midi declaring keys in conductor track only.
+pub fn key_mismatches_in_tracks(track_seq : &TrackSeq) -> Vec<Node<KeyMismatch>> {
+  mismatches(track_seq.tracks())
+}
+
+/// Key signatures are placed on every track by quater beat, since every track converts ticks to seconds with its own tempos.
+fn mismatches(tracks : &[NoteSeq]) -> Vec<Node<KeyMismatch>> {
+  // (beat, time, key) of declared key signatures, later track wins on same beat
+  let mut key_signatures : Vec<(FloatWord, FloatWord, &str)> = Vec::new();
+  for note_seq in tracks {
+    for key_signature in note_seq.declared_key_signatures() {
+      let beat = note_seq.beat_at(key_signature.at());
+      key_signatures.retain(|(at, _, _)| *at != beat);
+      key_signatures.push((beat, key_signature.at(), key_signature.element()));
+    }
+  }
+  key_signatures.sort_by(|a, b| a.0.total_cmp(&b.0));
+  let end_beat = tracks.iter().map(|note_seq| note_seq.beat_at(note_seq.total_time())).fold(0.0, FloatWord::max);
+
+  let mut mismatches = Vec::new();
+  for (i, (beat, at, name)) in key_signatures.iter().enumerate() {
+    let Some(declared) = Key::from_name(name) else { continue };
+    let end = key_signatures.get(i + 1).map_or(end_beat, |next| next.0);
+
+    let mut profile = [0.0; 12];
+    for note_seq in tracks {
+      let track_profile = pitch_class_profile(note_seq.notes(), note_seq.time_at(*beat), note_seq.time_at(end));
+      profile.iter_mut().zip(track_profile).for_each(|(weight, track_weight)| *weight += track_weight);
+    }
+    let Some(estimated) = estimate_key_from_profile(&profile) else { continue };
+    if estimated.key != declared {
+      mismatches.push(Node::new(*at, KeyMismatch { 
+        declared, 
+        estimated, 
+        relative : estimated.key == declared.relative() 
+      }));
+    }
+  }
+  mismatches
+}
@@ -0,0 +1,66 @@
+//! Harmonic analysis over [`NoteSeq`].
+//! 
+//! - [`chord`] : estimates the sounding chord per beat or bar.
+//! - [`key`] : estimates global and local key (Krumhansl-Schmuckler), 
+//!   and compares it with key signatures declared in MIDI.
+//! 
+//! Results are returned as timed [`Node`]s, same as key signatures and tempos of [`NoteSeq`].
+
+use crate::{
+  primitive::FloatWord,
+  model::note_seq::{NoteSeq, Node, Note}
+};
+
+pub mod chord;
+pub mod key;
+
+pub const PITCH_CLASS_NAMES : [&str; 12] = ["C", "C#", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B"];
+
+/// Length of the segments analysis runs on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+  /// quater beat, following tempo changes.
+  Beat,
+  /// bar, following tempo and time signature changes.
+  Bar,
+  /// fixed number of quater beats.
+  Beats(FloatWord),
+}
+
+/// Splits note sequence in segments of (start time, end time) in seconds, covering all notes.
+pub(crate) fn segments(note_seq : &NoteSeq, resolution : Resolution) -> Vec<(FloatWord, FloatWord)> {
+  let end_beat = note_seq.beat_at(note_seq.total_time());
+  let mut segments = Vec::new();
+  let mut beat = 0.0;
+
+  while beat < end_beat {
+    let length = match resolution {
+      Resolution::Beat => 1.0,
+      Resolution::Beats(beats) => beats.max(0.25),
+      Resolution::Bar => {
+        let time = note_seq.time_at(beat);
+        let (nn, dd) = note_seq.time_signatures()
+          .iter()
+          .rev()
+          .find(|time_signature| time_signature.at() <= time)
+          .map_or((4, 4), |time_signature| *time_signature.element());
+        (nn as FloatWord * 4.0 / dd.max(1) as FloatWord).max(0.25)
+      }
+    };
+    segments.push((note_seq.time_at(beat), note_seq.time_at(beat + length)));
+    beat += length;
+  }
+  segments
+}
+
+/// Pitch class profile of notes sounding between `start` and `end` seconds, weighted by sounding duration.
+pub(crate) fn pitch_class_profile(notes : &[Node<Note>], start : FloatWord, end : FloatWord) -> [FloatWord; 12] {
+  let mut profile = [0.0; 12];
+  for note in notes {
+    let overlap = note.end_time().min(end) - note.start_time().max(start);
+    if overlap > 0.0 {
+      profile[(note.pitch() % 12) as usize] += overlap;
+    }
+  }
+  profile
+}
@@ -4,7 +4,10 @@
 pub mod primitive;
 
 /// parser parses the byte sequence to core Midi struct
-pub(crate) mod parser;
+pub mod parser;
+
+/// writer writes the Midi back to Standard Midi File
+pub mod writer;
 
 /// hold utility functions
 pub(crate) mod utils;
@@ -15,6 +18,24 @@ pub mod reader;
 /// core MIDI models and some of its derivatives
 pub mod model;
 
+/// tokenize converts note sequences to token sequences for sequence models, and back
+pub mod tokenize;
+
+/// analysis estimates chords and keys of note sequences
+pub mod analysis;
+
+/// text writes Midi as human readable text, one line per event, and assembles it back
+pub mod text;
+
+/// abc imports folk tunes in ABC notation to note sequences and Midi, and exports note sequences back
+pub mod abc;
+
+/// playback plays Midi in real time to pluggable sinks, with seek, loop, speed and mute / solo
+pub mod playback;
+
+/// render renders Midi offline to WAV, with built-in synthesizer or SoundFont samples
+pub mod render;
+
 /// web module will expose rmidirs to web-assembly in js world.
 pub mod web;
 // pub mod ds;
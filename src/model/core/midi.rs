@@ -1,27 +1,87 @@
 use std::usize;
 
-use super::{midi_header::MidiHeader, midi_track::MidiTrack};
+use super::{midi_header::MidiHeader, midi_track::MidiTrack, unknown_chunk::UnknownChunk, query::{EventQuery, TimedEvent}, validation::{self, ValidationError}};
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Midi {
   header : MidiHeader,
   tracks : Vec<MidiTrack>,
-  total_tracks : usize,
+  unknown_chunks : Vec<UnknownChunk>,
 }
 
 impl Midi {
+  /// Sets the header, time division of tracks follows division of header.
+  /// `ntrk` is kept as given, use [`Midi::validate`] to check it against the tracks.
   pub fn add_header(&mut self, header: MidiHeader)  {
     self.header = header;
-    // self
+    if let Some(time_div) = self.header.division().metric_time() {
+      for track in self.tracks.iter_mut() {
+        track.time_div = time_div;
+      }
+    }
   }
 
   pub fn header(&self) -> &MidiHeader {
     &self.header
   }
 
-  pub fn add_track(&mut self, track: MidiTrack) {
+  /// Adds track after the last track, `ntrk` of header is updated and time division of track follows the header.
+  pub fn add_track(&mut self, mut track: MidiTrack) {
+    if let Some(time_div) = self.header.division().metric_time() {
+      track.time_div = time_div;
+    }
     self.tracks.push(track);
-    self.total_tracks += 1;
+    self.header.set_ntrk(self.tracks.len());
+  }
+
+  /// Removes track at index `n`, `ntrk` of header is updated.
+  /// Unknown chunks after the track keep their place among remaining tracks.
+  ///
+  /// # Panics
+  /// if `n` is out of bounds.
+  pub fn remove_track(&mut self, n : usize) -> MidiTrack {
+    let track = self.tracks.remove(n);
+    self.header.set_ntrk(self.tracks.len());
+    self.unknown_chunks = std::mem::take(&mut self.unknown_chunks)
+      .into_iter()
+      .map(|chunk| match chunk.position() {
+        position if position > n && position != usize::MAX => chunk.with_position(position - 1),
+        _ => chunk,
+      })
+      .collect();
+    track
+  }
+
+  /// Reorders tracks, `order[i]` is the current index of track placed at index `i`.
+  /// Unknown chunks stay in front of the track they preceded, chunks after the last track stay at the end.
+  ///
+  /// # Panics
+  /// if `order` is not a permutation of track indices.
+  pub fn reorder_tracks(&mut self, order : &[usize]) {
+    let mut seen = vec![false; self.tracks.len()];
+    assert!(
+      order.len() == self.tracks.len() && order.iter().all(|&n| n < seen.len() && !std::mem::replace(&mut seen[n], true)),
+      "order {:?} is not a permutation of {} track indices", order, self.tracks.len()
+    );
+
+    let mut tracks : Vec<Option<MidiTrack>> = std::mem::take(&mut self.tracks).into_iter().map(Some).collect();
+    self.tracks = order.iter().map(|&n| tracks[n].take().unwrap()).collect();
+
+    // new index of every current track
+    let mut moved_to = vec![0; order.len()];
+    for (index, &n) in order.iter().enumerate() {
+      moved_to[n] = index;
+    }
+    self.unknown_chunks = std::mem::take(&mut self.unknown_chunks)
+      .into_iter()
+      .map(|chunk| match chunk.position() {
+        position if position < moved_to.len() => chunk.with_position(moved_to[position]),
+        _ => chunk,
+      })
+      .collect();
+    // writer expects chunks in order of position
+    self.unknown_chunks.sort_by_key(|chunk| chunk.position());
   }
 
   pub fn track(&self, n : usize) -> &MidiTrack {
@@ -29,4 +89,37 @@ impl Midi {
   }
 
   pub fn tracks(&self) -> &Vec<MidiTrack> {&self.tracks}
+
+  /// Query over events of all tracks, track after track, see [`EventQuery`]
+  pub fn events(&self) -> EventQuery<'_> {
+    EventQuery::from_tracks(&self.tracks)
+  }
+
+  /// Keeps only events matching `keep` in all tracks, see [`MidiTrack::retain`]
+  pub fn retain<F : FnMut(&TimedEvent) -> bool>(&mut self, mut keep : F) {
+    for (track, midi_track) in self.tracks.iter_mut().enumerate() {
+      midi_track.retain_in(track, &mut keep);
+    }
+  }
+
+  /// Removes events matching `remove` from all tracks, returns number of removed events.
+  pub fn remove_where<F : FnMut(&TimedEvent) -> bool>(&mut self, mut remove : F) -> usize {
+    let len : usize = self.tracks.iter().map(|midi_track| midi_track.events.len()).sum();
+    self.retain(|event| !remove(event));
+    len - self.tracks.iter().map(|midi_track| midi_track.events.len()).sum::<usize>()
+  }
+
+  /// Checks the midi against the rules of Standard Midi File, see [`validation::ViolationKind`].
+  /// Every violation found is reported, not just the first.
+  pub fn validate(&self) -> Result<(), ValidationError> {
+    validation::validate(self)
+  }
+
+  /// Adds chunk of unknown type, it is written back at its position among tracks.
+  pub fn add_unknown_chunk(&mut self, chunk: UnknownChunk) {
+    self.unknown_chunks.push(chunk);
+  }
+
+  /// chunks other than header and tracks, in file order
+  pub fn unknown_chunks(&self) -> &Vec<UnknownChunk> {&self.unknown_chunks}
 }
@@ -0,0 +1,65 @@
+use crate::primitive::m2byte;
+
+use super::{
+  midi::Midi,
+  midi_header::{MidiHeader, MidiFormat, MidiDivision, DEFAULT_DIVISION},
+  midi_track::MidiTrack,
+  unknown_chunk::UnknownChunk,
+  validation::ValidationError,
+};
+
+/// Builds Midi from tracks, `ntrk` of header is counted from the tracks.
+///
+/// ```no_run
+/// # use rmidirs::model::core::{midi_builder::MidiBuilder, midi_header::{MidiFormat, MidiDivision}, midi_track::MidiTrack};
+/// // track without End Of Track is refused
+/// let result = MidiBuilder::new(MidiFormat::MultiTracks, MidiDivision::MetricTime(960))
+///   .track(MidiTrack::default())
+///   .build();
+/// assert!(result.is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MidiBuilder {
+  format : MidiFormat,
+  division : MidiDivision,
+  tracks : Vec<MidiTrack>,
+  unknown_chunks : Vec<UnknownChunk>,
+}
+
+impl Default for MidiBuilder {
+  fn default() -> Self {
+    Self::new(MidiFormat::MultiTracks, MidiDivision::MetricTime(DEFAULT_DIVISION))
+  }
+}
+
+impl MidiBuilder {
+  pub fn new(format : MidiFormat, division : MidiDivision) -> Self {
+    Self { format, division, tracks : Vec::new(), unknown_chunks : Vec::new() }
+  }
+
+  /// adds track after the tracks added before
+  pub fn track(mut self, track : MidiTrack) -> Self {
+    self.tracks.push(track);
+    self
+  }
+
+  /// adds chunk of unknown type, see [`UnknownChunk::new`]
+  pub fn unknown_chunk(mut self, chunk : UnknownChunk) -> Self {
+    self.unknown_chunks.push(chunk);
+    self
+  }
+
+  /// Builds the Midi, fails if it breaks rules of Standard Midi File, see [`Midi::validate`].
+  pub fn build(self) -> Result<Midi, ValidationError> {
+    let mut midi = Midi::default();
+    midi.add_header(MidiHeader::new(self.format, m2byte!(self.tracks.len()), self.division));
+    for track in self.tracks {
+      midi.add_track(track);
+    }
+    for chunk in self.unknown_chunks {
+      midi.add_unknown_chunk(chunk);
+    }
+    midi.validate()?;
+    Ok(midi)
+  }
+}
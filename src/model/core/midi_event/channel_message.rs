@@ -1,5 +1,5 @@
 
-use std::{fs::File, default};
+use std::default;
 
 use serde_json;
 
@@ -14,10 +14,11 @@ use crate::primitive::{
 
 lazy_static::lazy_static!(
   #[derive(Debug)]
-  static ref CHANNEL_EVENT_SCHEMA : serde_json::Value = serde_json::de::from_reader(File::open("./midis/midi-channel-event-schema.json").expect("File should open read only")).unwrap();
+  static ref CHANNEL_EVENT_SCHEMA : serde_json::Value = serde_json::from_str(include_str!("../../../parser/schema/midi-v1-channel-event-schema.json")).unwrap();
 );
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NoteOn {
   pub(crate) channel : M4Bits,
   pub(crate) note : M1Byte,
@@ -26,11 +27,12 @@ pub struct NoteOn {
 
 impl From<NoteOn> for Vec<u8> {
   fn from(note_on: NoteOn) -> Self {
-    vec![0x90 & u8::from(note_on.channel), note_on.note.into(), note_on.velocity.into()]
+    vec![0x90 | u8::from(note_on.channel), note_on.note.into(), note_on.velocity.into()]
   }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NoteOff {
   pub(crate) channel : M4Bits,
   pub(crate) note : M1Byte,
@@ -39,11 +41,12 @@ pub struct NoteOff {
 
 impl From<NoteOff> for Vec<u8> {
   fn from(note_off: NoteOff) -> Self {
-    vec![0x80 & u8::from(note_off.channel), note_off.note.into(), note_off.velocity.into()]
+    vec![0x80 | u8::from(note_off.channel), note_off.note.into(), note_off.velocity.into()]
   }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AfterTouch {
   channel : M4Bits,
   note : M1Byte,
@@ -52,11 +55,12 @@ pub struct AfterTouch {
 
 impl From<AfterTouch> for Vec<u8> {
   fn from(after_touch: AfterTouch) -> Self {
-    vec![0xA0 & u8::from(after_touch.channel), after_touch.note.into(), after_touch.amount.into()]
+    vec![0xA0 | u8::from(after_touch.channel), after_touch.note.into(), after_touch.amount.into()]
   }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Controller {
   channel : M4Bits,
   controller_type : M1Byte,
@@ -65,12 +69,13 @@ pub struct Controller {
 
 impl From<Controller> for Vec<u8> {
   fn from(controller: Controller) -> Self {
-    vec![0xB0 & u8::from(controller.channel), controller.controller_type.into(), controller.value.into()]
+    vec![0xB0 | u8::from(controller.channel), controller.controller_type.into(), controller.value.into()]
   }
 }
 
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProgramChange {
   channel : M4Bits,
   program_number : M1Byte
@@ -78,11 +83,12 @@ pub struct ProgramChange {
 
 impl From<ProgramChange> for Vec<u8> {
   fn from(program_change: ProgramChange) -> Self {
-    vec![0xC0 & u8::from(program_change.channel), program_change.program_number.into()]
+    vec![0xC0 | u8::from(program_change.channel), program_change.program_number.into()]
   }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChannelAfterTouch {
   channel : M4Bits,
   amount : M1Byte
@@ -90,12 +96,13 @@ pub struct ChannelAfterTouch {
 
 impl From<ChannelAfterTouch> for Vec<u8> {
   fn from(channel_after_touch: ChannelAfterTouch) -> Self {
-    vec![0xD0 & u8::from(channel_after_touch.channel), channel_after_touch.amount.into()]
+    vec![0xD0 | u8::from(channel_after_touch.channel), channel_after_touch.amount.into()]
   }
 }
 
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PitchBend {
   channel : M4Bits,
   vlsb : M1Byte,
@@ -104,12 +111,13 @@ pub struct PitchBend {
 
 impl From<PitchBend> for Vec<u8> {
   fn from(pitch_bend: PitchBend) -> Self {
-    vec![0xE0 & u8::from(pitch_bend.channel), pitch_bend.vlsb.into(), pitch_bend.vmsb.into()]
+    vec![0xE0 | u8::from(pitch_bend.channel), pitch_bend.vlsb.into(), pitch_bend.vmsb.into()]
   }
 }
 
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum ChannelMessage {
   NoteOn(NoteOn) = 0x9,
@@ -184,6 +192,29 @@ impl ChannelMessage {
     } 
   }
 
+  pub fn get_velocity(&self) -> Option<M1Byte> {
+    match self {
+      ChannelMessage::NoteOn(event) => Some(event.velocity),
+      ChannelMessage::NoteOff(event) => Some(event.velocity),
+      _=> None
+    } 
+  }
+
+  /// (controller number, value) of controller message
+  pub fn get_controller(&self) -> Option<(M1Byte, M1Byte)> {
+    match self {
+      ChannelMessage::Controller(event) => Some((event.controller_type, event.value)),
+      _=> None
+    } 
+  }
+
+  pub fn get_program(&self) -> Option<M1Byte> {
+    match self {
+      ChannelMessage::ProgramChange(event) => Some(event.program_number),
+      _=> None
+    } 
+  }
+
   pub fn is_channel_event(byte : u8) -> bool {
     return byte & 0xF0 >= 0x80 && byte & 0xF0 < 0xF0;
   }
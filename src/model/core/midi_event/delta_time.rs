@@ -8,11 +8,16 @@ use super::meta_message::Tempo;
 /// It is not stored as seconds, it unit complete depends on Midi Header,
 /// Metric Version / SMPTE resolution byte in Midi Header.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct DeltaTime(MXByte);
 
 impl  DeltaTime {
   pub fn len(&self) -> usize {self.0.len()}
 
+  /// number of ticks, unit of tick is defined by division in Midi Header
+  pub fn ticks(&self) -> u32 {*self.0}
+
   pub fn to_microseconds(&self, time_div : u32, tempo : f32) -> f32 {
     (((*self.0 as f32 * 16.0 / time_div as f32).ceil() / 2.0).floor() * tempo) / 8.0
   }
@@ -1,14 +1,83 @@
-use crate::primitive::{M3Byte, M1Byte, m1byte, m3byte, m1bit, M1Bit, Word, MNBits};
+use crate::primitive::{M3Byte, M2Byte, M1Byte, MXByte, m1byte, m2byte, m3byte, mxbyte, m1bit, M1Bit, Word, MNBits, FloatWord};
 
 use super::MidiMessage;
 
+/// Payload of text like meta events (text, copyright, track name, lyrics, ...)
+///
+/// SMF does not fix the text encoding, so raw bytes are kept as is.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextEvent(Vec<u8>);
+
+impl TextEvent {
+  pub fn new(text : &str) -> Self { TextEvent(text.as_bytes().to_vec()) }
+
+  pub fn bytes(&self) -> &[u8] { &self.0 }
+
+  /// text decoded as utf-8, invalid sequences are replaced
+  pub fn text(&self) -> String { String::from_utf8_lossy(&self.0).into_owned() }
+}
+
+impl From<&[u8]> for TextEvent {
+  fn from(buf: &[u8]) -> Self {
+    TextEvent(buf.to_vec())
+  }
+}
+
+impl From<TextEvent> for Vec<u8> {
+  fn from(text_event: TextEvent) -> Self {
+    text_event.0
+  }
+}
+
 #[derive(Debug, Clone)]
-pub struct TextEvent;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SequenceNumber(M2Byte);
+
+impl SequenceNumber {
+  pub fn new(number : u16) -> Self { SequenceNumber(m2byte!(number)) }
+  pub fn number(&self) -> Word { *self.0 }
+}
+
+impl From<SequenceNumber> for Vec<u8> {
+  fn from(sequence_number: SequenceNumber) -> Self {
+    sequence_number.0.into()
+  }
+}
 
 #[derive(Debug, Clone)]
-pub struct ChannelPrefix;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelPrefix(M1Byte);
 
+impl ChannelPrefix {
+  pub fn new(channel : u8) -> Self { ChannelPrefix(m1byte!(channel)) }
+  pub fn channel(&self) -> Word { *self.0 }
+}
+
+impl From<ChannelPrefix> for Vec<u8> {
+  fn from(channel_prefix: ChannelPrefix) -> Self {
+    channel_prefix.0.into()
+  }
+}
+
+/// Sequencer specific meta event, payload is opaque to rmidirs
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SequencerSpecific(Vec<u8>);
+
+impl SequencerSpecific {
+  pub fn new(data : Vec<u8>) -> Self { SequencerSpecific(data) }
+  pub fn data(&self) -> &[u8] { &self.0 }
+}
+
+impl From<SequencerSpecific> for Vec<u8> {
+  fn from(sequencer_specific: SequencerSpecific) -> Self {
+    sequencer_specific.0
+  }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MIDIPort(M1Byte);
 
 impl From<MIDIPort> for Vec<u8> {
@@ -18,6 +87,7 @@ impl From<MIDIPort> for Vec<u8> {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EndOfTrack;
 
 
@@ -27,6 +97,7 @@ pub struct EndOfTrack;
 ///
 /// rmidirs stores it in M3Byte, which is u32, but with 3 byte mask. 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tempo(M3Byte);
 
 impl Tempo {
@@ -44,6 +115,11 @@ impl Tempo {
   pub fn bpm(&self) -> u32 {
     ((60.0 * 1000_000.0) / *self.0 as f32 ).floor() as u32
   }
+
+  /// quater beats per minute, not rounded like [`Tempo::bpm`]
+  pub fn qbpm(&self) -> f32 {
+    60_000_000.0 / (*self.0).max(1) as f32
+  }
 }
 
 impl From<Tempo> for Vec<u8> {
@@ -58,10 +134,53 @@ impl Default for Tempo {
   }
 }
 
+/// SMPTE time at which the track starts
 #[derive(Debug, Clone)]
-pub struct SMPTEOffset;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SMPTEOffset {
+  hr : M1Byte,
+  mn : M1Byte,
+  se : M1Byte,
+  fr : M1Byte,
+  ff : M1Byte,
+}
+
+impl SMPTEOffset {
+  pub fn new(hours : u8, minutes : u8, seconds : u8, frames : u8, fractional_frames : u8) -> Self {
+    SMPTEOffset {
+      hr : m1byte!(hours),
+      mn : m1byte!(minutes),
+      se : m1byte!(seconds),
+      fr : m1byte!(frames),
+      ff : m1byte!(fractional_frames)
+    }
+  }
+
+  /// hours, the top bits of the byte (frame rate) are masked out
+  pub fn hours(&self) -> Word { *self.hr & 0x1F }
+  pub fn minutes(&self) -> Word { *self.mn }
+  pub fn seconds(&self) -> Word { *self.se }
+  pub fn frames(&self) -> Word { *self.fr }
+
+  /// 1/100th of a frame
+  pub fn fractional_frames(&self) -> Word { *self.ff }
+}
+
+impl From<&[u8]> for SMPTEOffset {
+  fn from(buf: &[u8]) -> Self {
+    assert!(buf.len() == 5, "smpte_offset must be 5 bytes long. But passed {} bytes instead.", buf.len());
+    SMPTEOffset::new(buf[0], buf[1], buf[2], buf[3], buf[4])
+  }
+}
+
+impl From<SMPTEOffset> for Vec<u8> {
+  fn from(smpte_offset: SMPTEOffset) -> Self {
+    vec![smpte_offset.hr.into(), smpte_offset.mn.into(), smpte_offset.se.into(), smpte_offset.fr.into(), smpte_offset.ff.into()]
+  }
+}
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeSignature {
   nn : M1Byte,
   dd : M1Byte,
@@ -69,6 +188,40 @@ pub struct TimeSignature {
   bb : M1Byte,
 }
 
+impl TimeSignature {
+  /// Returns `None` when `denominator` is not power of two, as it can't be stored.
+  pub fn new(numerator : u8, denominator : u8, clocks_per_click : u8, notated_32nds : u8) -> Option<Self> {
+    if !denominator.is_power_of_two() { return None; }
+    Some(TimeSignature {
+      nn : m1byte!(numerator),
+      dd : m1byte!(denominator.trailing_zeros()),
+      cc : m1byte!(clocks_per_click),
+      bb : m1byte!(notated_32nds)
+    })
+  }
+
+  /// number of beats in bar
+  pub fn numerator(&self) -> Word { *self.nn }
+
+  /// note value of single beat, stored as negative power of two i.e. 2 stands for quarter note, 3 for eighth note.
+  pub fn denominator(&self) -> Word { 1 << (*self.dd).min(31) }
+
+  /// number of MIDI clocks in a metronome click
+  pub fn clocks_per_click(&self) -> Word { *self.cc }
+
+  /// number of notated 32nd notes in a MIDI quarter note (24 MIDI clocks)
+  pub fn notated_32nds(&self) -> Word { *self.bb }
+
+  /// Length of single beat in quarter notes
+  pub fn beat_length(&self) -> FloatWord { 4.0 / self.denominator() as FloatWord }
+}
+
+impl Default for TimeSignature {
+  fn default() -> Self {
+    Self::new(4, 4, 24, 8).expect("4 is power of two")
+  }
+}
+
 impl From<&[u8]> for TimeSignature {
   fn from(buf: &[u8]) -> Self {
     assert!(buf.len() == 4, "time_signature must be 4 bytes long. But passed '{:X}' instead.", buf[1]);
@@ -95,6 +248,7 @@ impl From<TimeSignature> for Vec<u8> {
 /// &nbsp;&nbsp;&nbsp; : sf < 0 no of flat keys if sf is negative
 /// - mi : minor(1) or major (0)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeySignature {
   sf : M1Byte,
   mi : M1Byte
@@ -109,6 +263,67 @@ impl From<&[u8]> for KeySignature {
   }
 }
 
+/// tonics of major keys, from 7 flats to 7 sharps
+const MAJOR_KEYS : [&str; 15] = ["cb", "gb", "db", "ab", "eb", "bb", "f", "c", "g", "d", "a", "e", "b", "f#", "c#"];
+
+/// tonics of minor keys, from 7 flats to 7 sharps
+const MINOR_KEYS : [&str; 15] = ["ab", "eb", "bb", "f", "c", "g", "d", "a", "e", "b", "f#", "c#", "g#", "d#", "a#"];
+
+/// note letters in order of the line of fifths, starting at F
+const FIFTHS : [char; 7] = ['F', 'C', 'G', 'D', 'A', 'E', 'B'];
+
+impl KeySignature {
+  pub fn new(sf : i8, minor : bool) -> Self {
+    KeySignature { sf : m1byte!(sf as u8), mi : m1byte!(minor as u8) }
+  }
+
+  /// number of sharps (positive) or flats (negative)
+  pub fn sf(&self) -> i8 { u8::from(self.sf) as i8 }
+
+  pub fn is_minor(&self) -> bool { *self.mi == 1 }
+
+  /// Name of key, in lower case with `#` for sharp and `b` for flat, e.g. `c-major`, `f#-minor`, `bb-major`
+  pub fn name(&self) -> String {
+    let index = (self.sf().clamp(-7, 7) + 7) as usize;
+    match self.is_minor() {
+      true => format!("{}-minor", MINOR_KEYS[index]),
+      false => format!("{}-major", MAJOR_KEYS[index]),
+    }
+  }
+
+  /// Parses name returned by [`KeySignature::name`], e.g. `f#-minor`
+  pub fn from_name(name : &str) -> Option<Self> {
+    let (tonic, mode) = name.split_once('-')?;
+    let (keys, minor) = match mode { "major" => (MAJOR_KEYS, false), "minor" => (MINOR_KEYS, true), _ => return None };
+    let index = keys.iter().position(|key| key.eq_ignore_ascii_case(tonic))?;
+    Some(Self::new(index as i8 - 7, minor))
+  }
+
+  /// Spells MIDI `pitch` as (step, alter, octave) following the key, e.g. 61 is (`C`, 1, 4) in D major and (`D`, -1, 4) in F major.
+  ///
+  /// Pitch is placed on the line of fifths in window of 12 fifths around the key,
+  /// so sharp keys prefer sharps and flat keys prefer flats for notes outside the key.
+  pub fn spell(&self, pitch : Word) -> (char, i32, i32) {
+    let fifths = self.sf() as i32;
+    let window = if fifths >= 0 { fifths - 3 } else { fifths - 4 };
+    let pitch_class = (pitch % 12) as i32;
+    let position = (window .. window + 12).find(|position| (position * 7).rem_euclid(12) == pitch_class).unwrap_or(0);
+
+    let step = FIFTHS[(position + 1).rem_euclid(7) as usize];
+    let alter = (position + 1).div_euclid(7);
+    (step, alter, (pitch as i32 - alter) / 12 - 1)
+  }
+
+  /// alteration the key signature applies to note letter `step`, 1 for sharp and -1 for flat
+  pub fn alter(&self, step : char) -> i32 {
+    let fifths = self.sf() as i32;
+    let Some(position) = FIFTHS.iter().position(|letter| letter.eq_ignore_ascii_case(&step)) else { return 0 };
+    if fifths > 0 && (position as i32) < fifths { 1 }
+    else if fifths < 0 && (position as i32) >= 7 + fifths { -1 }
+    else { 0 }
+  }
+}
+
 impl From<KeySignature> for Vec<u8> { 
   fn from(key_signature: KeySignature) -> Self {
     vec![key_signature.sf.into(), key_signature.mi.into()]
@@ -117,8 +332,10 @@ impl From<KeySignature> for Vec<u8> {
 
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum MetaMessage {
+  SequenceNumber(SequenceNumber) = 0x00,
   Text(TextEvent) = 0x01,
   CopyrightNotice(TextEvent) = 0x02,
   TrackName(TextEvent) = 0x03,
@@ -130,14 +347,75 @@ pub enum MetaMessage {
   MIDIPort(MIDIPort) = 0x21,
   EndOfTrack = 0x2F,
   Tempo(Tempo) = 0x51,
-  SMPTEOffset = 0x54,
+  SMPTEOffset(SMPTEOffset) = 0x54,
   TimeSignature(TimeSignature) = 0x58,
   KeySignature (KeySignature) = 0x59,
+  SequencerSpecific(SequencerSpecific) = 0x7F,
   Invalid(String)
 }
 
 impl MetaMessage {
 
+  /// meta event type byte (the one after `0xFF`), `None` for invalid messages
+  pub fn meta_type(&self) -> Option<u8> {
+    match self {
+      Self::SequenceNumber(_) => Some(0x00),
+      Self::Text(_) => Some(0x01),
+      Self::CopyrightNotice(_) => Some(0x02),
+      Self::TrackName(_) => Some(0x03),
+      Self::InstrumentName(_) => Some(0x04),
+      Self::Lyrics(_) => Some(0x05),
+      Self::Marker(_) => Some(0x06),
+      Self::CuePoint(_) => Some(0x07),
+      Self::ChannelPrefix(_) => Some(0x20),
+      Self::MIDIPort(_) => Some(0x21),
+      Self::EndOfTrack => Some(0x2F),
+      Self::Tempo(_) => Some(0x51),
+      Self::SMPTEOffset(_) => Some(0x54),
+      Self::TimeSignature(_) => Some(0x58),
+      Self::KeySignature(_) => Some(0x59),
+      Self::SequencerSpecific(_) => Some(0x7F),
+      Self::Invalid(_) => None,
+    }
+  }
+
+  /// payload of the meta event, without the `0xFF`, type and length prefix
+  pub fn data(&self) -> Vec<u8> {
+    match self.clone() {
+      Self::SequenceNumber(sequence_number) => sequence_number.into(),
+      Self::Text(text)
+      | Self::CopyrightNotice(text)
+      | Self::TrackName(text)
+      | Self::InstrumentName(text)
+      | Self::Lyrics(text)
+      | Self::Marker(text)
+      | Self::CuePoint(text) => text.into(),
+      Self::ChannelPrefix(channel_prefix) => channel_prefix.into(),
+      Self::MIDIPort(midi_port) => midi_port.into(),
+      Self::EndOfTrack => vec![],
+      Self::Tempo(tempo) => tempo.into(),
+      Self::SMPTEOffset(smpte_offset) => smpte_offset.into(),
+      Self::TimeSignature(time_signature) => time_signature.into(),
+      Self::KeySignature(key_signature) => key_signature.into(),
+      Self::SequencerSpecific(sequencer_specific) => sequencer_specific.into(),
+      Self::Invalid(_) => vec![],
+    }
+  }
+
+  /// text of text like meta events
+  pub fn get_text(&self) -> Option<&TextEvent> {
+    match self {
+      Self::Text(text)
+      | Self::CopyrightNotice(text)
+      | Self::TrackName(text)
+      | Self::InstrumentName(text)
+      | Self::Lyrics(text)
+      | Self::Marker(text)
+      | Self::CuePoint(text) => Some(text),
+      _ => None
+    }
+  }
+
   pub fn get_tempo(&self) -> Option<Tempo> {
     match self {
         MetaMessage::Tempo(tempo) => Some(tempo.clone()),
@@ -145,6 +423,20 @@ impl MetaMessage {
     }
   }
 
+  pub fn get_time_signature(&self) -> Option<TimeSignature> {
+    match self {
+        MetaMessage::TimeSignature(time_signature) => Some(time_signature.clone()),
+        _ => None,
+    }
+  }
+
+  pub fn get_key_signature(&self) -> Option<KeySignature> {
+    match self {
+        MetaMessage::KeySignature(key_signature) => Some(key_signature.clone()),
+        _ => None,
+    }
+  }
+
   pub fn is_tempo_event(&self) -> bool {
     match &self {
       Self::Tempo(_) => true,
@@ -173,13 +465,22 @@ impl From<(u8, u8, &[u8])> for MetaMessage {
   fn from((byte, subtype, rest): (u8, u8, &[u8])) -> Self {
     
     match subtype {
-      0x01..=0x07 => Self::Text(TextEvent), 
-      0x20 => Self::ChannelPrefix(ChannelPrefix),
+      0x00 if rest.len() == 2 => Self::SequenceNumber(SequenceNumber(rest.into())),
+      0x01 => Self::Text(rest.into()),
+      0x02 => Self::CopyrightNotice(rest.into()),
+      0x03 => Self::TrackName(rest.into()),
+      0x04 => Self::InstrumentName(rest.into()),
+      0x05 => Self::Lyrics(rest.into()),
+      0x06 => Self::Marker(rest.into()),
+      0x07 => Self::CuePoint(rest.into()),
+      0x20 if rest.len() == 1 => Self::ChannelPrefix(ChannelPrefix(rest[0].into())),
       0x2F => Self::EndOfTrack,
-      0x21 => Self::MIDIPort(MIDIPort(rest[0].into())),
-      0x51 => Self::get_tempo_from(rest),
-      0x58 => Self::get_time_signature_from(rest),
-      0x59 => Self::get_key_signature_from(rest),
+      0x21 if rest.len() == 1 => Self::MIDIPort(MIDIPort(rest[0].into())),
+      0x51 if rest.len() == 3 => Self::get_tempo_from(rest),
+      0x54 if rest.len() == 5 => Self::SMPTEOffset(rest.into()),
+      0x58 if rest.len() == 4 => Self::get_time_signature_from(rest),
+      0x59 if rest.len() == 2 => Self::get_key_signature_from(rest),
+      0x7F => Self::SequencerSpecific(SequencerSpecific(rest.to_vec())),
       
       _=> Self::Invalid(format!("From<&[u8]> trait not implemented for Meta-event sub-type with start byte 0x{subtype:X}"))
     }
@@ -198,24 +499,17 @@ impl From<&[u8]> for MetaMessage{
   }
 }
 
+/// Encodes the message as in SMF, i.e. `0xFF`, type, variable length and payload.
+///
+/// Invalid messages have no encoding and produce no bytes.
 impl From<MetaMessage> for Vec<u8> {
   fn from(meta_message: MetaMessage) -> Self {
-    match meta_message {
-      MetaMessage::Text(_) => todo!(),
-      MetaMessage::CopyrightNotice(_) => todo!(),
-      MetaMessage::TrackName(_) => todo!(),
-      MetaMessage::InstrumentName(_) => todo!(),
-      MetaMessage::Lyrics(_) => todo!(),
-      MetaMessage::Marker(_) => todo!(),
-      MetaMessage::CuePoint(_) => todo!(),
-      MetaMessage::ChannelPrefix(_) => todo!(),
-      MetaMessage::MIDIPort(midi_port) => midi_port.into(),
-      MetaMessage::EndOfTrack => vec![0x2F],
-      MetaMessage::Tempo(tempo) => tempo.into(),
-      MetaMessage::SMPTEOffset => todo!(),
-      MetaMessage::TimeSignature(time_signature) => time_signature.into(),
-      MetaMessage::KeySignature(key_signature) => key_signature.into(),
-      MetaMessage::Invalid(_) => todo!(),
-      }
-  }
-}
\ No newline at end of file
+    let Some(meta_type) = meta_message.meta_type() else { return vec![] };
+    let data = meta_message.data();
+
+    let mut bytes = vec![0xFF, meta_type];
+    bytes.extend(Vec::<u8>::from(mxbyte!(data.len())));
+    bytes.extend(data);
+    bytes
+  }
+}
@@ -2,21 +2,27 @@ use std::default;
 
 use crate::primitive::{MXByte, M1Byte};
 
-use self::{channel_message::ChannelMessage, meta_message::{MetaMessage, Tempo}, delta_time::DeltaTime, sys_event::SysEvent};
+use self::{channel_message::ChannelMessage, meta_message::{MetaMessage, Tempo, KeySignature, TimeSignature}, delta_time::DeltaTime, sys_event::SysEvent, system_message::{SystemCommon, SystemRealTime}};
 
 pub mod channel_message;
 pub mod meta_message;
 pub mod sys_event;
+pub mod system_message;
 
 pub mod delta_time;
 
 
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MidiMessage {
     ChannelMessage(ChannelMessage),
     MetaMessage(MetaMessage),
     SysMessage(SysEvent),
+    /// System Common message, stored in Standard Midi File escaped in `F7` packet
+    SystemCommon(SystemCommon),
+    /// System Real-Time message, stored in Standard Midi File escaped in `F7` packet
+    SystemRealTime(SystemRealTime),
     Invalid(String)
 }
 
@@ -26,18 +32,38 @@ pub enum MidiMessageType {
   Channel,
   Meta,
   Sys,
+  SystemCommon,
+  SystemRealTime,
   Invalid(String)
 }
 
 impl MidiMessage {
+  /// Type of event starting with status `byte` in Standard Midi File, where `FF` is meta event rather than System Reset.
   pub fn event_type<'a>(byte : u8) -> Option<MidiMessageType> {
     if byte & 0xF0 >= 0x80 && byte & 0xF0 < 0xF0 { return Some(MidiMessageType::Channel) };
     match byte {
        0xFF => Some(MidiMessageType::Meta),
        0xF0 | 0xF7  => Some(MidiMessageType::Sys),
+       0xF1 ..= 0xF6 => Some(MidiMessageType::SystemCommon),
+       0xF8 ..= 0xFE => Some(MidiMessageType::SystemRealTime),
        _ => None
     }
   }
+
+  /// Sysex event as message, `F7` packet holding single System Common or System Real-Time message is decoded to it.
+  pub fn from_sys_event(sys_event : SysEvent) -> Self {
+    if sys_event.event_byte() == 0xF7 {
+      if let Some(message) = SystemCommon::decode(sys_event.data()) {
+        return MidiMessage::SystemCommon(message);
+      }
+      if let [status] = sys_event.data() {
+        if let Some(message) = SystemRealTime::from_status(*status) {
+          return MidiMessage::SystemRealTime(message);
+        }
+      }
+    }
+    MidiMessage::SysMessage(sys_event)
+  }
 }
 
 impl From<(u8, &[u8])> for MidiMessage {
@@ -54,8 +80,11 @@ impl From<(u8, &[u8])> for MidiMessage {
         MidiMessage::MetaMessage(MetaMessage::from((byte, rest)))
       },
       MidiMessageType::Sys => { // sysex event
-        MidiMessage::SysMessage(SysEvent)
+        MidiMessage::from_sys_event(SysEvent::from((byte, rest)))
       }
+      MidiMessageType::SystemCommon | MidiMessageType::SystemRealTime => MidiMessage::Invalid(
+        format!("System message 0x{:0X} should be escaped in F7 packet in Standard Midi File", byte)
+      ),
       MidiMessageType::Invalid(msg) => MidiMessage::Invalid(msg),
     } 
   }
@@ -68,6 +97,8 @@ impl From<MidiMessage> for Vec<u8> {
         MidiMessage::ChannelMessage(channel_message) => channel_message.into(),
         MidiMessage::MetaMessage(meta_message) => meta_message.into(),
         MidiMessage::SysMessage(sys_event) => sys_event.into(),
+        MidiMessage::SystemCommon(message) => SysEvent::new(0xF7, message.into()).into(),
+        MidiMessage::SystemRealTime(message) => SysEvent::new(0xF7, message.into()).into(),
         MidiMessage::Invalid(_) => vec![],
     }
   }
@@ -75,6 +106,7 @@ impl From<MidiMessage> for Vec<u8> {
 
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MidiEvent {
   delta_time : DeltaTime,
   message : MidiMessage
@@ -89,7 +121,7 @@ impl MidiEvent {
   }
 
   pub fn is_channel_byte(byte : u8) -> bool {
-    byte >= 0x80 && byte < 0xFF
+    (0x80..0xF0).contains(&byte)
   }
 
   pub fn is_meta_byte(byte : u8) -> bool {
@@ -100,6 +132,15 @@ impl MidiEvent {
     byte == 0xF7 || byte ==  0xF0
   }
 
+  pub fn is_system_common_byte(byte : u8) -> bool {
+    (0xF1..=0xF6).contains(&byte)
+  }
+
+  /// `FF` is meta event in Standard Midi File, so only `F8` to `FE` are taken as System Real-Time
+  pub fn is_system_real_time_byte(byte : u8) -> bool {
+    (0xF8..=0xFE).contains(&byte)
+  }
+
   pub fn is_channel_event(&self) -> bool {
     match self.message {
       MidiMessage::ChannelMessage(_) => true,
@@ -139,6 +180,13 @@ impl MidiEvent {
     }
   }
 
+  pub fn is_end_of_track(&self) -> bool {
+    match &self.message {
+      MidiMessage::MetaMessage(MetaMessage::EndOfTrack) => true,
+      _ => false
+    }
+  }
+
   pub fn get_tempo(&self) -> Option<Tempo> {
     match &self.message {
       MidiMessage::MetaMessage(msg) => msg.get_tempo(),
@@ -146,6 +194,20 @@ impl MidiEvent {
     }
   } 
 
+  pub fn get_time_signature(&self) -> Option<TimeSignature> {
+    match &self.message {
+      MidiMessage::MetaMessage(msg) => msg.get_time_signature(),
+      _ => None,
+    }
+  }
+
+  pub fn get_key_signature(&self) -> Option<KeySignature> {
+    match &self.message {
+      MidiMessage::MetaMessage(msg) => msg.get_key_signature(),
+      _ => None,
+    }
+  }
+
   pub fn get_note_number(&self) -> Option<M1Byte> {
     match &self.message {
       MidiMessage::ChannelMessage(event) => event.get_note_number(),
@@ -153,11 +215,55 @@ impl MidiEvent {
     }
   }
 
+  pub fn get_velocity(&self) -> Option<M1Byte> {
+    match &self.message {
+      MidiMessage::ChannelMessage(event) => event.get_velocity(),
+      _ => None
+    }
+  }
+
+  /// (controller number, value) of controller events
+  pub fn get_controller(&self) -> Option<(M1Byte, M1Byte)> {
+    match &self.message {
+      MidiMessage::ChannelMessage(event) => event.get_controller(),
+      _ => None
+    }
+  }
+
+  pub fn get_program(&self) -> Option<M1Byte> {
+    match &self.message {
+      MidiMessage::ChannelMessage(event) => event.get_program(),
+      _ => None
+    }
+  }
+
+  /// channel of channel events
+  pub fn channel(&self) -> Option<u8> {
+    match &self.message {
+      MidiMessage::ChannelMessage(event) => event.event_channel(),
+      _ => None
+    }
+  }
+
+  /// type byte of meta events
+  pub fn meta_type(&self) -> Option<u8> {
+    match &self.message {
+      MidiMessage::MetaMessage(event) => event.meta_type(),
+      _ => None
+    }
+  }
+
+  pub fn set_delta_time(&mut self, delta_time : DeltaTime) {
+    self.delta_time = delta_time;
+  }
+
   pub fn event_byte(&self) -> Option<u8> {
     match &self.message {
         MidiMessage::ChannelMessage(event) => event.event_byte(),
         MidiMessage::MetaMessage(_) => Some(0xFF),
-        MidiMessage::SysMessage(_) => Some(0xF0),
+        MidiMessage::SysMessage(event) => Some(event.event_byte()),
+        // escaped in F7 packet
+        MidiMessage::SystemCommon(_) | MidiMessage::SystemRealTime(_) => Some(0xF7),
         MidiMessage::Invalid(_) => None
     }
   }
@@ -1,8 +1,39 @@
+use crate::primitive::{MXByte, mxbyte};
+
+/// System Exclusive event in MIDI.v1 
+/// 
+/// stored in Standard MIDI File as `F0 <length> <bytes to be transmitted after F0>`, 
+/// or as `F7 <length> <bytes to be transmitted>` for escaped / continued packets.
 #[derive(Debug, Clone)]
-pub struct SysEvent;
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SysEvent {
+  event_byte : u8,
+  data : Vec<u8>,
+}
+
+impl SysEvent {
+  pub fn new(event_byte : u8, data : Vec<u8>) -> Self {
+    SysEvent { event_byte, data }
+  }
+
+  /// 0xF0 for sysex message, 0xF7 for escaped / continued packet.
+  pub fn event_byte(&self) -> u8 { self.event_byte }
+
+  /// bytes following the length
+  pub fn data(&self) -> &[u8] { &self.data }
+}
+
+impl From<(u8, &[u8])> for SysEvent {
+  /// Creates SysEvent from event byte and rest of the buffer starting with variable length
+  fn from((byte, rest): (u8, &[u8])) -> Self {
+    let length = MXByte::from(rest);
+    SysEvent::new(byte, rest[length.len() .. length.len() + *length as usize].to_vec())
+  }
+}
 
 impl From<SysEvent> for Vec<u8> {
-  fn from(value: SysEvent) -> Self {
-    todo!()
+  fn from(sys_event: SysEvent) -> Self {
+    let length : Vec<u8> = mxbyte!(sys_event.data.len()).into();
+    [vec![sys_event.event_byte], length, sys_event.data].concat()
   }
-}
\ No newline at end of file
+}
@@ -0,0 +1,121 @@
+/// System Common message (`F1` to `F6`), for all devices of the system.
+///
+/// Encoded to and decoded from wire bytes, status followed by data bytes.
+/// In Standard Midi File these are escaped in `F7` packets, see [`MidiMessage`](super::MidiMessage).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SystemCommon {
+  /// MTC Quarter Frame (`F1`), `piece` 0 to 7 of timecode, each carrying 4 bit `value`
+  QuarterFrame { piece : u8, value : u8 },
+  /// Song Position Pointer (`F2`), in MIDI beats (sixteenth notes) since start of song, 14 bits
+  SongPositionPointer(u16),
+  /// Song Select (`F3`), song or sequence number
+  SongSelect(u8),
+  /// Tune Request (`F6`), analog synthesizers should tune their oscillators
+  TuneRequest,
+  /// undefined status `F4` or `F5`, without data
+  Undefined(u8),
+}
+
+impl SystemCommon {
+  pub fn status(&self) -> u8 {
+    match self {
+      Self::QuarterFrame { .. } => 0xF1,
+      Self::SongPositionPointer(_) => 0xF2,
+      Self::SongSelect(_) => 0xF3,
+      Self::TuneRequest => 0xF6,
+      Self::Undefined(status) => *status,
+    }
+  }
+
+  /// number of data bytes following `status`, None when status isn't System Common
+  pub fn data_len(status : u8) -> Option<usize> {
+    match status {
+      0xF1 | 0xF3 => Some(1),
+      0xF2 => Some(2),
+      0xF4 ..= 0xF6 => Some(0),
+      _ => None,
+    }
+  }
+
+  /// Decodes status and data bytes, None unless `bytes` are exactly single System Common message.
+  pub fn decode(bytes : &[u8]) -> Option<Self> {
+    let (&status, data) = bytes.split_first()?;
+    if Self::data_len(status)? != data.len() || data.iter().any(|byte| *byte > 0x7F) { return None; }
+
+    Some(match status {
+      0xF1 => Self::QuarterFrame { piece : data[0] >> 4, value : data[0] & 0x0F },
+      0xF2 => Self::SongPositionPointer(data[0] as u16 | (data[1] as u16) << 7),
+      0xF3 => Self::SongSelect(data[0]),
+      0xF6 => Self::TuneRequest,
+      status => Self::Undefined(status),
+    })
+  }
+}
+
+impl From<SystemCommon> for Vec<u8> {
+  fn from(message : SystemCommon) -> Self {
+    match message {
+      SystemCommon::QuarterFrame { piece, value } => vec![0xF1, (piece & 0x07) << 4 | value & 0x0F],
+      SystemCommon::SongPositionPointer(beats) => vec![0xF2, beats as u8 & 0x7F, (beats >> 7) as u8 & 0x7F],
+      SystemCommon::SongSelect(song) => vec![0xF3, song & 0x7F],
+      message => vec![message.status()],
+    }
+  }
+}
+
+/// System Real-Time message (`F8` to `FF`), single status byte for timing and transport.
+///
+/// On the wire these may come between bytes of any other message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SystemRealTime {
+  /// `F8`, sent 24 times per quarter note
+  TimingClock,
+  /// `FA`, start from beginning of song
+  Start,
+  /// `FB`, continue from current song position
+  Continue,
+  /// `FC`
+  Stop,
+  /// `FE`, once sent, receiver expects some byte at least every 300ms
+  ActiveSensing,
+  /// `FF`, reset all receivers to power up state; in Standard Midi File `FF` is meta event instead
+  SystemReset,
+  /// undefined status `F9` or `FD`
+  Undefined(u8),
+}
+
+impl SystemRealTime {
+  pub fn status(&self) -> u8 {
+    match self {
+      Self::TimingClock => 0xF8,
+      Self::Start => 0xFA,
+      Self::Continue => 0xFB,
+      Self::Stop => 0xFC,
+      Self::ActiveSensing => 0xFE,
+      Self::SystemReset => 0xFF,
+      Self::Undefined(status) => *status,
+    }
+  }
+
+  /// None when `status` isn't System Real-Time
+  pub fn from_status(status : u8) -> Option<Self> {
+    Some(match status {
+      0xF8 => Self::TimingClock,
+      0xFA => Self::Start,
+      0xFB => Self::Continue,
+      0xFC => Self::Stop,
+      0xFE => Self::ActiveSensing,
+      0xFF => Self::SystemReset,
+      0xF9 | 0xFD => Self::Undefined(status),
+      _ => return None,
+    })
+  }
+}
+
+impl From<SystemRealTime> for Vec<u8> {
+  fn from(message : SystemRealTime) -> Self {
+    vec![message.status()]
+  }
+}
@@ -2,6 +2,7 @@ use crate::{utils::{functions::{number, masked_number}, ByteEncodingFormat}, pri
 
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i16)]
 pub enum MidiFormat {
   SingleTracksMultiChannel = 0,
@@ -33,6 +34,7 @@ impl From<MidiFormat> for Vec<u8> {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MidiDivision {
   MetricTime(u16),
   SubDivision((i8, u8)),
@@ -52,14 +54,19 @@ impl MidiDivision {
 impl From<MidiDivision> for Vec<u8>{
   fn from(midi_division: MidiDivision) -> Self {
     match midi_division {
-        MidiDivision::MetricTime(m) => vec![m as u8 >> 8, m as u8 & 0xF],
-        MidiDivision::SubDivision(_)     => todo!(),
-        MidiDivision::Invalid(_)         => todo!(),
+        MidiDivision::MetricTime(m) => vec![(m >> 8) as u8 & 0x7F, (m & 0xFF) as u8],
+        MidiDivision::SubDivision((n_smpte, resolution)) => vec![n_smpte as u8 | 0x80, resolution],
+        // rejected by validation, so midi writer never encodes it
+        MidiDivision::Invalid(_)         => DEFAULT_DIVISION.to_be_bytes().to_vec(),
     }
   }
 }
 
+/// ticks per quarter note used when division is not given
+pub const DEFAULT_DIVISION : u16 = 480;
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MidiHeader {
   header : String,
   length : u32,
@@ -75,7 +82,7 @@ impl Default for MidiHeader {
       length : 6,
       format : MidiFormat::SingleTracksMultiChannel,
       ntrk : m2byte!(0),
-      division : MidiDivision::MetricTime(480)
+      division : MidiDivision::MetricTime(DEFAULT_DIVISION)
     }
   }
 }
@@ -85,6 +92,18 @@ impl MidiHeader {
   pub fn division(&self) -> MidiDivision {self.division.clone()}
   pub fn ntrk(&self) -> M2Byte {self.ntrk}
 
+  /// length of header chunk as declared in file, 6 unless file came from a newer spec
+  pub fn length(&self) -> u32 {self.length}
+
+  pub(crate) fn with_length(mut self, length : u32) -> Self {
+    self.length = length;
+    self
+  }
+
+  pub(crate) fn set_ntrk(&mut self, ntrk : usize) {
+    self.ntrk = m2byte!(ntrk as u16);
+  }
+
   pub fn new(format : MidiFormat, ntrk : M2Byte, division : MidiDivision) -> Self {
     Self {
       header : "MThd".to_string(),
@@ -132,10 +151,13 @@ impl MidiHeader {
 
       // Sub Division;  SMPTE and MIDI Time Code.
       BIT_MASK => {
-        let n_smpte =  i8::from_be_bytes([div[0] & 0x7f]);
+        // negative frames per second, stored as two's complement
+        let n_smpte =  i8::from_be_bytes([div[0]]);
         let frame_resolution = div[1] & 0xFF;
-        assert!(!(n_smpte == -24 || n_smpte ==-25 || n_smpte == 29 || n_smpte == 30), "SMPTE (1st byte) of division should be from list [-24, -25, -29, -30]. But {} was passed with byte val : {}", n_smpte, div[0]);
-        MidiDivision::SubDivision((n_smpte, frame_resolution))
+        match n_smpte {
+          -24 | -25 | -29 | -30 => MidiDivision::SubDivision((n_smpte, frame_resolution)),
+          _ => MidiDivision::Invalid(format!("SMPTE (1st byte) of division should be from list [-24, -25, -29, -30]. But {} was passed with byte val : {}", n_smpte, div[0]))
+        }
       }
 
       _=>MidiDivision::Invalid(format!("midi_header > division : This should never happen"))
@@ -143,12 +165,13 @@ impl MidiHeader {
   }
 }
 
+/// Encodes the header chunk, extra bytes of longer headers are not kept so length is always 6.
 impl From<MidiHeader> for Vec<u8> {
   fn from(midi_header: MidiHeader) -> Self {
     let midi_header_bytes: Vec<u8> = b"MThd".to_vec();
     let midi_header_len  : Vec<u8> = vec![0,0,0,6];
     let midi_format      : Vec<u8> = midi_header.format.into();
-    let ntracks          : Vec<u8> = vec![*midi_header.ntrk as u8 >> 8, *midi_header.ntrk as u8 & 0xFF];
+    let ntracks          : Vec<u8> = midi_header.ntrk.into();
     let midi_division    : Vec<u8> = midi_header.division.into();
     
     [ midi_header_bytes,
@@ -0,0 +1,228 @@
+use std::ops::Deref;
+
+use crate::{
+  parser::{
+    RawEvent, MidiParseError,
+    parser_state::ParserState,
+    ParseOptions, ChunkReader,
+    midi_header_parser::MidiHeaderParser,
+    midi_track_header_parser::MidiTrackHeaderParser,
+    midi_event_parser::MidiEventParser
+  },
+  primitive::m3byte
+};
+
+use super::{
+  midi::Midi,
+  midi_header::MidiHeader,
+  midi_track::MidiTrack,
+  unknown_chunk::UnknownChunk,
+  midi_event::{MidiEvent, MidiMessage, meta_message::Tempo}
+};
+
+/// Borrowed view of midi buffer.
+/// 
+/// Only the midi header and track chunk headers are read on creation, 
+/// events are decoded lazily while iterating tracks and never copy the buffer.
+/// Use [`MidiRef::to_midi`] to convert to owned [`Midi`] when needed.
+#[derive(Debug, Clone)]
+pub struct MidiRef<'a> {
+  buf : &'a [u8],
+  header : MidiHeader,
+  tracks : Vec<TrackRef<'a>>,
+  unknown_chunks : Vec<UnknownChunk>,
+}
+
+impl<'a> MidiRef<'a> {
+  pub fn parse(buf : &'a [u8]) -> Result<MidiRef<'a>, MidiParseError> {
+    let mut state = ChunkReader::smf_state(buf).map_err(|err| err.with_context(buf))?;
+
+    let header = MidiHeaderParser::parse(buf, &mut state).map_err(|err| err.with_context(buf))?;
+
+    let (track_parsers, unknown_chunks, _) = MidiTrackHeaderParser::parse_with(
+      buf, header.clone(), state.with_name(String::from("track-header-parser")), &ParseOptions::strict()
+    ).map_err(|err| err.with_context(buf))?;
+
+    let tracks = track_parsers
+      .iter()
+      .map(|track_parser| TrackRef { buf, state : track_parser.state().clone(), track : track_parser.track() })
+      .collect();
+
+    Ok(MidiRef { buf, header, tracks, unknown_chunks })
+  }
+
+  pub fn header(&self) -> &MidiHeader { &self.header }
+
+  pub fn bytes(&self) -> &'a [u8] { self.buf }
+
+  pub fn track(&self, n : usize) -> Option<&TrackRef<'a>> { self.tracks.get(n) }
+
+  pub fn tracks(&self) -> &Vec<TrackRef<'a>> { &self.tracks }
+
+  /// chunks other than header and tracks, copied on creation
+  pub fn unknown_chunks(&self) -> &Vec<UnknownChunk> { &self.unknown_chunks }
+
+  /// Decodes all tracks to owned Midi.
+  pub fn to_midi(&self) -> Result<Midi, MidiParseError> {
+    let mut midi = Midi::default();
+    midi.add_header(self.header.clone());
+    for track in self.tracks.iter() {
+      midi.add_track(track.to_track()?);
+    }
+    for unknown_chunk in self.unknown_chunks.iter() {
+      midi.add_unknown_chunk(unknown_chunk.clone());
+    }
+    Ok(midi)
+  }
+}
+
+/// Borrowed view of single track chunk.
+#[derive(Debug, Clone)]
+pub struct TrackRef<'a> {
+  buf : &'a [u8],
+  state : ParserState,
+  track : usize,
+}
+
+impl<'a> TrackRef<'a> {
+  /// index of track in midi
+  pub fn index(&self) -> usize { self.track }
+
+  /// events bytes of track chunk, without chunk header.
+  pub fn bytes(&self) -> &'a [u8] { &self.buf[self.state.curr() .. self.state.end()] }
+
+  /// Lazily decodes events of track.
+  pub fn events(&self) -> EventRefIter<'a> {
+    EventRefIter {
+      buf : self.buf,
+      state : self.state.clone(),
+      track : self.track,
+      event : 0,
+      event_parser : MidiEventParser::new(),
+      tick : 0,
+      failed : false
+    }
+  }
+
+  /// Decodes all events to owned MidiTrack.
+  pub fn to_track(&self) -> Result<MidiTrack, MidiParseError> {
+    let mut midi_track = MidiTrack::default();
+    for event in self.events() {
+      midi_track.add_event(event?.to_event());
+    }
+    Ok(midi_track)
+  }
+}
+
+/// Iterator over events of TrackRef, stops after first error.
+#[derive(Debug, Clone)]
+pub struct EventRefIter<'a> {
+  buf : &'a [u8],
+  state : ParserState,
+  track : usize,
+  event : usize,
+  event_parser : MidiEventParser,
+  tick : u64,
+  failed : bool,
+}
+
+impl<'a> Iterator for EventRefIter<'a> {
+  type Item = Result<EventRef<'a>, MidiParseError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.failed || self.state.curr() >= self.state.end() { return None; }
+
+    Some(match self.event_parser.parse_raw_event(self.buf, &mut self.state) {
+      Ok(raw_event) => {
+        self.event += 1;
+        self.tick += raw_event.delta_time() as u64;
+        Ok(EventRef { raw_event, tick : self.tick })
+      },
+      Err(err) => {
+        self.failed = true;
+        Err(err.in_track(self.track).at_event(self.event).with_context(self.buf))
+      }
+    })
+  }
+}
+
+/// Borrowed event, message fields are decoded on access from the borrowed bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct EventRef<'a> {
+  raw_event : RawEvent<'a>,
+  tick : u64,
+}
+
+impl<'a> Deref for EventRef<'a> {
+  type Target = RawEvent<'a>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.raw_event
+  }
+}
+
+impl<'a> EventRef<'a> {
+  /// absolute tick of event in track
+  pub fn tick(&self) -> u64 { self.tick }
+
+  pub fn channel(&self) -> Option<u8> {
+    if self.is_channel_event() { Some(self.event_byte() & 0x0F) } else { None }
+  }
+
+  pub fn is_note_on_event(&self) -> bool {
+    self.is_channel_event() && self.event_byte() & 0xF0 == 0x90 && self.data()[1] != 0
+  }
+
+  pub fn is_note_off_event(&self) -> bool {
+    self.is_channel_event() && (self.event_byte() & 0xF0 == 0x80 || (self.event_byte() & 0xF0 == 0x90 && self.data()[1] == 0))
+  }
+
+  /// note number of note on, note off and after touch events
+  pub fn note(&self) -> Option<u8> {
+    match self.event_byte() & 0xF0 {
+      0x80 | 0x90 | 0xA0 if self.is_channel_event() => Some(self.data()[0]),
+      _ => None
+    }
+  }
+
+  /// velocity of note on and note off events
+  pub fn velocity(&self) -> Option<u8> {
+    match self.event_byte() & 0xF0 {
+      0x80 | 0x90 if self.is_channel_event() => Some(self.data()[1]),
+      _ => None
+    }
+  }
+
+  /// (controller number, value) of controller events
+  pub fn controller(&self) -> Option<(u8, u8)> {
+    match self.event_byte() & 0xF0 {
+      0xB0 if self.is_channel_event() => Some((self.data()[0], self.data()[1])),
+      _ => None
+    }
+  }
+
+  pub fn tempo(&self) -> Option<Tempo> {
+    match self.meta_type() {
+      Some(0x51) if self.data().len() == 3 => Some(Tempo::new(m3byte!(self.data()))),
+      _ => None
+    }
+  }
+
+  /// text of text like meta events (0x01 to 0x0F), None if text isn't valid UTF-8
+  pub fn text(&self) -> Option<&'a str> {
+    match self.meta_type() {
+      Some(0x01 ..= 0x0F) => std::str::from_utf8(self.data()).ok(),
+      _ => None
+    }
+  }
+
+  /// payload of sysex events
+  pub fn sysex(&self) -> Option<&'a [u8]> {
+    if self.is_sys_event() { Some(self.data()) } else { None }
+  }
+
+  /// Decodes the event to owned MidiEvent.
+  pub fn to_event(&self) -> MidiEvent {
+    self.raw_event.to_event()
+  }
+}
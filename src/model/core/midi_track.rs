@@ -1,12 +1,16 @@
 #![allow(dead_code, unused_variables, unused_must_use, unused_imports)]
 
-use std::ops::Add;
+use std::ops::{Add, Range, RangeBounds};
 
 use crate::primitive::{M4Byte, M2Byte, m2byte, m4byte, m3byte};
 
-use super::{midi_event::{MidiEvent, meta_message::Tempo, AbsoluteMidiEvent}, midi_header::MidiHeader};
+use super::{midi_event::{MidiEvent, MidiMessage, meta_message::{MetaMessage, Tempo}, AbsoluteMidiEvent, delta_time::DeltaTime}, midi_header::{MidiHeader, DEFAULT_DIVISION}, query::{EventQuery, TimedEvent}};
+
+/// event along with its absolute tick
+type AbsoluteEvent = (u64, MidiEvent);
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MidiTrack {
   pub(crate) events : Vec<MidiEvent>,
   pub(crate) time_div : M2Byte,
@@ -16,7 +20,7 @@ impl Default for MidiTrack {
     fn default() -> Self {
         Self { 
           events: Vec::new(), 
-          time_div : m2byte!(540)
+          time_div : m2byte!(DEFAULT_DIVISION)
         }
     }
 }
@@ -28,7 +32,7 @@ impl From<(&MidiHeader, u32)> for MidiTrack {
       time_div : midi_header
                     .division()
                     .metric_time()
-                    .unwrap_or(m2byte!(540))
+                    .unwrap_or(m2byte!(DEFAULT_DIVISION))
     }
   }
 }
@@ -38,53 +42,268 @@ impl MidiTrack {
   pub fn new(length : u32) -> Self {
     Self{
       events: Vec::new(),
-      time_div : m2byte!(540)
+      time_div : m2byte!(DEFAULT_DIVISION)
     }
   }
-  /// Create a new MidiTrackIter
-  pub fn iter(&self) -> MidiTrackIter {
-    MidiTrackIter { events: &self.events, top : 0 }
+  /// Create a new MidiTrackIter, iterating over borrowed events
+  pub fn iter(&self) -> MidiTrackIter<'_> {
+    MidiTrackIter { events: self.events.iter() }
+  }
+
+  /// Iterates over events along with their absolute tick, i.e. running total of delta times
+  pub fn iter_absolute(&self) -> MidiTrackAbsoluteIter<'_> {
+    MidiTrackAbsoluteIter { events: self.events.iter(), tick : 0 }
+  }
+
+  /// number of events in track
+  pub fn len(&self) -> usize {
+    self.events.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.events.is_empty()
   }
 
   /// Add MidiEvent at end off track
   pub fn add_event(&mut self, event : MidiEvent) {
     self.events.push(event);
   }
+
+  /// ticks from start of track to last event, i.e. sum of delta times
+  pub fn end_tick(&self) -> u64 {
+    self.events.iter().map(|event| event.delta_time().ticks() as u64).sum()
+  }
+
+  /// Inserts `message` at absolute `tick`, after events already at that tick, returns its index.
+  /// Delta time of following event is shortened, End Of Track is moved to `tick` if it was earlier.
+  pub fn insert_at_tick(&mut self, tick : u64, message : MidiMessage) -> usize {
+    let index = self.iter_absolute()
+      .position(|(event_tick, event)| event_tick > tick || event.is_end_of_track())
+      .unwrap_or(self.events.len());
+    let prev_tick = index.checked_sub(1)
+      .and_then(|prev| self.iter_absolute().nth(prev))
+      .map_or(0, |(event_tick, _)| event_tick);
+
+    if let Some(next) = self.events.get_mut(index) {
+      let next_tick = prev_tick + next.delta_time().ticks() as u64;
+      next.set_delta_time(DeltaTime::from(next_tick.saturating_sub(tick) as u32));
+    }
+    self.events.insert(index, MidiEvent::new(DeltaTime::from((tick - prev_tick) as u32), message));
+    index
+  }
+
+  /// Removes event at `index`, its delta time is carried to next event, so other events keep their ticks.
+  ///
+  /// # Panics
+  /// if `index` is out of bounds.
+  pub fn remove(&mut self, index : usize) -> MidiEvent {
+    let event = self.events.remove(index);
+    if let Some(next) = self.events.get_mut(index) {
+      next.set_delta_time(DeltaTime::from(next.delta_time().ticks() + event.delta_time().ticks()));
+    }
+    event
+  }
+
+  /// Moves event at `index` to absolute `tick`, returns its new index. End Of Track is left in place.
+  ///
+  /// # Panics
+  /// if `index` is out of bounds.
+  pub fn move_event(&mut self, index : usize, tick : u64) -> usize {
+    if self.events[index].is_end_of_track() { return index; }
+    let event = self.remove(index);
+    self.insert_at_tick(tick, event.message().clone())
+  }
+
+  /// Moves events with tick in `range` by `ticks`, events moved before start of track are placed at tick 0.
+  /// Events are reordered by tick, End Of Track stays last and is moved later if needed.
+  pub fn shift<R : RangeBounds<u64>>(&mut self, range : R, ticks : i64) {
+    let (mut events, end_of_track) = self.take_absolute();
+    for (tick, _) in events.iter_mut().filter(|(tick, _)| range.contains(tick)) {
+      *tick = (*tick as i64 + ticks).max(0) as u64;
+    }
+    self.set_absolute(events, end_of_track);
+  }
+
+  /// Replaces events with tick in `range` by events of `track`, placed from `range.start`.
+  /// Events from `range.end` are moved by difference of length of `track` and `range`,
+  /// like cutting and pasting a passage.
+  ///
+  /// Returns the removed events as track starting at tick 0, ending at length of `range`.
+  pub fn splice(&mut self, range : Range<u64>, mut track : MidiTrack) -> MidiTrack {
+    let (events, end_of_track) = self.take_absolute();
+    let (start, end) = (range.start, range.end.max(range.start));
+    let inserted_len = track.end_tick();
+    let moved = |tick : u64| tick - end + start + inserted_len;
+
+    let (mut kept, mut after, mut removed) = (Vec::new(), Vec::new(), Vec::new());
+    for (tick, event) in events {
+      match tick {
+        tick if tick < start => kept.push((tick, event)),
+        tick if tick < end   => removed.push((tick - start, event)),
+        tick                 => after.push((moved(tick), event)),
+      }
+    }
+    kept.extend(track.take_absolute().0.into_iter().map(|(tick, event)| (tick + start, event)));
+    kept.extend(after);
+
+    let end_of_track = end_of_track.map(|(tick, event)| match tick {
+      tick if tick < start => (tick, event),
+      tick if tick < end   => (start + inserted_len, event),
+      tick                 => (moved(tick), event),
+    });
+    self.set_absolute(kept, end_of_track);
+
+    let mut removed_track = MidiTrack { events : Vec::new(), time_div : self.time_div };
+    let end_of_track = MidiEvent::new(DeltaTime::default(), MidiMessage::MetaMessage(MetaMessage::EndOfTrack));
+    removed_track.set_absolute(removed, Some((end - start, end_of_track)));
+    removed_track
+  }
+
+  /// Takes events out of track with their absolute ticks, trailing End Of Track is returned separately.
+  fn take_absolute(&mut self) -> (Vec<AbsoluteEvent>, Option<AbsoluteEvent>) {
+    let mut tick = 0;
+    let mut events : Vec<AbsoluteEvent> = std::mem::take(&mut self.events)
+      .into_iter()
+      .map(|event| {
+        tick += event.delta_time().ticks() as u64;
+        (tick, event)
+      })
+      .collect();
+    let end_of_track = match events.last() {
+      Some((_, event)) if event.is_end_of_track() => events.pop(),
+      _ => None,
+    };
+    (events, end_of_track)
+  }
+
+  /// Puts events back ordered by tick with delta times recomputed,
+  /// End Of Track is placed last, not before the last event.
+  fn set_absolute(&mut self, mut events : Vec<AbsoluteEvent>, end_of_track : Option<AbsoluteEvent>) {
+    events.sort_by_key(|(tick, _)| *tick);
+    let last_tick = events.last().map_or(0, |(tick, _)| *tick);
+    let end_of_track = end_of_track.map(|(tick, event)| (tick.max(last_tick), event));
+
+    let mut last = 0;
+    for (tick, mut event) in events.into_iter().chain(end_of_track) {
+      event.set_delta_time(DeltaTime::from((tick - last) as u32));
+      self.events.push(event);
+      last = tick;
+    }
+  }
+
+  /// Query over events of track, see [`EventQuery`]
+  pub fn events(&self) -> EventQuery<'_> {
+    EventQuery::from_track(self)
+  }
+
+  /// note on and note off events
+  pub fn notes(&self) -> EventQuery<'_> {
+    self.events().notes()
+  }
+
+  /// controller events of controller number `controller`
+  pub fn controllers(&self, controller : u8) -> EventQuery<'_> {
+    self.events().controllers(controller)
+  }
+
+  /// Keeps only events matching `keep`, End Of Track is always kept.
+  /// Delta time of removed events is carried to next kept event, so kept events keep their ticks.
+  pub fn retain<F : FnMut(&TimedEvent) -> bool>(&mut self, keep : F) {
+    self.retain_in(0, keep);
+  }
+
+  /// Removes events matching `remove`, returns number of removed events. See [`MidiTrack::retain`].
+  pub fn remove_where<F : FnMut(&TimedEvent) -> bool>(&mut self, mut remove : F) -> usize {
+    let len = self.events.len();
+    self.retain(|event| !remove(event));
+    len - self.events.len()
+  }
+
+  pub(crate) fn retain_in<F : FnMut(&TimedEvent) -> bool>(&mut self, track : usize, mut keep : F) {
+    let mut tick = 0;
+    let mut carried = 0;
+    for mut event in std::mem::take(&mut self.events) {
+      let delta = event.delta_time().ticks();
+      tick += delta as u64;
+      carried += delta;
+      if event.is_end_of_track() || keep(&TimedEvent::new(track, tick, &event)) {
+        event.set_delta_time(DeltaTime::from(carried));
+        carried = 0;
+        self.events.push(event);
+      }
+    }
+  }
 }
 
 
 
+#[derive(Debug, Clone)]
 pub struct MidiTrackIter<'a> {
-  events : &'a [MidiEvent],
-  top : usize
+  events : std::slice::Iter<'a, MidiEvent>,
 }
 
 impl<'a> MidiTrackIter<'a> {
+    /// first tempo among events not yet iterated
     pub fn get_tempo(&self) -> Option<Tempo> {
-      let tempo =  self.events[self.top ..]
+      self.events
+        .as_slice()
         .iter()
-        .find(|event| event.get_tempo().is_some());
-      
-      match tempo {
-            Some(event) => event.get_tempo(),
-            None => None,
-        }
+        .find_map(|event| event.get_tempo())
     }
 }
 
 impl<'a> Iterator for MidiTrackIter<'a> {
-  type Item = MidiEvent;
+  type Item = &'a MidiEvent;
 
   fn next(&mut self) -> Option<Self::Item> {
-    self.top += 1;
-    if self.top >= self.events.len() {
-      None
-    } else {
-      Some(self.events[self.top - 1].clone())
-    }
+    self.events.next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.events.size_hint()
+  }
+}
+
+impl<'a> DoubleEndedIterator for MidiTrackIter<'a> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.events.next_back()
+  }
+}
+
+impl<'a> ExactSizeIterator for MidiTrackIter<'a> {}
+
+impl<'a> IntoIterator for &'a MidiTrack {
+  type Item = &'a MidiEvent;
+  type IntoIter = MidiTrackIter<'a>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+/// Iterator over events of track with their absolute tick
+#[derive(Debug, Clone)]
+pub struct MidiTrackAbsoluteIter<'a> {
+  events : std::slice::Iter<'a, MidiEvent>,
+  tick : u64,
+}
+
+impl<'a> Iterator for MidiTrackAbsoluteIter<'a> {
+  type Item = (u64, &'a MidiEvent);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let event = self.events.next()?;
+    self.tick += event.delta_time().ticks() as u64;
+    Some((self.tick, event))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.events.size_hint()
   }
 }
 
+impl<'a> ExactSizeIterator for MidiTrackAbsoluteIter<'a> {}
+
 
 
 #[derive(Debug, Clone)]
@@ -103,7 +322,7 @@ impl Default for AbsoluteMidiTrack {
   fn default() -> Self {
       Self { 
         events: Vec::new(), 
-        time_div : m2byte!(540)
+        time_div : m2byte!(DEFAULT_DIVISION)
       }
   }
 }
@@ -114,7 +333,7 @@ impl<'a> From<&MidiTrack> for AbsoluteMidiTrack {
 
     let track_iter = track.iter();
     
-    let tempo = 500_000.0;
+    let mut tempo = Tempo::default().micro_secs();
 
     let time_div = track.time_div.into();
 
@@ -122,16 +341,51 @@ impl<'a> From<&MidiTrack> for AbsoluteMidiTrack {
 
     
     for event in track_iter{
-      let tempo = match event.get_tempo() {
-        Some(tempo) => tempo.micro_secs(),
-        None => tempo,
-      };
-
       time += event.delta_time().to_seconds(time_div, tempo);
 
+      // tempo change applies to events after it
+      if let Some(new_tempo) = event.get_tempo() {
+        tempo = new_tempo.micro_secs();
+      }
+
       abs_midi_track.add_event(AbsoluteMidiEvent::new(time, event.message().clone()));
     }
 
     abs_midi_track
   }
 }
+
+/// Encodes the track chunk, i.e. `MTrk`, length and events.
+/// 
+/// Channel events repeating status of previous event are written with running status.
+/// Messages without encoding are dropped, their delta time is added to next written event.
+impl From<MidiTrack> for Vec<u8> {
+  fn from(midi_track: MidiTrack) -> Self {
+    let mut events : Vec<u8> = Vec::new();
+    let mut running_status = None;
+    let mut carried : u32 = 0;
+
+    for event in midi_track.events {
+      let status = event.event_byte();
+      let message : Vec<u8> = event.message().clone().into();
+      carried += event.delta_time().ticks();
+      if message.is_empty() { continue; }
+
+      let delta_time : Vec<u8> = DeltaTime::from(carried).into();
+      carried = 0;
+      events.extend(delta_time);
+      match status {
+        Some(0x80 ..= 0xEF) if status == running_status => events.extend(&message[1..]),
+        _ => events.extend(message),
+      }
+
+      // meta and sysex events cancel running status
+      running_status = status.filter(|status| (0x80 ..= 0xEF).contains(status));
+    }
+
+    [ b"MTrk".to_vec(),
+      (events.len() as u32).to_be_bytes().to_vec(),
+      events
+    ].concat()
+  }
+}
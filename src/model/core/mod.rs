@@ -2,4 +2,9 @@ pub mod midi;
 pub mod midi_header;
 pub mod midi_track;
 pub mod midi_event;
+pub mod midi_ref;
+pub mod unknown_chunk;
+pub mod query;
+pub mod validation;
+pub mod midi_builder;
 // pub mod timeline;
\ No newline at end of file
@@ -0,0 +1,170 @@
+use std::ops::{Deref, RangeBounds};
+
+use crate::{model::timing::TempoMap, primitive::{FloatWord, M2Byte, m2byte}};
+
+use super::{
+  midi_track::MidiTrack,
+  midi_header::DEFAULT_DIVISION,
+  midi_event::{MidiEvent, MidiMessage, meta_message::MetaMessage, delta_time::DeltaTime}
+};
+
+/// Event along with index of its track and absolute tick.
+#[derive(Debug, Clone, Copy)]
+pub struct TimedEvent<'a> {
+  track : usize,
+  tick : u64,
+  event : &'a MidiEvent,
+}
+
+impl<'a> TimedEvent<'a> {
+  pub fn new(track : usize, tick : u64, event : &'a MidiEvent) -> Self {
+    Self { track, tick, event }
+  }
+
+  /// index of track, 0 for queries on single track
+  pub fn track(&self) -> usize { self.track }
+
+  /// ticks from start of track
+  pub fn tick(&self) -> u64 { self.tick }
+
+  pub fn event(&self) -> &'a MidiEvent { self.event }
+}
+
+impl<'a> Deref for TimedEvent<'a> {
+  type Target = MidiEvent;
+
+  fn deref(&self) -> &Self::Target {
+    self.event
+  }
+}
+
+/// Lazy query over events of midi or track, built by chaining filters.
+///
+/// ```no_run
+/// # let midi = rmidirs::model::core::midi::Midi::default();
+/// // drum notes of first 4 bars in 4/4 at 480 ticks per quarter
+/// let drums = midi.events().channel(9).notes().between(0, 4 * 4 * 480).to_track();
+/// ```
+pub struct EventQuery<'a> {
+  events : Box<dyn Iterator<Item = TimedEvent<'a>> + 'a>,
+  /// ticks per quarter note of queried tracks, kept by [`to_track`](Self::to_track)
+  time_div : M2Byte,
+}
+
+impl<'a> EventQuery<'a> {
+  /// Query over `events` in ticks of default division, i.e. 480 ticks per quarter note.
+  pub fn new<I : Iterator<Item = TimedEvent<'a>> + 'a>(events : I) -> Self {
+    Self { events : Box::new(events), time_div : m2byte!(DEFAULT_DIVISION) }
+  }
+
+  /// Query over events of `tracks`, track after track
+  pub fn from_tracks(tracks : &'a [MidiTrack]) -> Self {
+    let query = Self::new(tracks.iter().enumerate().flat_map(|(track, midi_track)| Self::timed(track, midi_track)));
+    match tracks.first() {
+      Some(midi_track) => query.with_time_div(midi_track.time_div),
+      None => query,
+    }
+  }
+
+  /// Query over events of single track
+  pub fn from_track(midi_track : &'a MidiTrack) -> Self {
+    Self::new(Self::timed(0, midi_track)).with_time_div(midi_track.time_div)
+  }
+
+  fn with_time_div(mut self, time_div : M2Byte) -> Self {
+    self.time_div = time_div;
+    self
+  }
+
+  fn timed(track : usize, midi_track : &'a MidiTrack) -> impl Iterator<Item = TimedEvent<'a>> + 'a {
+    midi_track.events.iter().scan(0u64, move |tick, event| {
+      *tick += event.delta_time().ticks() as u64;
+      Some(TimedEvent::new(track, *tick, event))
+    })
+  }
+
+  /// keeps events matching the `predicate`
+  pub fn filter<P : FnMut(&TimedEvent<'a>) -> bool + 'a>(self, predicate : P) -> Self {
+    let time_div = self.time_div;
+    Self::new(self.events.filter(predicate)).with_time_div(time_div)
+  }
+
+  /// events of track with index `track`
+  pub fn track(self, track : usize) -> Self {
+    self.filter(move |event| event.track() == track)
+  }
+
+  /// channel events of `channel` (0 - 15)
+  pub fn channel(self, channel : u8) -> Self {
+    self.filter(move |event| event.channel() == Some(channel))
+  }
+
+  /// note on and note off events
+  pub fn notes(self) -> Self {
+    self.filter(|event| event.is_note_on_off_event())
+  }
+
+  /// note on events with non zero velocity
+  pub fn note_ons(self) -> Self {
+    self.filter(|event| event.is_note_on_event())
+  }
+
+  /// note events with note number in `range`
+  pub fn pitches<R : RangeBounds<u8> + 'a>(self, range : R) -> Self {
+    self.filter(move |event| matches!(event.get_note_number(), Some(note) if range.contains(&(*note as u8))))
+  }
+
+  /// controller events of controller number `controller`, e.g. 64 for sustain pedal
+  pub fn controllers(self, controller : u8) -> Self {
+    self.filter(move |event| matches!(event.get_controller(), Some((number, _)) if *number == controller as u32))
+  }
+
+  /// program change events
+  pub fn programs(self) -> Self {
+    self.filter(|event| event.get_program().is_some())
+  }
+
+  /// meta events of type `meta_type`, e.g. 0x51 for tempo
+  pub fn meta(self, meta_type : u8) -> Self {
+    self.filter(move |event| event.meta_type() == Some(meta_type))
+  }
+
+  /// events in tick range `[start, end)`
+  pub fn between(self, start : u64, end : u64) -> Self {
+    self.filter(move |event| start <= event.tick() && event.tick() < end)
+  }
+
+  /// events in time range `[start, end)` in seconds, ticks are converted using `tempo_map`
+  pub fn between_seconds(self, tempo_map : &TempoMap, start : FloatWord, end : FloatWord) -> Self {
+    let (start, end) = (tempo_map.tick(start), tempo_map.tick(end));
+    self.between(start, end)
+  }
+
+  /// Collects the events to new track, ordered by tick, in division of queried tracks.
+  /// Delta times are recomputed, and End Of Track is placed at tick of last event.
+  pub fn to_track(self) -> MidiTrack {
+    let mut events : Vec<(u64, MidiEvent)> = self.events
+      .filter(|event| !event.is_end_of_track())
+      .map(|event| (event.tick(), event.event().clone()))
+      .collect();
+    events.sort_by_key(|(tick, _)| *tick);
+
+    let mut midi_track = MidiTrack { time_div : self.time_div, ..MidiTrack::default() };
+    let mut last = 0;
+    for (tick, mut event) in events {
+      event.set_delta_time(DeltaTime::from((tick - last) as u32));
+      midi_track.add_event(event);
+      last = tick;
+    }
+    midi_track.add_event(MidiEvent::new(DeltaTime::default(), MidiMessage::MetaMessage(MetaMessage::EndOfTrack)));
+    midi_track
+  }
+}
+
+impl<'a> Iterator for EventQuery<'a> {
+  type Item = TimedEvent<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.events.next()
+  }
+}
@@ -0,0 +1,43 @@
+/// Chunk of type other than `MThd` and `MTrk`.
+///
+/// SMF requires readers to skip such chunks, rmidirs keeps them as opaque bytes,
+/// so they survive a read / write round trip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnknownChunk {
+  id : [u8; 4],
+  data : Vec<u8>,
+  position : usize,
+}
+
+impl UnknownChunk {
+  /// Creates chunk placed after the last track.
+  pub fn new(id : [u8; 4], data : Vec<u8>) -> Self {
+    Self { id, data, position : usize::MAX }
+  }
+
+  pub(crate) fn with_position(mut self, position : usize) -> Self {
+    self.position = position;
+    self
+  }
+
+  /// 4 byte chunk type
+  pub fn id(&self) -> [u8; 4] { self.id }
+
+  /// chunk type as text, e.g. `XFIH`
+  pub fn name(&self) -> String { String::from_utf8_lossy(&self.id).into_owned() }
+
+  pub fn data(&self) -> &[u8] { &self.data }
+
+  /// number of track chunks before this chunk in file
+  pub fn position(&self) -> usize { self.position }
+}
+
+impl From<UnknownChunk> for Vec<u8> {
+  fn from(chunk: UnknownChunk) -> Self {
+    [ chunk.id.to_vec(),
+      (chunk.data.len() as u32).to_be_bytes().to_vec(),
+      chunk.data
+    ].concat()
+  }
+}
@@ -0,0 +1,146 @@
+use std::{fmt, error};
+
+use super::{midi::Midi, midi_header::{MidiFormat, MidiDivision}, midi_event::{MidiMessage, channel_message::ChannelMessage, meta_message::MetaMessage}};
+
+/// Rule of Standard Midi File broken by the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ViolationKind {
+  /// `ntrk` of Midi Header doesn't match number of tracks.
+  TrackCountMismatch,
+  /// format 0 midi holds more than one track.
+  MultipleTracksInFormat0,
+  /// track has no End Of Track.
+  MissingEndOfTrack,
+  /// End Of Track is not the last event of track.
+  EventAfterEndOfTrack,
+  /// channel message has data byte above 127.
+  DataByteOutOfRange,
+  /// message couldn't be decoded, writer would drop it.
+  InvalidMessage,
+  /// division of Midi Header couldn't be decoded, it has no encoding.
+  InvalidDivision,
+}
+
+/// Single broken rule, along with track and event index where it was found.
+#[derive(Debug, Clone)]
+pub struct Violation {
+  kind : ViolationKind,
+  track : Option<usize>,
+  event : Option<usize>,
+  message : String,
+}
+
+impl Violation {
+  pub fn new(kind : ViolationKind, track : Option<usize>, event : Option<usize>, message : String) -> Self {
+    Self { kind, track, event, message }
+  }
+
+  pub fn kind(&self) -> ViolationKind { self.kind }
+
+  /// index of track, None for violations of header
+  pub fn track(&self) -> Option<usize> { self.track }
+
+  /// index of event in track
+  pub fn event(&self) -> Option<usize> { self.event }
+
+  pub fn message(&self) -> &str { &self.message }
+}
+
+impl fmt::Display for Violation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match (self.track, self.event) {
+      (Some(track), Some(event)) => write!(f, "track {}, event {}: {}", track, event, self.message),
+      (Some(track), None) => write!(f, "track {}: {}", track, self.message),
+      _ => write!(f, "{}", self.message),
+    }
+  }
+}
+
+/// Error returned when midi breaks rules of Standard Midi File, holds every violation found.
+#[derive(Debug, Clone)]
+pub struct ValidationError {
+  violations : Vec<Violation>,
+}
+
+impl ValidationError {
+  pub fn violations(&self) -> &[Violation] { &self.violations }
+}
+
+impl fmt::Display for ValidationError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "midi has {} violation(s)", self.violations.len())?;
+    for violation in self.violations.iter() {
+      write!(f, "\n  {}", violation)?;
+    }
+    Ok(())
+  }
+}
+
+impl error::Error for ValidationError {}
+
+/// Checks `midi` against the rules of Standard Midi File.
+///
+/// Running status is not part of the model, every event stores its own status,
+/// and the writer restates status after meta and sysex events, so it can't leak across them.
+pub(crate) fn validate(midi : &Midi) -> Result<(), ValidationError> {
+  let mut violations = Vec::new();
+  let tracks = midi.tracks();
+
+  let ntrk = *midi.header().ntrk() as usize;
+  if ntrk != tracks.len() {
+    violations.push(Violation::new(
+      ViolationKind::TrackCountMismatch, None, None,
+      format!("midi header declares {} tracks, but midi has {} tracks", ntrk, tracks.len())
+    ));
+  }
+
+  if let MidiDivision::Invalid(reason) = midi.header().division() {
+    violations.push(Violation::new(ViolationKind::InvalidDivision, None, None, reason));
+  }
+
+  if matches!(midi.header().format(), MidiFormat::SingleTracksMultiChannel) && tracks.len() > 1 {
+    violations.push(Violation::new(
+      ViolationKind::MultipleTracksInFormat0, None, None,
+      format!("format 0 midi can have single track, but has {} tracks", tracks.len())
+    ));
+  }
+
+  for (track_no, track) in tracks.iter().enumerate() {
+    match track.iter().position(|event| event.is_end_of_track()) {
+      None => violations.push(Violation::new(
+        ViolationKind::MissingEndOfTrack, Some(track_no), None,
+        String::from("track doesn't end with End Of Track")
+      )),
+      Some(end) if end + 1 < track.len() => violations.push(Violation::new(
+        ViolationKind::EventAfterEndOfTrack, Some(track_no), Some(end + 1),
+        format!("{} event(s) after End Of Track at event {}", track.len() - end - 1, end)
+      )),
+      _ => (),
+    }
+
+    for (event_no, event) in track.iter().enumerate() {
+      match event.message() {
+        MidiMessage::ChannelMessage(ChannelMessage::Invalid(reason))
+        | MidiMessage::MetaMessage(MetaMessage::Invalid(reason))
+        | MidiMessage::Invalid(reason) => violations.push(Violation::new(
+          ViolationKind::InvalidMessage, Some(track_no), Some(event_no), reason.clone()
+        )),
+        MidiMessage::ChannelMessage(message) => {
+          let bytes : Vec<u8> = message.clone().into();
+          if let Some(byte) = bytes.iter().skip(1).find(|byte| **byte > 0x7F) {
+            violations.push(Violation::new(
+              ViolationKind::DataByteOutOfRange, Some(track_no), Some(event_no),
+              format!("data byte 0x{:02X} of channel message is above 127", byte)
+            ));
+          }
+        },
+        _ => (),
+      }
+    }
+  }
+
+  match violations.is_empty() {
+    true => Ok(()),
+    false => Err(ValidationError { violations }),
+  }
+}
@@ -1,2 +1,4 @@
 pub mod core;
-pub mod note_seq;
\ No newline at end of file
+pub mod note_seq;
+pub mod timing;
+pub mod ump;
\ No newline at end of file
@@ -9,3 +9,7 @@ mod node;
 pub use crate::model::note_seq::track_seq::TrackSeq;
 
 pub use crate::model::note_seq::note_seq::NoteSeq;
+
+pub use crate::model::note_seq::note::Note;
+
+pub use crate::model::note_seq::node::Node;
@@ -2,13 +2,19 @@ use std::ops::{Deref, DerefMut};
 
 use crate::{primitive::FloatWord, model::core::midi_event::channel_message::ChannelMessage};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node<T>(FloatWord, T);
 
 impl<T> Node<T> {
     pub fn new(at: FloatWord, element: T) -> Self {
       Self(at, element)
     }
+
+    /// time in seconds at which node is placed.
+    pub fn at(&self) -> FloatWord { self.0 }
+
+    pub fn element(&self) -> &T { &self.1 }
 }
 
 impl<T> Deref for Node<T>  {
@@ -3,7 +3,8 @@ use crate::{primitive::{Word, FloatWord}, model::core::midi_event::channel_messa
 /// Note stores the information of Midi Note Event.
 /// Unlike the Note ON and Note OFF events in MIDI, 
 /// Note stores the start time and end time in single object.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Note {
   /// MIDI pitch; see https://en.wikipedia.org/wiki/MIDI_Tuning_Standard for details.
   pitch : Word,
@@ -26,6 +27,17 @@ impl Note {
   pub fn pitch_name(&self) -> &str { 
     return "C";
   }
+
+  pub fn pitch(&self) -> Word { self.pitch }
+
+  pub fn velocity(&self) -> Word { self.velocity }
+
+  pub fn start_time(&self) -> FloatWord { self.start_time }
+
+  pub fn end_time(&self) -> FloatWord { self.end_time }
+
+  /// Duration of note in seconds.
+  pub fn duration(&self) -> FloatWord { self.end_time - self.start_time }
 }
 
 
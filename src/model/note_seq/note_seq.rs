@@ -12,21 +12,25 @@ use crate::{
 
 use super::{node::Node, note::Note};
 
+/// tempo assumed when midi has no tempo event, in qbpm
+const DEFAULT_QBPM : FloatWord = 120.0;
+
 /// Note Sequence stores note progression similar to midi track,
 /// but instead of NoteOn and NoteOff events, single Note event is stored,
 /// and note duration and note length are stored instead of delta time.
 /// 
 /// All other meta / channel events information are store as part of note itself,
 /// or as part of note sequence attributes.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NoteSeq {
   /// total time of the note sequence stored in seconds
   total_time : FloatWord,
 
   notes : Vec<Node<Note>>,
   
-  /// stores tempo in **qbpm** (quater beats per minute) 
-  tempos : Vec<Node<Word>>,
+  /// stores tempo in **qbpm** (quater beats per minute), exact rather than rounded, e.g. 123.45
+  tempos : Vec<Node<FloatWord>>,
 
   /// default : 4/4 is assumed per MIDI standard. 
   /// represented as (4, 4)
@@ -34,7 +38,11 @@ pub struct NoteSeq {
   
   /// default : C Major, is assumed per MIDI standard.
   /// represented as cmajor
-  key_signatures : Vec<Node<String>>
+  key_signatures : Vec<Node<String>>,
+
+  /// whether key signature at start was set, rather than assumed
+  #[cfg_attr(feature = "serde", serde(default))]
+  start_key_declared : bool
 }
 
 impl Default for NoteSeq {
@@ -42,13 +50,89 @@ impl Default for NoteSeq {
     Self { 
       total_time: 0.0, 
       notes: Default::default(), 
-      tempos: vec![Node::new(0.0, 120)], 
+      tempos: vec![Node::new(0.0, DEFAULT_QBPM)], 
       time_signatures: vec![Node::new(0.0, (4, 4))], 
       key_signatures: vec![Node::new(0.0, "c-major".to_string())], 
+      start_key_declared: false,
     }
   }
 }
 
+impl NoteSeq {
+  pub fn total_time(&self) -> FloatWord { self.total_time }
+
+  pub fn notes(&self) -> &Vec<Node<Note>> { &self.notes }
+
+  pub fn tempos(&self) -> &Vec<Node<FloatWord>> { &self.tempos }
+
+  pub fn time_signatures(&self) -> &Vec<Node<FractionWord>> { &self.time_signatures }
+
+  pub fn key_signatures(&self) -> &Vec<Node<String>> { &self.key_signatures }
+
+  /// Key signatures that were set, without the assumed C Major at start.
+  pub fn declared_key_signatures(&self) -> &[Node<String>] {
+    match self.key_signatures.first() {
+      Some(first) if first.at() == 0.0 && !self.start_key_declared => &self.key_signatures[1..],
+      _ => &self.key_signatures,
+    }
+  }
+
+  /// Adds note to the sequence, node is placed at note end time similar to notes parsed from midi track.
+  pub fn add_note(&mut self, note : Note) {
+    self.total_time = self.total_time.max(note.end_time());
+    self.notes.push(Node::new(note.end_time(), note));
+  }
+
+  /// Sets the tempo (in qbpm) from `at` seconds onwards. 
+  /// Tempo already present at same time is replaced.
+  pub fn set_tempo(&mut self, at : FloatWord, qbpm : FloatWord) {
+    self.tempos.retain(|tempo| tempo.at() != at);
+    let index = self.tempos.partition_point(|tempo| tempo.at() < at);
+    self.tempos.insert(index, Node::new(at, qbpm));
+  }
+
+  /// Sets the time signature, as (numerator, denominator), from `at` seconds onwards.
+  /// Time signature already present at same time is replaced.
+  pub fn set_time_signature(&mut self, at : FloatWord, time_signature : FractionWord) {
+    self.time_signatures.retain(|time_signature| time_signature.at() != at);
+    let index = self.time_signatures.partition_point(|time_signature| time_signature.at() < at);
+    self.time_signatures.insert(index, Node::new(at, time_signature));
+  }
+
+  /// Sets the key signature from `at` seconds onwards.
+  /// Key signature already present at same time is replaced.
+  pub fn set_key_signature(&mut self, at : FloatWord, key : String) {
+    self.key_signatures.retain(|key| key.at() != at);
+    let index = self.key_signatures.partition_point(|key| key.at() < at);
+    self.key_signatures.insert(index, Node::new(at, key));
+    if at == 0.0 { self.start_key_declared = true; }
+  }
+
+  /// Returns the position in quarter beats at `time` seconds, following the tempo changes.
+  pub fn beat_at(&self, time : FloatWord) -> FloatWord {
+    let mut beats = 0.0;
+    for (i, tempo) in self.tempos.iter().enumerate() {
+      let end = self.tempos.get(i + 1).map_or(time, |next| next.at().min(time));
+      if end <= tempo.at() { break; }
+      beats += (end - tempo.at()) * **tempo / 60.0;
+    }
+    beats
+  }
+
+  /// Returns the time in seconds at which `beat` (in quarter beats) starts, following the tempo changes.
+  pub fn time_at(&self, beat : FloatWord) -> FloatWord {
+    let mut beats = 0.0;
+    for (i, tempo) in self.tempos.iter().enumerate() {
+      let qbps = **tempo / 60.0;
+      match self.tempos.get(i + 1) {
+        Some(next) if beats + (next.at() - tempo.at()) * qbps < beat => beats += (next.at() - tempo.at()) * qbps,
+        _ => return tempo.at() + (beat - beats) / qbps
+      }
+    }
+    // no tempo at all
+    beat * 60.0 / DEFAULT_QBPM
+  }
+}
 
 impl From<(MidiHeader, MidiTrack)> for NoteSeq {
   fn from(value: (MidiHeader, MidiTrack)) -> Self {
@@ -82,7 +166,18 @@ impl From<(&MidiHeader, &MidiTrack)> for NoteSeq {
       time += event.delta_time().to_seconds(midi_division.into(), tempo.micro_secs());
       
       // adjusting current tempo to new tempo if current event is tempo change event.
-      tempo = event.get_tempo().unwrap_or(tempo);
+      if let Some(new_tempo) = event.get_tempo() {
+        tempo = new_tempo;
+        note_seq.set_tempo(time, tempo.qbpm());
+      }
+
+      if let Some(time_signature) = event.get_time_signature() {
+        note_seq.set_time_signature(time, (time_signature.numerator(), time_signature.denominator()));
+      }
+
+      if let Some(key_signature) = event.get_key_signature() {
+        note_seq.set_key_signature(time, key_signature.name());
+      }
 
       match event.message() {
         ChannelMessage(channel_message) => {
@@ -104,10 +199,7 @@ impl From<(&MidiHeader, &MidiTrack)> for NoteSeq {
             };
             
             // adding the event to note_seq
-            note_seq.notes.push(Node::new(
-              time, 
-              Note::from((note, start_time, time))
-            ))
+            note_seq.add_note(Note::from((note, start_time, time)))
           }
         },
         _ => {}
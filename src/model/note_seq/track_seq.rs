@@ -4,13 +4,22 @@ use super::note_seq::NoteSeq;
 
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrackSeq {
   tracks : Vec<NoteSeq>,
   time_div : Word,
   format : Word,
 }
 
+impl TrackSeq {
+  pub fn tracks(&self) -> &Vec<NoteSeq> { &self.tracks }
+
+  pub fn time_div(&self) -> Word { self.time_div }
+
+  pub fn format(&self) -> Word { self.format }
+}
+
 impl From<Midi> for TrackSeq {
   fn from(midi: Midi) -> Self {
     let midi_header = midi.header();
@@ -0,0 +1,184 @@
+use std::fmt;
+
+use crate::{
+  primitive::{Word, FloatWord},
+  model::core::{
+    midi::Midi,
+    midi_event::meta_message::TimeSignature
+  }
+};
+
+use super::TempoMap;
+
+/// Musical position of tick, bar and beat are counted from 1 like in notation, 
+/// tick is offset from start of the beat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MeasurePosition {
+  pub bar : Word,
+  pub beat : Word,
+  pub tick : u64,
+}
+
+impl MeasurePosition {
+  pub fn new(bar : Word, beat : Word, tick : u64) -> Self {
+    Self { bar, beat, tick }
+  }
+}
+
+impl fmt::Display for MeasurePosition {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "bar {}, beat {}, tick {}", self.bar, self.beat, self.tick)
+  }
+}
+
+/// Measure Map converts absolute ticks and seconds to (bar, beat, tick) positions and back.
+/// 
+/// Beat is the note value of time signature denominator, i.e. eighth note in 6/8.
+/// Time signature change always starts new bar, so change in middle of the bar shortens that bar.
+#[derive(Debug, Clone)]
+pub struct MeasureMap {
+  tempo_map : TempoMap,
+  /// time signature segments, stored as (start tick, first bar, time signature), sorted by start tick.
+  segments : Vec<(u64, Word, TimeSignature)>,
+}
+
+impl Default for MeasureMap {
+  fn default() -> Self {
+    Self::new(TempoMap::default(), Vec::new())
+  }
+}
+
+impl MeasureMap {
+  /// Creates measure map from tempo map and (absolute tick, time signature) pairs.
+  /// 4/4 is assumed until first time signature change, per MIDI standard.
+  pub fn new(tempo_map : TempoMap, mut time_signatures : Vec<(u64, TimeSignature)>) -> Self {
+    time_signatures.sort_by_key(|(tick, _)| *tick);
+    if !matches!(time_signatures.first(), Some((0, _))) {
+      time_signatures.insert(0, (0, TimeSignature::default()));
+    }
+
+    let mut map = Self { tempo_map, segments : Vec::with_capacity(time_signatures.len()) };
+    for (tick, time_signature) in time_signatures {
+      let bar = match map.segments.last() {
+        // later time signature at same tick overrides former
+        Some((start, bar, _)) if *start == tick => { let bar = *bar; map.segments.pop(); bar },
+        Some(_) => {
+          let position = map.position(tick);
+          if position.beat == 1 && position.tick == 0 { position.bar } else { position.bar + 1 }
+        },
+        None => 1,
+      };
+      map.segments.push((tick, bar, time_signature));
+    }
+    map
+  }
+
+  pub fn tempo_map(&self) -> &TempoMap { &self.tempo_map }
+
+  /// Time signature changes as (absolute tick, time signature)
+  pub fn time_signatures(&self) -> Vec<(u64, TimeSignature)> {
+    self.segments.iter().map(|(tick, _, time_signature)| (*tick, time_signature.clone())).collect()
+  }
+
+  /// Time signature in effect at `tick`
+  pub fn time_signature_at(&self, tick : u64) -> TimeSignature {
+    self.segment_at_tick(tick).2.clone()
+  }
+
+  fn segment_at_tick(&self, tick : u64) -> &(u64, Word, TimeSignature) {
+    let index = self.segments.partition_point(|(start, _, _)| *start <= tick);
+    &self.segments[index.saturating_sub(1)]
+  }
+
+  fn segment_at_bar(&self, bar : Word) -> &(u64, Word, TimeSignature) {
+    let index = self.segments.partition_point(|(_, first_bar, _)| *first_bar <= bar);
+    &self.segments[index.saturating_sub(1)]
+  }
+
+  /// Number of ticks in single beat of time signature.
+  pub fn ticks_per_beat(&self, time_signature : &TimeSignature) -> u64 {
+    let ticks_per_quarter = self.tempo_map.ticks_per_quarter().max(1) as FloatWord;
+    ((ticks_per_quarter * time_signature.beat_length()).round() as u64).max(1)
+  }
+
+  /// Number of ticks in single bar of time signature.
+  pub fn ticks_per_bar(&self, time_signature : &TimeSignature) -> u64 {
+    self.ticks_per_beat(time_signature) * time_signature.numerator().max(1) as u64
+  }
+
+  /// Converts absolute `tick` to measure position.
+  pub fn position(&self, tick : u64) -> MeasurePosition {
+    let (start, first_bar, time_signature) = self.segment_at_tick(tick);
+    let offset = tick.saturating_sub(*start);
+    let per_beat = self.ticks_per_beat(time_signature);
+    let per_bar = self.ticks_per_bar(time_signature);
+
+    MeasurePosition {
+      bar : first_bar + (offset / per_bar) as Word,
+      beat : ((offset % per_bar) / per_beat) as Word + 1,
+      tick : offset % per_beat,
+    }
+  }
+
+  /// Converts measure position to absolute tick. 
+  /// Beats and ticks overflowing the bar are carried to following bars.
+  pub fn tick(&self, position : &MeasurePosition) -> u64 {
+    let bar = position.bar.max(1);
+    let (start, first_bar, time_signature) = self.segment_at_bar(bar);
+    start
+      + (bar - first_bar) as u64 * self.ticks_per_bar(time_signature)
+      + position.beat.saturating_sub(1) as u64 * self.ticks_per_beat(time_signature)
+      + position.tick
+  }
+
+  /// Converts `seconds` to measure position.
+  pub fn position_at(&self, seconds : FloatWord) -> MeasurePosition {
+    self.position(self.tempo_map.tick(seconds))
+  }
+
+  /// Converts measure position to seconds.
+  pub fn seconds(&self, position : &MeasurePosition) -> FloatWord {
+    self.tempo_map.seconds(self.tick(position))
+  }
+
+  /// Returns the (start tick, end tick) of `bar`, end tick is exclusive.
+  pub fn bar_range(&self, bar : Word) -> (u64, u64) {
+    (self.tick(&MeasurePosition::new(bar, 1, 0)), self.tick(&MeasurePosition::new(bar + 1, 1, 0)))
+  }
+
+  /// Returns absolute ticks of all downbeats (bar starts) before `end` tick.
+  pub fn downbeats(&self, end : u64) -> Vec<u64> {
+    let mut downbeats = Vec::new();
+    let mut bar = 1;
+    loop {
+      let tick = self.tick(&MeasurePosition::new(bar, 1, 0));
+      if tick >= end { return downbeats; }
+      downbeats.push(tick);
+      bar += 1;
+    }
+  }
+
+  /// Returns times in seconds of all downbeats (bar starts) before `end` seconds.
+  pub fn downbeat_times(&self, end : FloatWord) -> Vec<FloatWord> {
+    self.downbeats(self.tempo_map.tick(end))
+      .into_iter()
+      .map(|tick| self.tempo_map.seconds(tick))
+      .collect()
+  }
+}
+
+impl From<&Midi> for MeasureMap {
+  fn from(midi : &Midi) -> Self {
+    let mut time_signatures = Vec::new();
+    for track in midi.tracks() {
+      let mut tick = 0;
+      for event in track.events.iter() {
+        tick += event.delta_time().ticks() as u64;
+        if let Some(time_signature) = event.get_time_signature() {
+          time_signatures.push((tick, time_signature));
+        }
+      }
+    }
+    Self::new(TempoMap::from(midi), time_signatures)
+  }
+}
@@ -0,0 +1,9 @@
+//! Maps between MIDI ticks, seconds and musical (bar, beat) positions.
+//! 
+//! Both maps are built from tempo and time signature meta events of all tracks of [`Midi`](crate::model::core::midi::Midi),
+//! as in Format 1 files these are usually stored in first (conductor) track only.
+
+pub use self::{tempo_map::TempoMap, measure_map::{MeasureMap, MeasurePosition}};
+
+mod tempo_map;
+mod measure_map;
@@ -0,0 +1,109 @@
+use crate::{
+  primitive::FloatWord,
+  model::core::{
+    midi::Midi,
+    midi_header::MidiDivision,
+    midi_event::meta_message::Tempo
+  }
+};
+
+/// Tempo Map converts absolute ticks to seconds and back, following tempo changes of all tracks.
+/// 
+/// For SMPTE division tempo changes are ignored, as tick has fixed length of `1 / (frames * ticks per frame)` seconds.
+#[derive(Debug, Clone)]
+pub struct TempoMap {
+  /// ticks per quater note, 0 for SMPTE division
+  ticks_per_quarter : u32,
+  /// seconds per tick for SMPTE division
+  smpte_tick : FloatWord,
+  /// tempo segments stored as (start tick, start seconds, tempo), sorted by start tick.
+  tempos : Vec<(u64, FloatWord, Tempo)>,
+}
+
+impl Default for TempoMap {
+  fn default() -> Self {
+    Self::new(MidiDivision::MetricTime(480), Vec::new())
+  }
+}
+
+impl TempoMap {
+  /// Creates tempo map from `division` and (absolute tick, tempo) pairs.
+  /// Tempo of 120 qbpm is assumed until first tempo change, per MIDI standard.
+  pub fn new(division : MidiDivision, mut tempos : Vec<(u64, Tempo)>) -> Self {
+    let (ticks_per_quarter, smpte_tick) = match division {
+      MidiDivision::MetricTime(ticks) => (ticks.max(1) as u32, 0.0),
+      MidiDivision::SubDivision((frames, resolution)) => {
+        let frames = match frames.unsigned_abs() { 29 => 29.97, frames => frames as FloatWord };
+        (0, 1.0 / (frames.max(1.0) * resolution.max(1) as FloatWord))
+      },
+      MidiDivision::Invalid(_) => (480, 0.0),
+    };
+
+    tempos.sort_by_key(|(tick, _)| *tick);
+    if !matches!(tempos.first(), Some((0, _))) {
+      tempos.insert(0, (0, Tempo::default()));
+    }
+
+    let mut map = Self { ticks_per_quarter, smpte_tick, tempos : Vec::with_capacity(tempos.len()) };
+    for (tick, tempo) in tempos {
+      // later tempo at same tick overrides former
+      if matches!(map.tempos.last(), Some((last, _, _)) if *last == tick) {
+        map.tempos.pop();
+      }
+      let seconds = map.seconds(tick);
+      map.tempos.push((tick, seconds, tempo));
+    }
+    map
+  }
+
+  pub fn ticks_per_quarter(&self) -> u32 { self.ticks_per_quarter }
+
+  /// Tempo changes as (absolute tick, tempo)
+  pub fn tempos(&self) -> Vec<(u64, Tempo)> {
+    self.tempos.iter().map(|(tick, _, tempo)| (*tick, *tempo)).collect()
+  }
+
+  /// Tempo sounding at `tick`
+  pub fn tempo_at(&self, tick : u64) -> Tempo {
+    self.segment(tick).map_or(Tempo::default(), |(_, _, tempo)| *tempo)
+  }
+
+  fn segment(&self, tick : u64) -> Option<&(u64, FloatWord, Tempo)> {
+    let index = self.tempos.partition_point(|(start, _, _)| *start <= tick);
+    self.tempos.get(index.checked_sub(1)?)
+  }
+
+  /// Converts absolute `tick` to seconds.
+  pub fn seconds(&self, tick : u64) -> FloatWord {
+    if self.ticks_per_quarter == 0 { return tick as FloatWord * self.smpte_tick; }
+
+    let (start, seconds, tempo) = self.segment(tick).copied().unwrap_or((0, 0.0, Tempo::default()));
+    seconds + (tick - start) as FloatWord * tempo.secs() / self.ticks_per_quarter as FloatWord
+  }
+
+  /// Converts `seconds` to absolute tick, rounded to nearest tick.
+  pub fn tick(&self, seconds : FloatWord) -> u64 {
+    if self.ticks_per_quarter == 0 { return (seconds / self.smpte_tick).round().max(0.0) as u64; }
+
+    let index = self.tempos.partition_point(|(_, start, _)| *start <= seconds);
+    let (start, start_seconds, tempo) = self.tempos.get(index.saturating_sub(1)).copied().unwrap_or((0, 0.0, Tempo::default()));
+    let ticks = (seconds - start_seconds) * self.ticks_per_quarter as FloatWord / tempo.secs();
+    start + ticks.round().max(0.0) as u64
+  }
+}
+
+impl From<&Midi> for TempoMap {
+  fn from(midi : &Midi) -> Self {
+    let mut tempos = Vec::new();
+    for track in midi.tracks() {
+      let mut tick = 0;
+      for event in track.events.iter() {
+        tick += event.delta_time().ticks() as u64;
+        if let Some(tempo) = event.get_tempo() {
+          tempos.push((tick, tempo));
+        }
+      }
+    }
+    Self::new(midi.header().division(), tempos)
+  }
+}
@@ -0,0 +1,172 @@
+use crate::model::core::midi_event::channel_message::ChannelMessage;
+
+use super::field;
+
+/// MIDI 1.0 Channel Voice message in UMP (type 0x2), the MIDI 1.0 message with group, 32 bits.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Midi1ChannelVoice {
+  group : u8,
+  message : ChannelMessage,
+}
+
+impl Midi1ChannelVoice {
+  pub fn new(group : u8, message : ChannelMessage) -> Self {
+    Self { group : group & 0xF, message }
+  }
+
+  pub fn group(&self) -> u8 { self.group }
+
+  pub fn message(&self) -> &ChannelMessage { &self.message }
+
+  pub(crate) fn from_word(word : u32) -> Result<Self, String> {
+    let status = field(word, 16, 8) as u8;
+    if !ChannelMessage::is_channel_event(status) {
+      return Err(format!("expected channel voice status, found 0x{:02X}", status));
+    }
+    let data = [field(word, 8, 7) as u8, field(word, 0, 7) as u8];
+    Ok(Self::new(field(word, 24, 4) as u8, ChannelMessage::from((status, &data[..]))))
+  }
+
+  pub fn word(&self) -> u32 {
+    let bytes : Vec<u8> = self.message.clone().into();
+    let byte = |index : usize| bytes.get(index).copied().unwrap_or_default() as u32;
+    0x2 << 28 | (self.group as u32) << 24 | byte(0) << 16 | byte(1) << 8 | byte(2)
+  }
+}
+
+/// MIDI 2.0 Channel Voice message (type 0x4), 64 bits.
+///
+/// Velocities are 16 bits, controllers, pressures and pitch bends are 32 bits,
+/// pitch bends and relative controllers are centered at `0x80000000` and 0 respectively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Midi2Message {
+  NoteOff { note : u8, velocity : u16, attribute_type : u8, attribute : u16 },
+  NoteOn { note : u8, velocity : u16, attribute_type : u8, attribute : u16 },
+  PolyPressure { note : u8, value : u32 },
+  /// per-note controller with index defined by the specification, e.g. 3 for pitch in 7.25 format
+  RegisteredPerNoteController { note : u8, index : u8, value : u32 },
+  AssignablePerNoteController { note : u8, index : u8, value : u32 },
+  /// detaches controllers from previous notes of same number, and / or resets them to default
+  PerNoteManagement { note : u8, detach : bool, reset : bool },
+  ControlChange { index : u8, value : u32 },
+  /// RPN, as bank and index of the parameter
+  RegisteredController { bank : u8, index : u8, value : u32 },
+  /// NRPN, as bank and index of the parameter
+  AssignableController { bank : u8, index : u8, value : u32 },
+  RelativeRegisteredController { bank : u8, index : u8, value : i32 },
+  RelativeAssignableController { bank : u8, index : u8, value : i32 },
+  /// program, and bank as (msb, lsb) when bank is selected along
+  ProgramChange { program : u8, bank : Option<(u8, u8)> },
+  ChannelPressure { value : u32 },
+  PitchBend { value : u32 },
+  PerNotePitchBend { note : u8, value : u32 },
+}
+
+impl Midi2Message {
+  /// opcode, the upper 4 bits of status byte
+  pub fn opcode(&self) -> u8 {
+    match self {
+      Self::RegisteredPerNoteController { .. } => 0x0,
+      Self::AssignablePerNoteController { .. } => 0x1,
+      Self::RegisteredController { .. } => 0x2,
+      Self::AssignableController { .. } => 0x3,
+      Self::RelativeRegisteredController { .. } => 0x4,
+      Self::RelativeAssignableController { .. } => 0x5,
+      Self::PerNotePitchBend { .. } => 0x6,
+      Self::NoteOff { .. } => 0x8,
+      Self::NoteOn { .. } => 0x9,
+      Self::PolyPressure { .. } => 0xA,
+      Self::ControlChange { .. } => 0xB,
+      Self::ProgramChange { .. } => 0xC,
+      Self::ChannelPressure { .. } => 0xD,
+      Self::PitchBend { .. } => 0xE,
+      Self::PerNoteManagement { .. } => 0xF,
+    }
+  }
+}
+
+/// MIDI 2.0 Channel Voice message with group and channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Midi2ChannelVoice {
+  group : u8,
+  channel : u8,
+  message : Midi2Message,
+}
+
+impl Midi2ChannelVoice {
+  pub fn new(group : u8, channel : u8, message : Midi2Message) -> Self {
+    Self { group : group & 0xF, channel : channel & 0xF, message }
+  }
+
+  pub fn group(&self) -> u8 { self.group }
+
+  pub fn channel(&self) -> u8 { self.channel }
+
+  pub fn message(&self) -> &Midi2Message { &self.message }
+
+  pub(crate) fn from_words(words : &[u32]) -> Result<Self, String> {
+    let (first, data) = (words[0], words[1]);
+    let (byte3, byte4) = (field(first, 8, 8) as u8, field(first, 0, 8) as u8);
+    let note = byte3 & 0x7F;
+    let (bank, index) = (byte3 & 0x7F, byte4 & 0x7F);
+
+    let message = match field(first, 20, 4) {
+      0x0 => Midi2Message::RegisteredPerNoteController { note, index : byte4, value : data },
+      0x1 => Midi2Message::AssignablePerNoteController { note, index : byte4, value : data },
+      0x2 => Midi2Message::RegisteredController { bank, index, value : data },
+      0x3 => Midi2Message::AssignableController { bank, index, value : data },
+      0x4 => Midi2Message::RelativeRegisteredController { bank, index, value : data as i32 },
+      0x5 => Midi2Message::RelativeAssignableController { bank, index, value : data as i32 },
+      0x6 => Midi2Message::PerNotePitchBend { note, value : data },
+      0x8 => Midi2Message::NoteOff { note, velocity : (data >> 16) as u16, attribute_type : byte4, attribute : data as u16 },
+      0x9 => Midi2Message::NoteOn { note, velocity : (data >> 16) as u16, attribute_type : byte4, attribute : data as u16 },
+      0xA => Midi2Message::PolyPressure { note, value : data },
+      0xB => Midi2Message::ControlChange { index : byte3 & 0x7F, value : data },
+      0xC => Midi2Message::ProgramChange {
+        program : field(data, 24, 7) as u8,
+        bank : (byte4 & 1 == 1).then_some((field(data, 8, 7) as u8, field(data, 0, 7) as u8)),
+      },
+      0xD => Midi2Message::ChannelPressure { value : data },
+      0xE => Midi2Message::PitchBend { value : data },
+      0xF => Midi2Message::PerNoteManagement { note, detach : byte4 & 0b10 != 0, reset : byte4 & 0b01 != 0 },
+      opcode => return Err(format!("unknown MIDI 2.0 channel voice opcode 0x{:X}", opcode)),
+    };
+    Ok(Self::new(field(first, 24, 4) as u8, field(first, 16, 4) as u8, message))
+  }
+
+  pub fn words(&self) -> [u32; 2] {
+    let (byte3, byte4, data) = match &self.message {
+      Midi2Message::NoteOff { note, velocity, attribute_type, attribute }
+      | Midi2Message::NoteOn { note, velocity, attribute_type, attribute } => (*note, *attribute_type, (*velocity as u32) << 16 | *attribute as u32),
+      Midi2Message::PolyPressure { note, value }
+      | Midi2Message::PerNotePitchBend { note, value } => (*note, 0, *value),
+      Midi2Message::RegisteredPerNoteController { note, index, value }
+      | Midi2Message::AssignablePerNoteController { note, index, value } => (*note, *index, *value),
+      Midi2Message::PerNoteManagement { note, detach, reset } => (*note, (*detach as u8) << 1 | *reset as u8, 0),
+      Midi2Message::ControlChange { index, value } => (*index, 0, *value),
+      Midi2Message::RegisteredController { bank, index, value }
+      | Midi2Message::AssignableController { bank, index, value } => (*bank, *index, *value),
+      Midi2Message::RelativeRegisteredController { bank, index, value }
+      | Midi2Message::RelativeAssignableController { bank, index, value } => (*bank, *index, *value as u32),
+      Midi2Message::ProgramChange { program, bank } => {
+        let (msb, lsb) = bank.unwrap_or((0, 0));
+        (0, bank.is_some() as u8, (*program as u32 & 0x7F) << 24 | (msb as u32 & 0x7F) << 8 | lsb as u32 & 0x7F)
+      },
+      Midi2Message::ChannelPressure { value }
+      | Midi2Message::PitchBend { value } => (0, 0, *value),
+    };
+    // note numbers, controller banks and indexes of MIDI 1.0 origin are 7 bits
+    let byte3 = byte3 & 0x7F;
+    let byte4 = match self.message.opcode() {
+      0x2 ..= 0x5 => byte4 & 0x7F,
+      _ => byte4,
+    };
+    [
+      0x4 << 28 | (self.group as u32) << 24 | (self.message.opcode() as u32) << 20 | (self.channel as u32) << 16 | (byte3 as u32) << 8 | byte4 as u32,
+      data
+    ]
+  }
+}
@@ -0,0 +1,75 @@
+use crate::model::core::midi_header::DEFAULT_DIVISION;
+
+use super::Ump;
+
+/// file identifier of MIDI Clip File, the first 8 bytes
+pub const CLIP_FILE_ID : &[u8; 8] = b"SMF2CLIP";
+
+/// MIDI Clip File (`.midi2`), single sequence of UMP with delta times in ticks.
+///
+/// File is `SMF2CLIP` followed by UMP words in big endian order. Clip header holds
+/// Delta Clockstamp Ticks Per Quarter Note and configuration messages, e.g. project name as Flex Data.
+/// Sequence starts with Start of Clip and ends with End of Clip, and every packet in it is preceded
+/// by Delta Clockstamp with ticks since the previous packet.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MidiClip {
+  ticks_per_quarter : u16,
+  header : Vec<Ump>,
+  /// packets with ticks since previous packet
+  events : Vec<(u32, Ump)>,
+  /// ticks from last packet to End of Clip
+  end_delta : u32,
+}
+
+impl Default for MidiClip {
+  fn default() -> Self {
+    Self::new(DEFAULT_DIVISION)
+  }
+}
+
+impl MidiClip {
+  pub fn new(ticks_per_quarter : u16) -> Self {
+    Self { ticks_per_quarter, header : Vec::new(), events : Vec::new(), end_delta : 0 }
+  }
+
+  /// resolution of delta times, from Delta Clockstamp Ticks Per Quarter Note of clip header
+  pub fn ticks_per_quarter(&self) -> u16 { self.ticks_per_quarter }
+
+  /// configuration messages of clip header, before Start of Clip
+  pub fn header(&self) -> &[Ump] { &self.header }
+
+  /// packets of sequence with ticks since previous packet, without Start and End of Clip
+  pub fn events(&self) -> &[(u32, Ump)] { &self.events }
+
+  /// ticks from last packet to End of Clip
+  pub fn end_delta(&self) -> u32 { self.end_delta }
+
+  /// adds configuration message to clip header
+  pub fn add_header(&mut self, ump : Ump) {
+    self.header.push(ump);
+  }
+
+  /// adds packet `delta` ticks after the last packet
+  pub fn add_event(&mut self, delta : u32, ump : Ump) {
+    self.events.push((delta, ump));
+  }
+
+  /// sets End of Clip `delta` ticks after the last packet
+  pub fn set_end_delta(&mut self, delta : u32) {
+    self.end_delta = delta;
+  }
+
+  /// packets along with absolute tick from Start of Clip
+  pub fn iter_absolute(&self) -> impl Iterator<Item = (u64, &Ump)> {
+    self.events.iter().scan(0u64, |tick, (delta, ump)| {
+      *tick += *delta as u64;
+      Some((*tick, ump))
+    })
+  }
+
+  /// ticks from Start of Clip to End of Clip
+  pub fn length(&self) -> u64 {
+    self.events.iter().map(|(delta, _)| *delta as u64).sum::<u64>() + self.end_delta as u64
+  }
+}
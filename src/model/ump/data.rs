@@ -0,0 +1,155 @@
+use super::field;
+
+/// Position of packet in System Exclusive message split over many packets,
+/// also used as format of Flex Data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SysExStatus {
+  /// whole message in single packet
+  Complete = 0,
+  Start = 1,
+  Continue = 2,
+  End = 3,
+}
+
+impl SysExStatus {
+  pub(crate) fn from_bits(bits : u32) -> Self {
+    match bits & 0x3 {
+      0 => Self::Complete,
+      1 => Self::Start,
+      2 => Self::Continue,
+      _ => Self::End,
+    }
+  }
+
+  /// status of packet `index` among `count` packets
+  pub(crate) fn of(index : usize, count : usize) -> Self {
+    match (index, count) {
+      (_, 0 | 1) => Self::Complete,
+      (0, _) => Self::Start,
+      (index, count) if index + 1 == count => Self::End,
+      _ => Self::Continue,
+    }
+  }
+}
+
+/// System Exclusive 7 bit packet (type 0x3), up to 6 bytes of message without `F0` and `F7`, 64 bits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SysEx7 {
+  group : u8,
+  status : SysExStatus,
+  data : Vec<u8>,
+}
+
+impl SysEx7 {
+  /// bytes in single packet
+  pub const PACKET_LEN : usize = 6;
+
+  /// Packet with first 6 `data` bytes, bytes are masked to 7 bits.
+  pub fn new(group : u8, status : SysExStatus, data : &[u8]) -> Self {
+    let data = data.iter().take(Self::PACKET_LEN).map(|byte| byte & 0x7F).collect();
+    Self { group : group & 0xF, status, data }
+  }
+
+  /// Splits System Exclusive message, without `F0` and `F7`, in packets.
+  pub fn packets(group : u8, data : &[u8]) -> Vec<Self> {
+    let chunks : Vec<&[u8]> = match data.is_empty() {
+      true => vec![&[]],
+      false => data.chunks(Self::PACKET_LEN).collect(),
+    };
+    let count = chunks.len();
+    chunks.into_iter().enumerate().map(|(index, chunk)| Self::new(group, SysExStatus::of(index, count), chunk)).collect()
+  }
+
+  /// Joins data of packets back to System Exclusive message, without `F0` and `F7`.
+  pub fn join(packets : &[Self]) -> Vec<u8> {
+    packets.iter().flat_map(|packet| packet.data.iter().copied()).collect()
+  }
+
+  pub fn group(&self) -> u8 { self.group }
+
+  pub fn status(&self) -> SysExStatus { self.status }
+
+  pub fn data(&self) -> &[u8] { &self.data }
+
+  pub(crate) fn from_words(words : &[u32]) -> Result<Self, String> {
+    let len = field(words[0], 16, 4) as usize;
+    if len > Self::PACKET_LEN {
+      return Err(format!("SysEx7 packet holds up to {} bytes, found {}", Self::PACKET_LEN, len));
+    }
+    let bytes = [words[0].to_be_bytes(), words[1].to_be_bytes()].concat();
+    Ok(Self::new(field(words[0], 24, 4) as u8, SysExStatus::from_bits(field(words[0], 20, 4)), &bytes[2 .. 2 + len]))
+  }
+
+  pub fn words(&self) -> [u32; 2] {
+    let mut bytes = [0u8; 8];
+    bytes[0] = 0x3 << 4 | self.group;
+    bytes[1] = (self.status as u8) << 4 | self.data.len() as u8;
+    bytes[2 .. 2 + self.data.len()].copy_from_slice(&self.data);
+    [u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]), u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]])]
+  }
+}
+
+/// System Exclusive 8 bit packet (type 0x5), stream id and up to 13 bytes of message, 128 bits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SysEx8 {
+  group : u8,
+  status : SysExStatus,
+  stream_id : u8,
+  data : Vec<u8>,
+}
+
+impl SysEx8 {
+  /// bytes in single packet, besides stream id
+  pub const PACKET_LEN : usize = 13;
+
+  /// Packet with first 13 `data` bytes
+  pub fn new(group : u8, status : SysExStatus, stream_id : u8, data : &[u8]) -> Self {
+    Self { group : group & 0xF, status, stream_id, data : data.iter().take(Self::PACKET_LEN).copied().collect() }
+  }
+
+  /// Splits System Exclusive message in packets of stream `stream_id`.
+  pub fn packets(group : u8, stream_id : u8, data : &[u8]) -> Vec<Self> {
+    let chunks : Vec<&[u8]> = match data.is_empty() {
+      true => vec![&[]],
+      false => data.chunks(Self::PACKET_LEN).collect(),
+    };
+    let count = chunks.len();
+    chunks.into_iter().enumerate().map(|(index, chunk)| Self::new(group, SysExStatus::of(index, count), stream_id, chunk)).collect()
+  }
+
+  /// Joins data of packets back to System Exclusive message.
+  pub fn join(packets : &[Self]) -> Vec<u8> {
+    packets.iter().flat_map(|packet| packet.data.iter().copied()).collect()
+  }
+
+  pub fn group(&self) -> u8 { self.group }
+
+  pub fn status(&self) -> SysExStatus { self.status }
+
+  pub fn stream_id(&self) -> u8 { self.stream_id }
+
+  pub fn data(&self) -> &[u8] { &self.data }
+
+  pub(crate) fn from_words(words : &[u32]) -> Result<Self, String> {
+    // number of bytes counts stream id too
+    let len = field(words[0], 16, 4) as usize;
+    if !(1 ..= Self::PACKET_LEN + 1).contains(&len) {
+      return Err(format!("SysEx8 packet holds 1 to {} bytes with stream id, found {}", Self::PACKET_LEN + 1, len));
+    }
+    let bytes : Vec<u8> = words.iter().flat_map(|word| word.to_be_bytes()).collect();
+    Ok(Self::new(field(words[0], 24, 4) as u8, SysExStatus::from_bits(field(words[0], 20, 4)), bytes[2], &bytes[3 .. 2 + len]))
+  }
+
+  pub fn words(&self) -> [u32; 4] {
+    let mut bytes = [0u8; 16];
+    bytes[0] = 0x5 << 4 | self.group;
+    bytes[1] = (self.status as u8) << 4 | (self.data.len() + 1) as u8;
+    bytes[2] = self.stream_id;
+    bytes[3 .. 3 + self.data.len()].copy_from_slice(&self.data);
+    let word = |index : usize| u32::from_be_bytes([bytes[index * 4], bytes[index * 4 + 1], bytes[index * 4 + 2], bytes[index * 4 + 3]]);
+    [word(0), word(1), word(2), word(3)]
+  }
+}
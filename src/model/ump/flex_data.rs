@@ -0,0 +1,132 @@
+use super::{field, data::SysExStatus};
+
+/// Flex Data message (type 0xD), 128 bits, addressed to a channel or to the whole group.
+///
+/// Message is told by status bank and status, e.g. bank 0 status 0 is Set Tempo,
+/// payload is the 3 words after the first. Text longer than 12 bytes is split over many packets,
+/// with format telling position of packet like [`SysExStatus`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlexData {
+  group : u8,
+  format : SysExStatus,
+  /// channel, `None` when addressed to the group
+  channel : Option<u8>,
+  status_bank : u8,
+  status : u8,
+  payload : [u32; 3],
+}
+
+impl FlexData {
+  /// bytes of text in single packet
+  pub const TEXT_LEN : usize = 12;
+
+  /// status bank of setup and performance messages, like tempo and time signature
+  pub const SETUP_AND_PERFORMANCE : u8 = 0x00;
+  /// status bank of metadata text, like project name and composer
+  pub const METADATA_TEXT : u8 = 0x01;
+  /// status bank of performance text, like lyrics
+  pub const PERFORMANCE_TEXT : u8 = 0x02;
+
+  pub fn new(group : u8, format : SysExStatus, channel : Option<u8>, status_bank : u8, status : u8, payload : [u32; 3]) -> Self {
+    Self { group : group & 0xF, format, channel : channel.map(|channel| channel & 0xF), status_bank, status, payload }
+  }
+
+  /// Set Tempo, in 10 nanosecond units per quarter note
+  pub fn tempo(group : u8, ten_nanos_per_quarter : u32) -> Self {
+    Self::new(group, SysExStatus::Complete, None, Self::SETUP_AND_PERFORMANCE, 0x00, [ten_nanos_per_quarter, 0, 0])
+  }
+
+  /// Set Time Signature, `denominator` is negative power of two like in Standard Midi File, e.g. 3 for eighth note
+  pub fn time_signature(group : u8, numerator : u8, denominator : u8, notated_32nds : u8) -> Self {
+    let word = (numerator as u32) << 24 | (denominator as u32) << 16 | (notated_32nds as u32) << 8;
+    Self::new(group, SysExStatus::Complete, None, Self::SETUP_AND_PERFORMANCE, 0x01, [word, 0, 0])
+  }
+
+  /// Set Key Signature, `sharps` is number of sharps (positive) or flats (negative) from -7 to 7,
+  /// `tonic` is 1 to 7 for A to G, 0 when unknown
+  pub fn key_signature(group : u8, channel : Option<u8>, sharps : i8, tonic : u8) -> Self {
+    let word = ((sharps as u32 & 0xF) << 4 | tonic as u32 & 0xF) << 24;
+    Self::new(group, SysExStatus::Complete, channel, Self::SETUP_AND_PERFORMANCE, 0x05, [word, 0, 0])
+  }
+
+  /// Splits UTF-8 `text` in packets of text message `status` of `status_bank`, e.g. status 1 of [`FlexData::METADATA_TEXT`] is project name.
+  pub fn texts(group : u8, channel : Option<u8>, status_bank : u8, status : u8, text : &str) -> Vec<Self> {
+    let chunks : Vec<&[u8]> = match text.is_empty() {
+      true => vec![&[]],
+      false => text.as_bytes().chunks(Self::TEXT_LEN).collect(),
+    };
+    let count = chunks.len();
+    chunks.into_iter().enumerate().map(|(index, chunk)| {
+      let mut bytes = [0u8; Self::TEXT_LEN];
+      bytes[..chunk.len()].copy_from_slice(chunk);
+      let word = |index : usize| u32::from_be_bytes([bytes[index * 4], bytes[index * 4 + 1], bytes[index * 4 + 2], bytes[index * 4 + 3]]);
+      Self::new(group, SysExStatus::of(index, count), channel, status_bank, status, [word(0), word(1), word(2)])
+    }).collect()
+  }
+
+  pub fn group(&self) -> u8 { self.group }
+
+  pub fn format(&self) -> SysExStatus { self.format }
+
+  pub fn channel(&self) -> Option<u8> { self.channel }
+
+  pub fn status_bank(&self) -> u8 { self.status_bank }
+
+  pub fn status(&self) -> u8 { self.status }
+
+  pub fn payload(&self) -> &[u32; 3] { &self.payload }
+
+  /// tempo of Set Tempo, in 10 nanosecond units per quarter note
+  pub fn get_tempo(&self) -> Option<u32> {
+    (self.status_bank == Self::SETUP_AND_PERFORMANCE && self.status == 0x00).then_some(self.payload[0])
+  }
+
+  /// (numerator, denominator as negative power of two, notated 32nd notes) of Set Time Signature
+  pub fn get_time_signature(&self) -> Option<(u8, u8, u8)> {
+    let word = self.payload[0];
+    (self.status_bank == Self::SETUP_AND_PERFORMANCE && self.status == 0x01).then_some(((word >> 24) as u8, (word >> 16) as u8, (word >> 8) as u8))
+  }
+
+  /// (sharps or flats, tonic) of Set Key Signature
+  pub fn get_key_signature(&self) -> Option<(i8, u8)> {
+    let byte = (self.payload[0] >> 24) as u8;
+    // sharps are signed 4 bits
+    let sharps = ((byte as i8) >> 4).clamp(-7, 7);
+    (self.status_bank == Self::SETUP_AND_PERFORMANCE && self.status == 0x05).then_some((sharps, byte & 0xF))
+  }
+
+  /// text bytes of text message packet, without padding
+  pub fn text_bytes(&self) -> Vec<u8> {
+    if self.status_bank != Self::METADATA_TEXT && self.status_bank != Self::PERFORMANCE_TEXT { return Vec::new(); }
+    let mut bytes : Vec<u8> = self.payload.iter().flat_map(|word| word.to_be_bytes()).collect();
+    while bytes.last() == Some(&0) { bytes.pop(); }
+    bytes
+  }
+
+  /// Joins text of packets split by [`FlexData::texts`].
+  pub fn join_text(packets : &[Self]) -> String {
+    let bytes : Vec<u8> = packets.iter().flat_map(|packet| packet.text_bytes()).collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+  }
+
+  pub(crate) fn from_words(words : &[u32]) -> Result<Self, String> {
+    let first = words[0];
+    let channel = match field(first, 20, 2) {
+      0 => Some(field(first, 16, 4) as u8),
+      1 => None,
+      address => return Err(format!("reserved Flex Data address 0x{:X}", address)),
+    };
+    Ok(Self::new(
+      field(first, 24, 4) as u8, SysExStatus::from_bits(field(first, 22, 2)), channel,
+      field(first, 8, 8) as u8, field(first, 0, 8) as u8, [words[1], words[2], words[3]]
+    ))
+  }
+
+  pub fn words(&self) -> [u32; 4] {
+    let address = match self.channel { Some(channel) => channel as u32, None => 1 << 4 };
+    let first = 0xD << 28 | (self.group as u32) << 24 | (self.format as u32) << 22 | address << 16
+      | (self.status_bank as u32) << 8 | self.status as u32;
+    [first, self.payload[0], self.payload[1], self.payload[2]]
+  }
+}
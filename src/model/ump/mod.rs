@@ -0,0 +1,184 @@
+//! MIDI 2.0 Universal MIDI Packet (UMP), messages of 32, 64, 96 or 128 bits sent as 32 bit words.
+//!
+//! First 4 bits of every packet are its message type, which gives its length:
+//!
+//! | type | bits | messages |
+//! |------|------|----------|
+//! | 0x0  | 32   | Utility, NOOP, JR Clock / Timestamp, Delta Clockstamps |
+//! | 0x1  | 32   | System Common and System Real Time |
+//! | 0x2  | 32   | MIDI 1.0 Channel Voice |
+//! | 0x3  | 64   | Data (SysEx7) |
+//! | 0x4  | 64   | MIDI 2.0 Channel Voice |
+//! | 0x5  | 128  | Data (SysEx8) |
+//! | 0xD  | 128  | Flex Data |
+//! | 0xF  | 128  | UMP Stream |
+//!
+//! Message types reserved by the specification are kept as [`Ump::Unknown`] with their words.
+//! Conversions between MIDI 1.0 [`ChannelMessage`](crate::model::core::midi_event::channel_message::ChannelMessage)
+//! and MIDI 2.0 messages are in [`translate`], MIDI Clip File is modeled by [`clip::MidiClip`].
+
+use std::{fmt, error};
+
+pub use self::{
+  utility::Utility,
+  channel_voice::{Midi1ChannelVoice, Midi2ChannelVoice, Midi2Message},
+  data::{SysEx7, SysEx8, SysExStatus},
+  flex_data::FlexData,
+  stream::StreamMessage,
+};
+
+mod utility;
+mod channel_voice;
+mod data;
+mod flex_data;
+mod stream;
+pub mod translate;
+pub mod clip;
+
+/// Error in words of UMP, with index of the word where the failing packet starts.
+#[derive(Debug, Clone)]
+pub struct UmpError {
+  offset : usize,
+  message : String,
+}
+
+impl UmpError {
+  pub fn new(offset : usize, message : String) -> Self {
+    Self { offset, message }
+  }
+
+  /// index of first word of failing packet, or byte offset for errors of clip file
+  pub fn offset(&self) -> usize { self.offset }
+
+  pub fn message(&self) -> &str { &self.message }
+}
+
+impl fmt::Display for UmpError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "at {}: {}", self.offset, self.message)
+  }
+}
+
+impl error::Error for UmpError {}
+
+/// Universal MIDI Packet
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Ump {
+  Utility(Utility),
+  /// System Common or Real Time message, as status byte and 2 data bytes
+  System { group : u8, status : u8, data : [u8; 2] },
+  Midi1ChannelVoice(Midi1ChannelVoice),
+  SysEx7(SysEx7),
+  Midi2ChannelVoice(Midi2ChannelVoice),
+  SysEx8(SysEx8),
+  FlexData(FlexData),
+  Stream(StreamMessage),
+  /// packet of reserved message type
+  Unknown(Vec<u32>),
+}
+
+impl Ump {
+  /// number of 32 bit words of packet with `message_type`
+  pub fn word_len(message_type : u8) -> usize {
+    match message_type & 0xF {
+      0x0 | 0x1 | 0x2 | 0x6 | 0x7 => 1,
+      0x3 | 0x4 | 0x8 | 0x9 | 0xA => 2,
+      0xB | 0xC => 3,
+      _ => 4,
+    }
+  }
+
+  /// message type, first 4 bits of packet
+  pub fn message_type(&self) -> u8 {
+    match self {
+      Self::Utility(_) => 0x0,
+      Self::System { .. } => 0x1,
+      Self::Midi1ChannelVoice(_) => 0x2,
+      Self::SysEx7(_) => 0x3,
+      Self::Midi2ChannelVoice(_) => 0x4,
+      Self::SysEx8(_) => 0x5,
+      Self::FlexData(_) => 0xD,
+      Self::Stream(_) => 0xF,
+      Self::Unknown(words) => (words.first().copied().unwrap_or_default() >> 28) as u8,
+    }
+  }
+
+  /// group of packet, `None` for groupless Utility and Stream messages
+  pub fn group(&self) -> Option<u8> {
+    match self {
+      Self::Utility(_) | Self::Stream(_) => None,
+      Self::System { group, .. } => Some(*group),
+      Self::Midi1ChannelVoice(message) => Some(message.group()),
+      Self::SysEx7(message) => Some(message.group()),
+      Self::Midi2ChannelVoice(message) => Some(message.group()),
+      Self::SysEx8(message) => Some(message.group()),
+      Self::FlexData(message) => Some(message.group()),
+      Self::Unknown(words) => Some((words.first().copied().unwrap_or_default() >> 24) as u8 & 0xF),
+    }
+  }
+
+  /// Parses the packet at start of `words`, returns it along with number of words it takes.
+  pub fn from_words(words : &[u32]) -> Result<(Self, usize), UmpError> {
+    let first = *words.first().ok_or_else(|| UmpError::new(0, String::from("no words left")))?;
+    let message_type = (first >> 28) as u8;
+    let len = Self::word_len(message_type);
+    if words.len() < len {
+      return Err(UmpError::new(0, format!("message type 0x{:X} needs {} words, found {}", message_type, len, words.len())));
+    }
+    let words = &words[..len];
+    let error = |message : String| UmpError::new(0, message);
+
+    let ump = match message_type {
+      0x0 => Self::Utility(Utility::from_word(first).map_err(error)?),
+      0x1 => Self::System { group : (first >> 24) as u8 & 0xF, status : (first >> 16) as u8, data : [(first >> 8) as u8 & 0x7F, first as u8 & 0x7F] },
+      0x2 => Self::Midi1ChannelVoice(Midi1ChannelVoice::from_word(first).map_err(error)?),
+      0x3 => Self::SysEx7(SysEx7::from_words(words).map_err(error)?),
+      0x4 => Self::Midi2ChannelVoice(Midi2ChannelVoice::from_words(words).map_err(error)?),
+      0x5 if (first >> 20) & 0xF <= 3 => Self::SysEx8(SysEx8::from_words(words).map_err(error)?),
+      0xD => Self::FlexData(FlexData::from_words(words).map_err(error)?),
+      0xF => Self::Stream(StreamMessage::from_words(words)),
+      _ => Self::Unknown(words.to_vec()),
+    };
+    Ok((ump, len))
+  }
+
+  /// Parses every packet of `words`, error offset is the index of first word of failing packet.
+  pub fn parse_all(words : &[u32]) -> Result<Vec<Self>, UmpError> {
+    let mut packets = Vec::new();
+    let mut offset = 0;
+    while offset < words.len() {
+      let (ump, len) = Self::from_words(&words[offset..]).map_err(|err| UmpError::new(offset, err.message))?;
+      packets.push(ump);
+      offset += len;
+    }
+    Ok(packets)
+  }
+
+  /// words of packet
+  pub fn words(&self) -> Vec<u32> {
+    match self {
+      Self::Utility(message) => vec![message.word()],
+      Self::System { group, status, data } => vec![0x1 << 28 | (*group as u32 & 0xF) << 24 | (*status as u32) << 16 | (data[0] as u32 & 0x7F) << 8 | data[1] as u32 & 0x7F],
+      Self::Midi1ChannelVoice(message) => vec![message.word()],
+      Self::SysEx7(message) => message.words().to_vec(),
+      Self::Midi2ChannelVoice(message) => message.words().to_vec(),
+      Self::SysEx8(message) => message.words().to_vec(),
+      Self::FlexData(message) => message.words().to_vec(),
+      Self::Stream(message) => message.words().to_vec(),
+      Self::Unknown(words) => words.clone(),
+    }
+  }
+}
+
+/// Converts packet to bytes, words in big endian order as in MIDI Clip File.
+impl From<&Ump> for Vec<u8> {
+  fn from(ump : &Ump) -> Self {
+    ump.words().iter().flat_map(|word| word.to_be_bytes()).collect()
+  }
+}
+
+/// `bits` of `value` from bit `shift`
+fn field(value : u32, shift : u32, bits : u32) -> u32 {
+  (value >> shift) & ((1 << bits) - 1)
+}
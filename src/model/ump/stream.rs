@@ -0,0 +1,39 @@
+use super::field;
+
+/// UMP Stream message (type 0xF), groupless, 128 bits.
+///
+/// Only Start of Clip and End of Clip, which mark the sequence of MIDI Clip File, are modeled,
+/// other stream messages (endpoint and function block discovery etc.) are kept as words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StreamMessage {
+  StartOfClip,
+  EndOfClip,
+  Other([u32; 4]),
+}
+
+impl StreamMessage {
+  /// 10 bit status of the message
+  pub fn status(&self) -> u16 {
+    match self {
+      Self::StartOfClip => 0x20,
+      Self::EndOfClip => 0x21,
+      Self::Other(words) => field(words[0], 16, 10) as u16,
+    }
+  }
+
+  pub(crate) fn from_words(words : &[u32]) -> Self {
+    match (field(words[0], 26, 2), field(words[0], 16, 10)) {
+      (0, 0x20) => Self::StartOfClip,
+      (0, 0x21) => Self::EndOfClip,
+      _ => Self::Other([words[0], words[1], words[2], words[3]]),
+    }
+  }
+
+  pub fn words(&self) -> [u32; 4] {
+    match self {
+      Self::Other(words) => *words,
+      _ => [0xF << 28 | (self.status() as u32) << 16, 0, 0, 0],
+    }
+  }
+}
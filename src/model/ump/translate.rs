@@ -0,0 +1,170 @@
+//! Translation between MIDI 1.0 channel voice messages and MIDI 2.0 channel voice messages,
+//! following the default translation of the UMP specification.
+//!
+//! Values are scaled up with min-center-max scaling, so minimum, center and maximum of MIDI 1.0 value
+//! land on minimum, center and maximum of MIDI 2.0 value, e.g. velocity 64 becomes `0x8000`
+//! and 127 becomes `0xFFFF`. Values are scaled down by dropping the low bits.
+//!
+//! MIDI 1.0 Bank Select, RPN / NRPN selection and Data Entry controllers are stateful,
+//! so MIDI 1.0 to MIDI 2.0 goes through [`Midi1ToMidi2`], which keeps state of every group and channel.
+
+use std::collections::HashMap;
+
+use crate::model::core::midi_event::channel_message::ChannelMessage;
+
+use super::{Midi2ChannelVoice, Midi2Message};
+
+/// Scales `value` of `src_bits` up to `dst_bits` with min-center-max scaling.
+pub fn scale_up(value : u32, src_bits : u32, dst_bits : u32) -> u32 {
+  let scale_bits = dst_bits - src_bits;
+  let shifted = value << scale_bits;
+  let center = 1 << (src_bits - 1);
+  if value <= center || src_bits < 2 {
+    return shifted;
+  }
+
+  // bits below the highest are repeated to fill the low bits
+  let repeat_bits = src_bits - 1;
+  let mut repeat = value & ((1 << repeat_bits) - 1);
+  repeat = match scale_bits > repeat_bits {
+    true => repeat << (scale_bits - repeat_bits),
+    false => repeat >> (repeat_bits - scale_bits),
+  };
+  let mut scaled = shifted;
+  while repeat != 0 {
+    scaled |= repeat;
+    repeat >>= repeat_bits;
+  }
+  scaled
+}
+
+/// Scales `value` of `src_bits` down to `dst_bits`, dropping the low bits.
+pub fn scale_down(value : u32, src_bits : u32, dst_bits : u32) -> u32 {
+  value >> (src_bits - dst_bits)
+}
+
+/// state of Bank Select and parameter selection of single channel
+#[derive(Debug, Clone, Copy, Default)]
+struct ChannelState {
+  /// (msb, lsb) of Bank Select
+  bank : Option<(u8, u8)>,
+  /// true for RPN, false for NRPN
+  registered : bool,
+  /// (msb, lsb) of selected RPN / NRPN, both 127 is null
+  parameter : Option<(u8, u8)>,
+  data_msb : u8,
+}
+
+/// Translates MIDI 1.0 channel voice messages to MIDI 2.0, keeping state of controllers.
+///
+/// - Note On with velocity 0 becomes Note Off with velocity `0x8000`
+/// - Bank Select (CC 0 / 32) is held and sent along with next Program Change
+/// - RPN (CC 101 / 100) and NRPN (CC 99 / 98) select the parameter of following Data Entry (CC 6 / 38),
+///   which becomes Registered / Assignable Controller; Data Entry MSB is sent at once, and again along with LSB
+/// - other controllers become Control Change
+#[derive(Debug, Clone, Default)]
+pub struct Midi1ToMidi2 {
+  channels : HashMap<(u8, u8), ChannelState>,
+}
+
+impl Midi1ToMidi2 {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// MIDI 2.0 message of `message` sent on `group`, `None` when it only changes state, like Bank Select
+  pub fn translate(&mut self, group : u8, message : &ChannelMessage) -> Option<Midi2ChannelVoice> {
+    let bytes : Vec<u8> = message.clone().into();
+    let status = *bytes.first()?;
+    let channel = status & 0xF;
+    let data = |index : usize| bytes.get(index).copied().unwrap_or_default() as u32;
+    let state = self.channels.entry((group & 0xF, channel)).or_default();
+
+    let message = match status & 0xF0 {
+      0x80 => Midi2Message::NoteOff { note : data(1) as u8, velocity : scale_up(data(2), 7, 16) as u16, attribute_type : 0, attribute : 0 },
+      0x90 if data(2) == 0 => Midi2Message::NoteOff { note : data(1) as u8, velocity : 0x8000, attribute_type : 0, attribute : 0 },
+      0x90 => Midi2Message::NoteOn { note : data(1) as u8, velocity : scale_up(data(2), 7, 16) as u16, attribute_type : 0, attribute : 0 },
+      0xA0 => Midi2Message::PolyPressure { note : data(1) as u8, value : scale_up(data(2), 7, 32) },
+      0xB0 => return Self::controller(state, data(1) as u8, data(2) as u8).map(|message| Midi2ChannelVoice::new(group, channel, message)),
+      0xC0 => Midi2Message::ProgramChange { program : data(1) as u8, bank : state.bank },
+      0xD0 => Midi2Message::ChannelPressure { value : scale_up(data(1), 7, 32) },
+      0xE0 => Midi2Message::PitchBend { value : scale_up(data(1) | data(2) << 7, 14, 32) },
+      _ => return None,
+    };
+    Some(Midi2ChannelVoice::new(group, channel, message))
+  }
+
+  fn controller(state : &mut ChannelState, index : u8, value : u8) -> Option<Midi2Message> {
+    let parameter = state.parameter.filter(|parameter| *parameter != (127, 127));
+    match (index, parameter) {
+      (0, _) => { state.bank = Some((value, state.bank.map_or(0, |bank| bank.1))); None },
+      (32, _) => { state.bank = Some((state.bank.map_or(0, |bank| bank.0), value)); None },
+      (101 | 99, _) => {
+        state.registered = index == 101;
+        state.parameter = Some((value, state.parameter.map_or(0, |parameter| parameter.1)));
+        None
+      },
+      (100 | 98, _) => {
+        state.registered = index == 100;
+        state.parameter = Some((state.parameter.map_or(0, |parameter| parameter.0), value));
+        None
+      },
+      (6, Some((bank, index))) => {
+        state.data_msb = value;
+        Some(Self::parameter(state.registered, bank, index, (value as u32) << 7))
+      },
+      (38, Some((bank, index))) => Some(Self::parameter(state.registered, bank, index, (state.data_msb as u32) << 7 | value as u32)),
+      (index, _) => Some(Midi2Message::ControlChange { index, value : scale_up(value as u32, 7, 32) }),
+    }
+  }
+
+  fn parameter(registered : bool, bank : u8, index : u8, value : u32) -> Midi2Message {
+    let value = scale_up(value, 14, 32);
+    match registered {
+      true => Midi2Message::RegisteredController { bank, index, value },
+      false => Midi2Message::AssignableController { bank, index, value },
+    }
+  }
+}
+
+impl Midi2ChannelVoice {
+  /// Translates to MIDI 1.0 messages, empty for messages without MIDI 1.0 counterpart
+  /// (per-note controllers, per-note pitch bend, per-note management and relative controllers).
+  ///
+  /// Note On velocity scaled down to 0 is sent as 1, so it isn't read as Note Off.
+  /// Registered / Assignable Controller becomes RPN / NRPN selection followed by Data Entry MSB and LSB,
+  /// Program Change with bank becomes Bank Select MSB and LSB followed by Program Change.
+  pub fn to_midi1(&self) -> Vec<ChannelMessage> {
+    let channel = self.channel();
+    let message = |status : u8, data : &[u8]| ChannelMessage::from((status | channel, data));
+    let control = |index : u8, value : u32| message(0xB0, &[index, value as u8 & 0x7F]);
+
+    match self.message() {
+      Midi2Message::NoteOff { note, velocity, .. } => vec![message(0x80, &[*note, scale_down(*velocity as u32, 16, 7) as u8])],
+      Midi2Message::NoteOn { note, velocity, .. } => vec![message(0x90, &[*note, scale_down(*velocity as u32, 16, 7).max(1) as u8])],
+      Midi2Message::PolyPressure { note, value } => vec![message(0xA0, &[*note, scale_down(*value, 32, 7) as u8])],
+      Midi2Message::ControlChange { index, value } => vec![control(*index, scale_down(*value, 32, 7))],
+      Midi2Message::RegisteredController { bank, index, value }
+      | Midi2Message::AssignableController { bank, index, value } => {
+        let (msb, lsb) = match self.message() { Midi2Message::RegisteredController { .. } => (101, 100), _ => (99, 98) };
+        let value = scale_down(*value, 32, 14);
+        vec![control(msb, *bank as u32), control(lsb, *index as u32), control(6, value >> 7), control(38, value)]
+      },
+      Midi2Message::ProgramChange { program, bank } => {
+        let mut messages = Vec::new();
+        if let Some((msb, lsb)) = bank {
+          messages.push(control(0, *msb as u32));
+          messages.push(control(32, *lsb as u32));
+        }
+        messages.push(message(0xC0, &[*program & 0x7F]));
+        messages
+      },
+      Midi2Message::ChannelPressure { value } => vec![message(0xD0, &[scale_down(*value, 32, 7) as u8])],
+      Midi2Message::PitchBend { value } => {
+        let value = scale_down(*value, 32, 14);
+        vec![message(0xE0, &[value as u8 & 0x7F, (value >> 7) as u8 & 0x7F])]
+      },
+      _ => Vec::new(),
+    }
+  }
+}
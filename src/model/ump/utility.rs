@@ -0,0 +1,45 @@
+use super::field;
+
+/// Utility message (type 0x0), groupless and 32 bits.
+///
+/// Jitter Reduction messages carry time of sender in 1/31250 seconds,
+/// Delta Clockstamps carry time in ticks, as used by MIDI Clip File.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Utility {
+  NoOp,
+  /// JR Clock, time of sender clock
+  JrClock(u16),
+  /// JR Timestamp, time at which the following message was sent
+  JrTimestamp(u16),
+  /// Delta Clockstamp Ticks Per Quarter Note, resolution of following Delta Clockstamps
+  DeltaClockstampTpq(u16),
+  /// Delta Clockstamp, ticks since last Delta Clockstamp, 20 bits
+  DeltaClockstamp(u32),
+}
+
+impl Utility {
+  /// largest ticks of single Delta Clockstamp
+  pub const MAX_DELTA_CLOCKSTAMP : u32 = 0xFFFFF;
+
+  pub(crate) fn from_word(word : u32) -> Result<Self, String> {
+    match field(word, 20, 4) {
+      0x0 => Ok(Self::NoOp),
+      0x1 => Ok(Self::JrClock(word as u16)),
+      0x2 => Ok(Self::JrTimestamp(word as u16)),
+      0x3 => Ok(Self::DeltaClockstampTpq(word as u16)),
+      0x4 => Ok(Self::DeltaClockstamp(field(word, 0, 20))),
+      status => Err(format!("unknown utility status 0x{:X}", status)),
+    }
+  }
+
+  pub fn word(&self) -> u32 {
+    match self {
+      Self::NoOp => 0,
+      Self::JrClock(time) => 0x1 << 20 | *time as u32,
+      Self::JrTimestamp(time) => 0x2 << 20 | *time as u32,
+      Self::DeltaClockstampTpq(ticks) => 0x3 << 20 | *ticks as u32,
+      Self::DeltaClockstamp(ticks) => 0x4 << 20 | ticks & Self::MAX_DELTA_CLOCKSTAMP,
+    }
+  }
+}
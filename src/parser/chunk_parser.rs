@@ -0,0 +1,131 @@
+use crate::{
+  model::core::unknown_chunk::UnknownChunk,
+  utils::{ByteEncodingFormat, functions::number}
+};
+
+use super::{
+  parser_state::ParserState,
+  error::{MidiParseError, MidiParseErrorKind}
+};
+
+/// Single chunk of SMF, 4 bytes type, 4 bytes length and data.
+#[derive(Debug, Clone)]
+pub struct Chunk<'a> {
+  id : [u8; 4],
+  offset : usize,
+  data : &'a [u8],
+}
+
+impl<'a> Chunk<'a> {
+  /// 4 byte chunk type, e.g. `MThd`, `MTrk`
+  pub fn id(&self) -> [u8; 4] { self.id }
+
+  /// byte offset of chunk start in buffer
+  pub fn offset(&self) -> usize { self.offset }
+
+  /// chunk data, without type and length
+  pub fn data(&self) -> &'a [u8] { self.data }
+
+  pub fn is_header(&self) -> bool { &self.id == b"MThd" }
+
+  pub fn is_track(&self) -> bool { &self.id == b"MTrk" }
+
+  /// Copies the chunk to owned [`UnknownChunk`]
+  pub fn to_unknown_chunk(&self) -> UnknownChunk {
+    UnknownChunk::new(self.id, self.data.to_vec())
+  }
+}
+
+/// Reads the chunks of SMF one by one, without looking into them.
+///
+/// SMF wrapped in RIFF `RMID` container (`.rmi` files) is unwrapped,
+/// so chunks of `data` chunk of RIFF are read.
+#[derive(Debug, Clone)]
+pub struct ChunkReader<'a> {
+  buf : &'a [u8],
+  state : ParserState,
+  failed : bool,
+}
+
+impl<'a> ChunkReader<'a> {
+  pub fn new(buf : &'a [u8]) -> Result<ChunkReader<'a>, MidiParseError> {
+    Ok(ChunkReader { buf, state : Self::smf_state(buf)?, failed : false })
+  }
+
+  /// start and end of SMF in buffer, i.e. whole buffer or `data` chunk of RIFF
+  pub fn smf_range(&self) -> (usize, usize) { (self.state.start(), self.state.end()) }
+
+  /// Returns the state spanning SMF data in `buf`.
+  /// For RIFF `RMID` it is `data` chunk of RIFF, otherwise whole buffer.
+  pub(crate) fn smf_state(buf : &[u8]) -> Result<ParserState, MidiParseError> {
+    if !buf.starts_with(b"RIFF") {
+      return Ok(ParserState::new(String::from("midi"), 0, buf.len()));
+    }
+
+    let riff_state = ParserState::new(String::from("riff"), 0, buf.len());
+
+    if buf.len() < 12 || &buf[8..12] != b"RMID" {
+      return Err(MidiParseError::new(
+        riff_state,
+        MidiParseErrorKind::InvalidMidiHeader,
+        String::from("RIFF container should be of 'RMID' form"),
+        None
+      ).at(8).with_expected(String::from("'RMID'")).with_found(&buf[8 .. buf.len().min(12)]));
+    }
+
+    // RIFF stores the sizes as little endian, chunks are padded to even length
+    let riff_end = (8 + Self::riff_size(&buf[4..8])).min(buf.len());
+    let mut ptr = 12;
+    while ptr + 8 <= riff_end {
+      let size = Self::riff_size(&buf[ptr + 4 .. ptr + 8]);
+      if &buf[ptr .. ptr + 4] == b"data" {
+        return Ok(ParserState::new(String::from("midi"), ptr + 8, (ptr + 8 + size).min(riff_end)));
+      }
+      ptr += 8 + size + (size & 1);
+    }
+
+    Err(MidiParseError::new(
+      riff_state.with_name(format!("riff@{}", ptr)),
+      MidiParseErrorKind::InvalidMidiHeader,
+      String::from("RIFF 'RMID' container has no 'data' chunk"),
+      None
+    ))
+  }
+
+  fn riff_size(bytes : &[u8]) -> usize {
+    u32::from_le_bytes(bytes.try_into().unwrap()) as usize
+  }
+
+  /// Reads the chunk at current position of `state` and moves the state past it.
+  pub(crate) fn read_chunk(buf : &'a [u8], state : &mut ParserState) -> Result<Chunk<'a>, MidiParseError> {
+    state.ensure(8)?;
+
+    let offset = state.curr();
+    let id = state.take(buf, 4).try_into().unwrap();
+    let length = number(&buf[offset + 4 .. offset + 8], ByteEncodingFormat::BigEndian) as usize;
+
+    let mut data_state = state.clone();
+    data_state.forward(8);
+    data_state.ensure(length)?;
+
+    state.forward(8);
+    Ok(Chunk { id, offset, data : state.next(buf, length) })
+  }
+
+  /// chunk type is made of 4 ASCII letters, digits or spaces
+  pub(crate) fn is_chunk_type(id : &[u8]) -> bool {
+    id.len() == 4 && id.iter().all(|byte| byte.is_ascii_alphanumeric() || *byte == b' ')
+  }
+}
+
+impl<'a> Iterator for ChunkReader<'a> {
+  type Item = Result<Chunk<'a>, MidiParseError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.failed || self.state.remaining() == 0 { return None; }
+
+    let chunk = Self::read_chunk(self.buf, &mut self.state);
+    self.failed = chunk.is_err();
+    Some(chunk)
+  }
+}
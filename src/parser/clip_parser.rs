@@ -0,0 +1,66 @@
+use crate::model::ump::{Ump, Utility, StreamMessage, UmpError, clip::{MidiClip, CLIP_FILE_ID}};
+
+/// Parses MIDI Clip File (`SMF2CLIP`) to MidiClip, see [`MidiClip`].
+pub struct ClipParser;
+
+impl ClipParser {
+  /// Parses clip file, error offset is byte offset of failing packet.
+  ///
+  /// Fails when file identifier, Delta Clockstamp Ticks Per Quarter Note, Start of Clip or End of Clip is missing,
+  /// or file ends in the middle of packet. Delta Clockstamps in clip header are skipped,
+  /// consecutive Delta Clockstamps in sequence are added up and NOOPs are skipped.
+  pub fn parse(buf : &[u8]) -> Result<MidiClip, UmpError> {
+    if buf.len() < CLIP_FILE_ID.len() || &buf[..CLIP_FILE_ID.len()] != CLIP_FILE_ID {
+      return Err(UmpError::new(0, String::from("expected SMF2CLIP file identifier")));
+    }
+    let body = &buf[CLIP_FILE_ID.len()..];
+    let incomplete = body.len() % 4;
+    if incomplete > 0 {
+      return Err(UmpError::new(buf.len() - incomplete, format!("file ends with {} bytes of incomplete word", incomplete)));
+    }
+    let words : Vec<u32> = body.chunks(4).map(|word| u32::from_be_bytes([word[0], word[1], word[2], word[3]])).collect();
+
+    let mut clip = None;
+    let mut started = false;
+    let mut ended = false;
+    let mut delta = 0u32;
+    let mut offset = 0;
+    while offset < words.len() {
+      let byte_offset = CLIP_FILE_ID.len() + offset * 4;
+      let (ump, len) = Ump::from_words(&words[offset..]).map_err(|err| UmpError::new(byte_offset, err.message().to_string()))?;
+      offset += len;
+
+      match (ump, clip.as_mut()) {
+        (Ump::Utility(Utility::DeltaClockstampTpq(ticks)), None) => clip = Some(MidiClip::new(ticks)),
+        (Ump::Utility(Utility::DeltaClockstampTpq(_)), Some(_)) if !started => {
+          return Err(UmpError::new(byte_offset, String::from("Delta Clockstamp Ticks Per Quarter Note is given twice")));
+        },
+        (_, None) => return Err(UmpError::new(byte_offset, String::from("expected Delta Clockstamp Ticks Per Quarter Note first in clip header"))),
+        (Ump::Utility(Utility::NoOp), _) => {},
+        (Ump::Utility(Utility::DeltaClockstamp(ticks)), _) => if started { delta = delta.saturating_add(ticks) },
+        (Ump::Stream(StreamMessage::StartOfClip), _) if !started => {
+          started = true;
+          delta = 0;
+        },
+        (Ump::Stream(StreamMessage::EndOfClip), Some(clip)) if started => {
+          clip.set_end_delta(delta);
+          ended = true;
+          break;
+        },
+        (ump, Some(clip)) if started => {
+          clip.add_event(delta, ump);
+          delta = 0;
+        },
+        (ump, Some(clip)) => clip.add_header(ump),
+      }
+    }
+
+    let message = match (clip, started, ended) {
+      (Some(clip), _, true) => return Ok(clip),
+      (None, _, _) => "Delta Clockstamp Ticks Per Quarter Note is missing",
+      (_, false, _) => "Start of Clip is missing",
+      _ => "End of Clip is missing",
+    };
+    Err(UmpError::new(buf.len(), String::from(message)))
+  }
+}
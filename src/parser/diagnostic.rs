@@ -0,0 +1,90 @@
+use std::fmt;
+
+use super::error::MidiParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+  /// midi was parsed, but doesn't follow the standard.
+  Warning,
+  /// part of midi couldn't be parsed and was dropped.
+  Error,
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Severity::Warning => write!(f, "warning"),
+      Severity::Error => write!(f, "error"),
+    }
+  }
+}
+
+/// Problem found, and recovery made by parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+  /// track failed to parse, events before the failure are kept.
+  TrackError,
+  /// number of tracks doesn't match `ntrk` of Midi Header.
+  TrackCountMismatch,
+  /// declared length of track is wrong, length was inferred.
+  TrackLengthMismatch,
+  /// track doesn't end with End Of Track, it was synthesized.
+  MissingEndOfTrack,
+  /// bytes which are not part of any chunk were skipped, parser resynced at next chunk.
+  JunkSkipped,
+  /// data bytes found where no running status applies were skipped, parser resynced at next status byte.
+  StrayDataSkipped,
+}
+
+/// Diagnostic reports problem found while parsing midi, and recovery made by parser.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+  kind : DiagnosticKind,
+  severity : Severity,
+  offset : usize,
+  track : Option<usize>,
+  message : String,
+  error : Option<MidiParseError>,
+}
+
+impl Diagnostic {
+  pub fn new(kind : DiagnosticKind, severity : Severity, offset : usize, track : Option<usize>, message : String) -> Self {
+    Self { kind, severity, offset, track, message, error : None }
+  }
+
+  /// Error diagnostic for track which failed to parse
+  pub fn track_error(track : usize, error : MidiParseError) -> Self {
+    Self {
+      kind : DiagnosticKind::TrackError,
+      severity : Severity::Error,
+      offset : error.offset(),
+      track : Some(track),
+      message : error.to_string(),
+      error : Some(error),
+    }
+  }
+
+  pub fn kind(&self) -> DiagnosticKind { self.kind }
+
+  pub fn severity(&self) -> Severity { self.severity }
+
+  /// byte offset in buffer, where problem was found
+  pub fn offset(&self) -> usize { self.offset }
+
+  /// index of track, None for problems outside of tracks
+  pub fn track(&self) -> Option<usize> { self.track }
+
+  pub fn message(&self) -> &str { &self.message }
+
+  /// parse error which caused the diagnostic
+  pub fn error(&self) -> Option<&MidiParseError> { self.error.as_ref() }
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.track {
+      Some(track) => write!(f, "{} at byte {} (track {}): {}", self.severity, self.offset, track, self.message),
+      None => write!(f, "{} at byte {}: {}", self.severity, self.offset, self.message),
+    }
+  }
+}
@@ -1,4 +1,4 @@
-use std::{fmt, error};
+use std::{fmt, error, io, sync::Arc};
 
 use super::parser_state::ParserState;
 
@@ -9,6 +9,7 @@ pub enum MidiParseErrorKind {
   NotMidiMetricTime,
   InvalidMidiTrackHeader,
   InvalidMidiHeader,
+  Io,
 }
 
 impl fmt::Display for MidiParseErrorKind {
@@ -17,24 +18,170 @@ impl fmt::Display for MidiParseErrorKind {
     }
 }
 
+/// number of bytes shown before and after the failing byte in hexdump
+const CONTEXT_LEN : usize = 16;
+
 #[derive(Debug, Clone)]
 pub struct MidiParseError {
   state : ParserState,
   kind : MidiParseErrorKind,
   message : String,
+  offset : usize,
+  /// boxed, so Result holding the error stays small
+  detail : Option<Box<ErrorDetail>>,
+}
+
+/// Parts of MidiParseError which are set only by some errors.
+#[derive(Debug, Clone, Default)]
+struct ErrorDetail {
+  track : Option<usize>,
+  event : Option<usize>,
   trace : Option<String>,
+  expected : Option<String>,
+  found : Option<Vec<u8>>,
+  context : Option<(usize, Vec<u8>)>,
+  source : Option<Arc<io::Error>>,
 }
 
 impl MidiParseError {
   pub fn new(state: ParserState, kind: MidiParseErrorKind, message : String, trace : Option<String>) -> MidiParseError {
-    return MidiParseError { state, kind, message, trace};
+    let offset = state.curr();
+    let detail = trace.map(|trace| Box::new(ErrorDetail { trace : Some(trace), ..Default::default() }));
+    return MidiParseError { state, kind, message, offset, detail };
+  }
+
+  pub fn kind(&self) -> &MidiParseErrorKind { &self.kind }
+
+  pub fn message(&self) -> &str { &self.message }
+
+  pub fn trace(&self) -> Option<&str> { self.detail.as_ref()?.trace.as_deref() }
+
+  /// byte offset in buffer, where error occurred
+  pub fn offset(&self) -> usize { self.offset }
+
+  /// index of track, in which error occurred
+  pub fn track(&self) -> Option<usize> { self.detail.as_ref()?.track }
+
+  /// index of event in track, which failed to parse
+  pub fn event(&self) -> Option<usize> { self.detail.as_ref()?.event }
+
+  /// description of what parser expected at offset
+  pub fn expected(&self) -> Option<&str> { self.detail.as_ref()?.expected.as_deref() }
+
+  /// bytes found at offset
+  pub fn found(&self) -> Option<&[u8]> { self.detail.as_ref()?.found.as_deref() }
+
+  /// bytes around offset, along with offset of the first of them
+  pub fn context(&self) -> Option<(usize, &[u8])> {
+    self.detail.as_ref()?.context.as_ref().map(|(start, bytes)| (*start, bytes.as_slice()))
+  }
+
+  /// Hexdump of bytes around offset, 16 bytes per line, failing byte is put in brackets.
+  ///
+  /// ```text
+  /// 00000170: 00 ff 2f 00 4d 54 72 6b 00 00 37 bd 00 ff 58 04
+  /// 00000180: 04 02 18 08 00 [f4] 2b 00 ...
+  /// ```
+  pub fn hexdump(&self) -> Option<String> {
+    let (start, bytes) = self.context()?;
+    let lines = bytes
+      .chunks(16)
+      .enumerate()
+      .map(|(line, chunk)| {
+        let line_start = start + line * 16;
+        let hex = chunk.iter()
+          .enumerate()
+          .map(|(i, byte)| match line_start + i == self.offset {
+            true => format!("[{:02x}]", byte),
+            false => format!("{:02x}", byte),
+          })
+          .collect::<Vec<_>>()
+          .join(" ");
+        format!("{:08x}: {}", line_start, hex)
+      })
+      .collect::<Vec<_>>();
+    Some(lines.join("\n"))
+  }
+
+  pub(crate) fn io(state: ParserState, err : io::Error) -> MidiParseError {
+    let mut error = MidiParseError::new(state, MidiParseErrorKind::Io, err.to_string(), None);
+    error.detail_mut().source = Some(Arc::new(err));
+    error
+  }
+
+  fn detail_mut(&mut self) -> &mut ErrorDetail {
+    self.detail.get_or_insert_with(Default::default)
+  }
+
+  /// sets the offset of error, when it differs from current position of state
+  pub(crate) fn at(mut self, offset : usize) -> Self {
+    self.offset = offset;
+    self
+  }
+
+  pub(crate) fn in_track(mut self, track : usize) -> Self {
+    self.detail_mut().track.get_or_insert(track);
+    self
+  }
+
+  pub(crate) fn at_event(mut self, event : usize) -> Self {
+    self.detail_mut().event.get_or_insert(event);
+    self
+  }
+
+  pub(crate) fn with_expected(mut self, expected : String) -> Self {
+    self.detail_mut().expected = Some(expected);
+    self
+  }
+
+  pub(crate) fn with_found(mut self, found : &[u8]) -> Self {
+    self.detail_mut().found = Some(found.to_vec());
+    self
+  }
+
+  /// Keeps the bytes of `buf` around offset, for hexdump.
+  pub(crate) fn with_context(mut self, buf : &[u8]) -> Self {
+    if self.context().is_none() && self.offset <= buf.len() {
+      let start = self.offset.saturating_sub(CONTEXT_LEN) & !0xF;
+      let end = (self.offset + CONTEXT_LEN).min(buf.len());
+      self.detail_mut().context = Some((start, buf[start .. end].to_vec()));
+    }
+    self
+  }
+
+  /// Moves the offsets by `base`, for errors found in part of a larger stream.
+  pub(crate) fn relative_to(mut self, base : usize) -> Self {
+    self.offset += base;
+    if let Some((start, _)) = self.detail.as_mut().and_then(|detail| detail.context.as_mut()) { *start += base; }
+    self
   }
 }
 
 impl fmt::Display for MidiParseError {
+    /// Alternate format `{:#}` adds hexdump of bytes around offset.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-      write!(f, "Error occurred while parsing at position {}: {}", self.state, self.kind)
+      write!(f, "Error occurred while parsing {} at byte {}", self.state.name(), self.offset)?;
+      match (self.track(), self.event()) {
+        (Some(track), Some(event)) => write!(f, " (track {}, event {})", track, event)?,
+        (Some(track), None) => write!(f, " (track {})", track)?,
+        _ => ()
+      }
+      write!(f, ": {}: {}", self.kind, self.message)?;
+      if let Some(expected) = self.expected() {
+        write!(f, "; expected {}", expected)?;
+        if let Some(found) = self.found() {
+          write!(f, ", found {:02x?}", found)?;
+        }
+      }
+      if let (true, Some(hexdump)) = (f.alternate(), self.hexdump()) {
+        write!(f, "\n{}", hexdump)?;
+      }
+      Ok(())
     }
 }
 
-impl error::Error for MidiParseError {}
\ No newline at end of file
+impl error::Error for MidiParseError {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    self.detail.as_ref()?.source.as_deref().map(|err| err as &(dyn error::Error + 'static))
+  }
+}
@@ -1,8 +1,8 @@
-use std::{fs::File, error::Error, fmt::format};
+use std::{error::Error, fmt::format, ops::Range};
 
 use crate::{
   model::core::{
-    midi_event::{MidiEvent, self, meta_message::{self, MetaMessage}, channel_message::{ChannelMessage}, sys_event::SysEvent, MidiMessage}, 
+    midi_event::{MidiEvent, self, meta_message::{self, MetaMessage}, channel_message::{ChannelMessage}, sys_event::SysEvent, MidiMessage, MidiMessageType, delta_time::DeltaTime}, 
     midi::Midi, midi_header::MidiHeader
   }, 
   primitive::{
@@ -14,26 +14,170 @@ use super::{parser_state::ParserState, error::{MidiParseError, MidiParseErrorKin
 
 lazy_static::lazy_static!(
   #[derive(Debug)]
-  static ref CHANNEL_EVENT_SCHEMA : serde_json::Value = serde_json::de::from_reader(File::open("./src/parser/schema/midi-v1-channel-event-schema.json").expect("File should open read only")).unwrap();
-  static ref META_EVENT_SCHEMA : serde_json::Value = serde_json::de::from_reader(File::open("./src/parser/schema/midi-v1-meta-event-schema.json").expect("File should open read only")).unwrap();
+  static ref CHANNEL_EVENT_SCHEMA : serde_json::Value = serde_json::from_str(include_str!("schema/midi-v1-channel-event-schema.json")).unwrap();
+  static ref META_EVENT_SCHEMA : serde_json::Value = serde_json::from_str(include_str!("schema/midi-v1-meta-event-schema.json")).unwrap();
 );
 
-#[derive(Debug)]
-pub struct MidiEventParser<'a> {
-  midi_header : &'a MidiHeader,
+/// Midi Event split in its parts, without decoding the message.
+/// 
+/// Payload is borrowed from the parsed buffer, so reading RawEvent never copies meta or sysex data.
+#[derive(Debug, Clone, Copy)]
+pub struct RawEvent<'a> {
+  offset : usize,
+  delta_time : u32,
+  event_byte : u8,
+  meta_type : Option<u8>,
+  data : &'a [u8],
 }
 
-impl<'a> MidiEventParser<'a> {
-  pub fn new(midi_header : &MidiHeader) -> MidiEventParser {
-    MidiEventParser { midi_header}
+impl<'a> RawEvent<'a> {
+  /// position of event (its delta time) in buffer
+  pub fn offset(&self) -> usize { self.offset }
+
+  pub fn delta_time(&self) -> u32 { self.delta_time }
+
+  /// status byte of event, running status is already resolved for channel events.
+  pub fn event_byte(&self) -> u8 { self.event_byte }
+
+  /// type byte of meta event, None for other events.
+  pub fn meta_type(&self) -> Option<u8> { self.meta_type }
+
+  /// data bytes of channel event, or payload (following the length) of meta and sysex event.
+  pub fn data(&self) -> &'a [u8] { self.data }
+
+  pub fn is_channel_event(&self) -> bool { MidiEvent::is_channel_byte(self.event_byte) && !self.is_sys_event() }
+
+  pub fn is_meta_event(&self) -> bool { self.meta_type.is_some() }
+
+  pub fn is_sys_event(&self) -> bool { MidiEvent::is_sys_byte(self.event_byte) }
+
+  /// Decodes the message to owned MidiMessage.
+  pub fn message(&self) -> MidiMessage {
+    match self.meta_type {
+      Some(meta_type) => MidiMessage::MetaMessage(MetaMessage::from((self.event_byte, meta_type, self.data))),
+      None if self.is_sys_event() => MidiMessage::from_sys_event(SysEvent::new(self.event_byte, self.data.to_vec())),
+      None => MidiMessage::ChannelMessage(ChannelMessage::from((self.event_byte, self.data))),
+    }
+  }
+
+  /// Decodes the event to owned MidiEvent.
+  pub fn to_event(&self) -> MidiEvent {
+    MidiEvent::new(DeltaTime::from(self.delta_time), self.message())
+  }
+}
+
+impl<'a> From<RawEvent<'a>> for MidiEvent {
+  fn from(raw_event: RawEvent<'a>) -> Self {
+    raw_event.to_event()
+  }
+}
+
+/// Midi Event Parser parses single event at a time, and keeps the running status between events.
+#[derive(Debug, Clone, Default)]
+pub struct MidiEventParser {
+  running_status : Option<u8>,
+}
+
+impl MidiEventParser {
+  pub fn new() -> MidiEventParser {
+    MidiEventParser { running_status : None }
+  }
+
+  pub fn running_status(&self) -> Option<u8> { self.running_status }
+
+  /// Parses the next event (delta time and message) at current position of `state`, without decoding the message.
+  pub fn parse_raw_event<'a>(&mut self, buf : &'a [u8], state : &mut ParserState) -> Result<RawEvent<'a>, MidiParseError> {
+    self.parse_raw_event_with(buf, state, false).map(|(raw_event, _)| raw_event)
+  }
+
+  /// Parses the next event like [`parse_raw_event`](Self::parse_raw_event), 
+  /// but data bytes found where no running status applies are skipped up to next status byte.
+  /// Returns the range of skipped bytes along with event, skipped bytes keep the delta time read before them.
+  pub fn parse_raw_event_skipping<'a>(&mut self, buf : &'a [u8], state : &mut ParserState) -> Result<(RawEvent<'a>, Option<Range<usize>>), MidiParseError> {
+    self.parse_raw_event_with(buf, state, true)
   }
 
-  pub fn parse_channel_event(&mut self, buf : &[u8], state : &mut ParserState, last_event_byte : Option<u8>) -> Result<ChannelMessage, MidiParseError> {
+  /// Parses the next event, skipping stray data bytes when `skip_stray` is set.
+  pub(crate) fn parse_raw_event_with<'a>(&mut self, buf : &'a [u8], state : &mut ParserState, skip_stray : bool) -> Result<(RawEvent<'a>, Option<Range<usize>>), MidiParseError> {
+    let offset = state.curr();
 
-    let event_byte = if MidiEvent::is_channel_byte( state.byte(buf)) { 
+    let delta_time = *state.checked_mxbyte(buf)?;
+
+    state.ensure(1)?;
+
+    let mut skipped = None;
+    if skip_stray && self.running_status.is_none() && state.byte(buf) & 0x80 == 0 {
+      let start = state.curr();
+      while state.remaining() > 0 && state.byte(buf) & 0x80 == 0 {
+        state.forward(1);
+      }
+      skipped = Some(start .. state.curr());
+      state.ensure(1)?;
+    }
+
+    let (event_byte, meta_type, data) = match MidiEvent::event_type(state.byte(buf)) {
+      Some(MidiMessageType::SystemCommon) | Some(MidiMessageType::SystemRealTime) => return Err(
+        MidiParseError::new(
+          state.clone(),
+          MidiParseErrorKind::InvalidEventByte,
+          format!("system message 0x{:02X} should be escaped in F7 packet", state.byte(buf)),
+          None)
+        .with_expected(String::from("channel, meta or sysex status byte"))
+        .with_found(state.take(buf, 1))
+      ),
+      Some(MidiMessageType::Channel) | None => {
+        let (event_byte, data) = self.parse_channel_event(buf, state)?;
+        (event_byte, None, data)
+      },
+      Some(MidiMessageType::Meta) => {
+        self.running_status = None;
+        let (meta_type, data) = self.parse_meta_event(buf, state)?;
+        (0xFF, Some(meta_type), data)
+      },
+      Some(MidiMessageType::Sys) => {
+        self.running_status = None;
+        let (event_byte, data) = self.parse_sys_event(buf, state)?;
+        (event_byte, None, data)
+      },
+      Some(MidiMessageType::Invalid(msg)) => return Err(
+        MidiParseError::new(
+          state.clone(), 
+          MidiParseErrorKind::InvalidEventByte, 
+          format!("invalid event byte {}, can't tag to Channel, Meta or Sys event", msg),
+          Some(msg))
+        .with_expected(String::from("status byte"))
+        .with_found(state.take(buf, 1))
+      )
+    };
+
+    Ok((RawEvent { offset, delta_time, event_byte, meta_type, data }, skipped))
+  }
+
+  /// Parses the next event at current position of `state` to MidiEvent.
+  pub fn parse_event(&mut self, buf : &[u8], state : &mut ParserState) -> Result<MidiEvent, MidiParseError> {
+    Ok(self.parse_raw_event(buf, state)?.to_event())
+  }
+
+  /// Parses the channel event, returns the event byte and data bytes.
+  /// Event byte is taken from running status if current byte is data byte.
+  pub fn parse_channel_event<'a>(&mut self, buf : &'a [u8], state : &mut ParserState) -> Result<(u8, &'a [u8]), MidiParseError> {
+
+    // any status byte is taken, so status bytes other than channel fail below instead of using running status
+    let event_byte = if state.byte(buf) & 0x80 != 0 {
       state.next(buf, 1)[0]
     } else {
-      last_event_byte.expect(format!("{:X} not a channel event. {}", state.byte(buf), &state).as_str())
+      match self.running_status {
+        Some(event_byte) => event_byte,
+        None => return Err(
+          MidiParseError::new(
+            state.with_name(format!("{}@channel[0x{:02X}]", state.name(), state.byte(buf))), 
+            MidiParseErrorKind::InvalidEventByte,
+            format!("{:X} not a channel event, and no running status to apply", state.byte(buf)),
+            None)
+          .with_expected(String::from("status byte, or data byte after channel event"))
+          .with_found(state.take(buf, 1))
+        )
+      }
     };
     
     let event_type = (event_byte & 0xF0) >> 4;
@@ -47,18 +191,26 @@ impl<'a> MidiEventParser<'a> {
           MidiParseErrorKind::InvalidEventByte,
           format!("channel[{:1X}] not a valid channel event byte", event_byte),
           None)
+        .at(state.curr() - 1)
+        .with_expected(String::from("channel event byte (0x80..=0xEF)"))
+        .with_found(&[event_byte])
       );
     }
 
     let length = event_info["length"].as_u64().unwrap() as usize;
-    // dbg!(&state);
-    // dbg!(event_type);
-    // dbg!(state.take(buf, length));
-    Ok(ChannelMessage::from((event_byte, state.next(buf, length))))
+
+    state.ensure(length)?;
+
+    self.running_status = Some(event_byte);
+
+    Ok((event_byte, state.next(buf, length)))
   }
 
-  pub fn parse_meta_event(&mut self, buf : &[u8], state : &mut ParserState) -> Result<MetaMessage, MidiParseError> {
+  /// Parses the meta event, returns the meta type and payload.
+  pub fn parse_meta_event<'a>(&mut self, buf : &'a [u8], state : &mut ParserState) -> Result<(u8, &'a [u8]), MidiParseError> {
     
+    state.ensure(2)?;
+
     let event_type = state.next(buf, 1)[0];
     let event_sub_type = state.next(buf, 1)[0];
 
@@ -68,17 +220,27 @@ impl<'a> MidiEventParser<'a> {
         MidiParseErrorKind::InvalidEventByte, 
         format!("meta[0X{:02X}][0X{:02X}] not a valid meta event type", event_type, event_sub_type),
         None)
+        .at(state.curr() - 1)
+        .with_expected(String::from("known meta event type"))
+        .with_found(&[event_sub_type])
       )
     }
 
-    let event_length = *state.mxbyte(buf) as usize;
+    let event_length = *state.checked_mxbyte(buf)? as usize;
     
-    state.forward(event_length);
+    state.ensure(event_length)?;
 
-    Ok(MetaMessage::from((event_type, event_sub_type, state.retake(buf, event_length))))
+    Ok((event_sub_type, state.next(buf, event_length)))
   }
 
-  pub fn parse_sys_event(&mut self, buf : &[u8]) -> Result<SysEvent, MidiParseError> {
-    Ok(SysEvent)
+  /// Parses the sysex event (F0 or F7), returns the event byte and payload.
+  pub fn parse_sys_event<'a>(&mut self, buf : &'a [u8], state : &mut ParserState) -> Result<(u8, &'a [u8]), MidiParseError> {
+    let event_byte = state.next(buf, 1)[0];
+
+    let event_length = *state.checked_mxbyte(buf)? as usize;
+
+    state.ensure(event_length)?;
+
+    Ok((event_byte, state.next(buf, event_length)))
   }
-}
\ No newline at end of file
+}
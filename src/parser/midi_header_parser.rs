@@ -1,28 +1,39 @@
 use crate::{model::core::{midi::Midi, midi_header::MidiHeader}, utils::{functions::number, ByteEncodingFormat}};
 
-use super::{Parser, parser_state::ParserState, error::{MidiParseError, MidiParseErrorKind}};
+use super::{Parser, parser_state::ParserState, chunk_parser::ChunkReader, error::{MidiParseError, MidiParseErrorKind}};
 pub struct MidiHeaderParser;
 
 impl MidiHeaderParser {
+  /// Parses the header chunk at current position of `state`.
+  ///
+  /// Header data is 6 bytes long in midi v1, longer headers are accepted and extra bytes are skipped, 
+  /// as later versions of standard may add fields.
   pub fn parse(buf : &[u8], state : &mut ParserState) -> Result<MidiHeader, MidiParseError> {
+
+    // Midi header in midi v1 is at least 14 bytes long
+    state.ensure(14)?;
     
-    match &buf[..4] {
+    match state.take(buf, 4) {
       b"MThd" => {
-        const ENC_FORMAT: ByteEncodingFormat = ByteEncodingFormat::BigEndian;
-        let end = 14;
-        
-        let length = number(&buf[4..8], ENC_FORMAT);
-        
-        assert!(length == 6, "invalid MIDI header length. Length must be 6, but got {}", length);
+        let header_state = state.clone();
+        let chunk = ChunkReader::read_chunk(buf, state)?;
+        let data = chunk.data();
 
-        // Midi header in midi v1 is always 14 bytes long
-        state.forward(14);
+        if data.len() < 6 {
+          let length_offset = header_state.curr() + 4;
+          return Err(MidiParseError::new(
+            header_state,
+            MidiParseErrorKind::InvalidMidiHeader,
+            format!("invalid MIDI header length. Length must be at least 6, but got {}", data.len()),
+            None
+          ).at(length_offset).with_expected(String::from("header length of at least 6")).with_found(&buf[length_offset .. length_offset + 4]));
+        }
 
-        return Ok(MidiHeader::new_raw(
-          &buf[8..10],
-          &buf[10..12],
-          &buf[12..14]
-        ));
+        Ok(MidiHeader::new_raw(
+          &data[0..2],
+          &data[2..4],
+          &data[4..6]
+        ).with_length(data.len() as u32))
       },
       _header => Err(
         MidiParseError::new(
@@ -30,7 +41,7 @@ impl MidiHeaderParser {
         MidiParseErrorKind::InvalidMidiHeader,
         format!("MIDI header should start with 'MThd', but got {_header:?}"),
         None
-      ))
+      ).with_expected(String::from("'MThd'")).with_found(_header))
     }
   }
 }
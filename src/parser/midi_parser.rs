@@ -1,45 +1,135 @@
-use crate::{model::core::{midi::Midi, midi_track::MidiTrack}, reader::{Reader, buffer::Buffer}};
+use crate::{
+  model::core::{
+    midi::Midi, 
+    midi_track::MidiTrack, 
+    midi_event::{MidiEvent, MidiMessage, meta_message::MetaMessage, delta_time::DeltaTime}
+  }, 
+  reader::{Reader, buffer::Buffer}
+};
 
-use super::{midi_header_parser::MidiHeaderParser, Parser, midi_track_parser::MidiTrackParser, parser_state::ParserState, midi_track_header_parser::{MidiTrackHeaderParser, self}, error::MidiParseError};
+use super::{
+  midi_header_parser::MidiHeaderParser, Parser, midi_track_parser::MidiTrackParser, parser_state::ParserState, 
+  midi_track_header_parser::{MidiTrackHeaderParser, self}, error::MidiParseError, 
+  chunk_parser::ChunkReader,
+  diagnostic::{Diagnostic, DiagnosticKind, Severity},
+  options::ParseOptions
+};
 
 pub struct MidiParser;
 
 impl MidiParser {
     /// Parses a midi buffer to Midi instance
     /// Midi buffer should have valid Midi header and Midi track information
+    /// 
+    /// Fails with error of first track which couldn't be parsed.
     pub fn parse(buf : &[u8]) -> Result<Midi, MidiParseError> {
-      let mut state: ParserState = ParserState::new(String::from("midi"), 0, buf.len());
+      Self::parse_with(buf, &ParseOptions::strict()).map(|(midi, _)| midi)
+    }
+
+    /// Parses a midi buffer to Midi instance, recovering from broken parts of midi.
+    /// See [`Strictness::Lenient`](super::Strictness::Lenient) for recoveries made.
+    /// Fails only if Midi header can't be parsed.
+    pub fn parse_lenient(buf : &[u8]) -> Result<(Midi, Vec<Diagnostic>), MidiParseError> {
+      Self::parse_with(buf, &ParseOptions::lenient())
+    }
+
+    /// Parses a midi buffer to Midi instance following the `options`.
+    /// 
+    /// In strict mode fails with first error. 
+    /// In lenient mode track which fails to parse is kept with the events parsed before the failure, 
+    /// and every recovery is reported as diagnostic.
+    /// Track count not matching `ntrk` of Midi Header is reported as warning in both modes.
+    pub fn parse_with(buf : &[u8], options : &ParseOptions) -> Result<(Midi, Vec<Diagnostic>), MidiParseError> {
+      let smf_start = ChunkReader::smf_state(buf)?.start();
+      let (mut midi, mut midi_track_parsers, mut diagnostics) = Self::parse_headers(buf, options)
+        .map_err(|err| err.with_context(buf))?;
+      // adding tracks updates ntrk of header, so declared count is read first
+      let ntrk = *midi.header().ntrk() as usize;
+
+      for (track_no, (mut midi_track, err, track_diagnostics)) in Self::parse_tracks(buf, &mut midi_track_parsers, options).into_iter().enumerate() {
+        diagnostics.extend(track_diagnostics);
+        if let Some(err) = err {
+          if !options.is_lenient() { return Err(err); }
+          diagnostics.push(Diagnostic::track_error(track_no, err));
+        }
+
+        if options.is_lenient() && !matches!(midi_track.events.last(), Some(event) if event.is_end_of_track()) {
+          diagnostics.push(Diagnostic::new(
+            DiagnosticKind::MissingEndOfTrack, Severity::Warning, midi_track_parsers[track_no].state().end(), Some(track_no),
+            String::from("track doesn't end with End Of Track, End Of Track was added")
+          ));
+          midi_track.add_event(MidiEvent::new(DeltaTime::default(), MidiMessage::MetaMessage(MetaMessage::EndOfTrack)));
+        }
+        midi.add_track(midi_track);
+      }
+
+      if ntrk != midi.tracks().len() {
+        diagnostics.push(Diagnostic::new(
+          DiagnosticKind::TrackCountMismatch,
+          Severity::Warning,
+          // ntrk is stored at bytes 10..12 of header
+          smf_start + 10,
+          None,
+          format!("midi header declares {} tracks, but {} tracks were found", ntrk, midi.tracks().len())
+        ));
+      }
+
+      Ok((midi, diagnostics))
+    }
+
+    /// Parses many midi buffers, in parallel when `rayon` feature is enabled.
+    /// Results are returned in order of buffers.
+    pub fn parse_many<B : AsRef<[u8]> + Sync>(bufs : &[B]) -> Vec<Result<Midi, MidiParseError>> {
+      cfg_if::cfg_if! {
+        if #[cfg(feature = "rayon")] {
+          use rayon::prelude::*;
+          bufs.par_iter().map(|buf| Self::parse(buf.as_ref())).collect()
+        } else {
+          bufs.iter().map(|buf| Self::parse(buf.as_ref())).collect()
+        }
+      }
+    }
+
+    /// Parses the Midi header and track headers, returns Midi with header, unknown chunks and parser for every track.
+    /// SMF in RIFF `RMID` container is unwrapped first.
+    fn parse_headers(buf : &[u8], options : &ParseOptions) -> Result<(Midi, Vec<MidiTrackParser>, Vec<Diagnostic>), MidiParseError> {
+      let mut state: ParserState = ChunkReader::smf_state(buf)?;
 
-      
       let midi_header = MidiHeaderParser::parse(buf, &mut state)?;
       
       let mut midi = Midi::default();
 
       midi.add_header(midi_header.clone());
 
-
       let track_header_state = state.with_name(String::from("track-header-parser"));
 
-      match MidiTrackHeaderParser::parse(buf, midi_header, track_header_state) {
-        Ok(mut midi_track_parsers) => {
-
-          midi_track_parsers
-            .iter_mut()
-            .map(|midi_track_parser| midi_track_parser.parse(buf))
-            .map(|midi_track| -> Result<_, MidiParseError>{
-              match midi_track {
-                Ok(track) => Ok(midi.add_track(track)),
-                Err(err) => return Err(err),
-              }
-            })
-            .collect::<Vec<Result<_, MidiParseError>>>()
-            ;
-        },
-        Err(err) => return Err(err)
-      };
-      return Ok(midi)
-    }
+      let (midi_track_parsers, unknown_chunks, diagnostics) = MidiTrackHeaderParser::parse_with(buf, midi_header, track_header_state, options)?;
+
+      for unknown_chunk in unknown_chunks {
+        midi.add_unknown_chunk(unknown_chunk);
+      }
 
+      Ok((midi, midi_track_parsers, diagnostics))
+    }
 
-    
-}
\ No newline at end of file
+    /// Parses the tracks, in parallel when `rayon` feature is enabled.
+    /// Every track parser owns its own state, so tracks are independent of each other,
+    /// results are reassembled in track order.
+    fn parse_tracks(buf : &[u8], midi_track_parsers : &mut [MidiTrackParser], options : &ParseOptions) -> Vec<(MidiTrack, Option<MidiParseError>, Vec<Diagnostic>)> {
+      let parse = |midi_track_parser : &mut MidiTrackParser| match options.is_lenient() {
+        true => midi_track_parser.parse_lenient(buf),
+        false => {
+          let (midi_track, err) = midi_track_parser.parse_partial(buf);
+          (midi_track, err, Vec::new())
+        }
+      };
+      cfg_if::cfg_if! {
+        if #[cfg(feature = "rayon")] {
+          use rayon::prelude::*;
+          midi_track_parsers.par_iter_mut().map(parse).collect()
+        } else {
+          midi_track_parsers.iter_mut().map(parse).collect()
+        }
+      }
+    }
+}
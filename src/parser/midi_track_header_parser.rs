@@ -3,16 +3,22 @@ use std::fmt::format;
 use crate::{
   utils::{ByteEncodingFormat, functions::number}, 
   parser::midi_track_parser::MidiTrackParser, 
-  model::core::midi_header::MidiHeader
+  model::core::{midi_header::MidiHeader, unknown_chunk::UnknownChunk}
 };
 
 use super::{
   parser_state::ParserState, 
+  chunk_parser::ChunkReader,
+  options::ParseOptions,
+  diagnostic::{Diagnostic, DiagnosticKind, Severity},
   error::{
     MidiParseError,
     MidiParseErrorKind::InvalidMidiTrackHeader
   }};
 
+/// track parsers, along with chunks of unknown type and diagnostics of lenient parsing
+type TrackHeaders = (Vec<MidiTrackParser>, Vec<UnknownChunk>, Vec<Diagnostic>);
+
 #[derive(Debug, Clone)]
 pub struct MidiTrackHeaderParser;
 
@@ -21,42 +27,90 @@ impl MidiTrackHeaderParser {
   /// Parses the the Midi Tracks Header.
   /// 
   /// It just retrived the header and track length information.
-  pub fn parse(buf : &[u8], midi_header : MidiHeader, mut state : ParserState) -> Result<Vec<MidiTrackParser>, MidiParseError>{
+  pub fn parse(buf : &[u8], midi_header : MidiHeader, state : ParserState) -> Result<Vec<MidiTrackParser>, MidiParseError>{
+    Self::parse_with(buf, midi_header, state, &ParseOptions::strict()).map(|(midi_track_headers, _, _)| midi_track_headers)
+  }
+
+  /// Parses the the Midi Tracks Header, following the `options`.
+  /// 
+  /// Chunks of unknown type are skipped and returned as [`UnknownChunk`] in both modes.
+  /// In lenient mode junk between chunks is skipped, 
+  /// and wrong track lengths are inferred from position of next `MTrk`.
+  pub fn parse_with(buf : &[u8], midi_header : MidiHeader, mut state : ParserState, options : &ParseOptions) -> Result<TrackHeaders, MidiParseError>{
     
     const ENC_FORMAT: ByteEncodingFormat = ByteEncodingFormat::BigEndian;
 
+    // midi may be followed by other data, e.g. in RIFF container
+    let buf = &buf[.. state.end().min(buf.len())];
+
     let mut midi_track_headers = Vec::new();
 
+    let mut unknown_chunks = Vec::new();
+
+    let mut diagnostics = Vec::new();
+
     let mut track_no = -1; 
     
     loop {
 
-      if state.curr() >= state.end() { return Ok(midi_track_headers); }
+      if state.curr() >= state.end() { return Ok((midi_track_headers, unknown_chunks, diagnostics)); }
+
+      let ptr = state.curr();
+
+      if options.is_lenient() && state.remaining() < 8 {
+        diagnostics.push(Diagnostic::new(
+          DiagnosticKind::JunkSkipped, Severity::Warning, ptr, None,
+          format!("skipped {} trailing bytes after last chunk", state.remaining())
+        ));
+        return Ok((midi_track_headers, unknown_chunks, diagnostics));
+      }
+
+      state.ensure(8)?;
 
       track_no += 1;
 
       let track_name = format!("track-{}", track_no);
 
-      let ptr = state.curr();
+      let length = number(&buf[ptr + 4 .. ptr + 8], ENC_FORMAT) as usize;
 
       match &buf[ptr .. ptr + 4] {
         b"MTrk" => {
-          let length = number(&buf[ptr + 4 .. ptr + 8], ENC_FORMAT);
+          let length = match options.is_lenient() {
+            true => Self::infer_length(buf, ptr, length, track_no as usize, &mut diagnostics),
+            false => length
+          };
 
-          let total_length = 8 + length as usize; // header + track length
+          let total_length = 8 + length; // header + track length
 
           let mut track_state = ParserState::new(
             track_name, 
             ptr, 
-            ptr + total_length
+            (ptr + total_length).min(buf.len())
           );
 
           track_state.forward(8);
 
-          midi_track_headers.push(MidiTrackParser::new(midi_header.clone(), track_state));
+          midi_track_headers.push(MidiTrackParser::new(midi_header.clone(), track_state, track_no as usize));
 
           state.forward(total_length);
         }
+        // unknown chunks are skipped as per standard, truncated ones are junk in lenient mode
+        header if ChunkReader::is_chunk_type(header) && (!options.is_lenient() || ptr + 8 + length <= buf.len()) => {
+          track_no -= 1;
+
+          let chunk = ChunkReader::read_chunk(buf, &mut state)?;
+          unknown_chunks.push(chunk.to_unknown_chunk().with_position(midi_track_headers.len()));
+        }
+        header if options.is_lenient() => {
+          track_no -= 1;
+
+          let next = Self::find_track(buf, ptr + 1).unwrap_or(buf.len());
+          diagnostics.push(Diagnostic::new(
+            DiagnosticKind::JunkSkipped, Severity::Warning, ptr, None,
+            format!("skipped {} junk bytes, resynced at position {}", next - ptr, next)
+          ));
+          state.forward(next - ptr);
+        }
         header => {
           let track_err_start = ParserState::new(
             format!("{}-err-header-{:?}", track_name, header.to_vec()),
@@ -69,9 +123,36 @@ impl MidiTrackHeaderParser {
               InvalidMidiTrackHeader, 
               format!("invalid track header found at position {}", state.curr()),
               None
-            ))
+            ).in_track(track_no as usize).with_expected(String::from("'MTrk' or other chunk type")).with_found(header))
         }
       }
     }
   }
-}
\ No newline at end of file
+
+  /// Returns the declared track length, if track ends at end of buffer, ends with End Of Track or is followed by chunk.
+  /// Otherwise length is inferred from position of next `MTrk`, or end of buffer.
+  fn infer_length(buf : &[u8], ptr : usize, length : usize, track_no : usize, diagnostics : &mut Vec<Diagnostic>) -> usize {
+    let end = ptr + 8 + length;
+
+    if end == buf.len() 
+      || (end <= buf.len() && length >= 3 && buf[end - 3 .. end] == [0xFF, 0x2F, 0x00])
+      || (end + 4 <= buf.len() && ChunkReader::is_chunk_type(&buf[end .. end + 4])) {
+      return length;
+    }
+
+    let inferred = Self::find_track(buf, ptr + 8).unwrap_or(buf.len()) - ptr - 8;
+    diagnostics.push(Diagnostic::new(
+      DiagnosticKind::TrackLengthMismatch, Severity::Warning, ptr + 4, Some(track_no),
+      format!("track declares length of {} bytes, but inferred length is {} bytes", length, inferred)
+    ));
+    inferred
+  }
+
+  /// position of next `MTrk` from `from`
+  fn find_track(buf : &[u8], from : usize) -> Option<usize> {
+    buf.get(from ..)?
+      .windows(4)
+      .position(|window| window == b"MTrk")
+      .map(|position| from + position)
+  }
+}
@@ -17,65 +17,72 @@ use crate::{
   }, primitive::MXByte, 
 };
 
-use super::{Parser, parser_state::ParserState};
+use super::{Parser, parser_state::ParserState, diagnostic::{Diagnostic, DiagnosticKind, Severity}};
 
 
 #[derive(Debug, Clone)]
 pub struct MidiTrackParser {
   midi_header : MidiHeader,
-  state : ParserState
+  state : ParserState,
+  track : usize,
 }
 
 impl MidiTrackParser {
 
-  pub fn new(midi_header: MidiHeader, state : ParserState) -> MidiTrackParser {
-    MidiTrackParser { midi_header, state }
+  pub fn new(midi_header: MidiHeader, state : ParserState, track : usize) -> MidiTrackParser {
+    MidiTrackParser { midi_header, state, track }
   }
 
+  pub fn state(&self) -> &ParserState { &self.state }
+
+  /// index of track in midi
+  pub fn track(&self) -> usize { self.track }
+
   /// Parses a bytes into MIDI track.
   pub fn parse(&mut self, buf : &[u8]) -> Result<MidiTrack, MidiParseError> {
-    let mut midi_track = MidiTrack::default();
+    match self.parse_partial(buf) {
+      (midi_track, None) => Ok(midi_track),
+      (_, Some(err)) => Err(err)
+    }
+  }
+
+  /// Parses a bytes into MIDI track, stopping at first error.
+  /// Returns the events parsed before the error, along with the error.
+  pub fn parse_partial(&mut self, buf : &[u8]) -> (MidiTrack, Option<MidiParseError>) {
+    let (midi_track, err, _) = self.parse_with(buf, false);
+    (midi_track, err)
+  }
+
+  /// Parses a bytes into MIDI track like [`parse_partial`](Self::parse_partial),
+  /// but data bytes without running status are skipped up to next status byte, and reported as diagnostics.
+  pub fn parse_lenient(&mut self, buf : &[u8]) -> (MidiTrack, Option<MidiParseError>, Vec<Diagnostic>) {
+    self.parse_with(buf, true)
+  }
 
-    let mut midi_event_parser = MidiEventParser::new(&self.midi_header);
+  fn parse_with(&mut self, buf : &[u8], skip_stray : bool) -> (MidiTrack, Option<MidiParseError>, Vec<Diagnostic>) {
+    let mut midi_track = MidiTrack::default();
+    let mut diagnostics = Vec::new();
 
-    let mut last_event_byte = None;
+    let mut midi_event_parser = MidiEventParser::new();
 
     loop {
-      if self.state.curr() >= self.state.end() {return Ok(midi_track)}
-
-      let delta_time = DeltaTime::from(self.state.mxbyte(buf));
-      
-      let midi_event =  match MidiEvent::event_type(self.state.byte(buf))  {
-
-          Some(MidiMessageType::Channel) | None => {
-            match midi_event_parser.parse_channel_event(buf, &mut self.state, last_event_byte) {
-              Ok(channel_message) => {
-                last_event_byte = channel_message.event_byte();
-                MidiEvent::new(delta_time, MidiMessage::ChannelMessage(channel_message))
-              },
-              Err(err) => return Err(err)
-            }
-          },  
-          Some(MidiMessageType::Meta) => {
-            last_event_byte = None;
-            let meta_event = midi_event_parser.parse_meta_event(buf, &mut self.state).unwrap();
-            MidiEvent::new(delta_time, MidiMessage::MetaMessage(meta_event))
-          },
-          Some(MidiMessageType::Sys) => {
-            last_event_byte = None;
-            let sys_event = midi_event_parser.parse_sys_event(buf).unwrap();
-            MidiEvent::new(delta_time, MidiMessage::SysMessage(sys_event))
-          }
+      if self.state.curr() >= self.state.end() {return (midi_track, None, diagnostics)}
 
-          Some(MidiMessageType::Invalid(msg)) => return Err(
-            MidiParseError::new(
-              self.state.clone(), 
-              MidiParseErrorKind::InvalidEventByte, 
-              format!("invalid event byte {}, can't tag to Channel, Meta or Sys event", msg),
-              Some(msg))
-          )
-      };
-      midi_track.add_event(midi_event);
+      match midi_event_parser.parse_raw_event_with(buf, &mut self.state, skip_stray) {
+        Ok((raw_event, skipped)) => {
+          if let Some(skipped) = skipped {
+            diagnostics.push(Diagnostic::new(
+              DiagnosticKind::StrayDataSkipped, Severity::Warning, skipped.start, Some(self.track),
+              format!("{} data bytes without running status were skipped", skipped.len())
+            ));
+          }
+          midi_track.add_event(raw_event.to_event())
+        },
+        Err(err) => {
+          let err = err.in_track(self.track).at_event(midi_track.events.len()).with_context(buf);
+          return (midi_track, Some(err), diagnostics)
+        }
+      }
     };  
   }
 
@@ -1,14 +1,30 @@
 use crate::{model::core::midi::Midi};
 
-pub use self::midi_parser::MidiParser;
+pub use self::{
+  midi_parser::MidiParser,
+  midi_event_parser::RawEvent,
+  chunk_parser::{Chunk, ChunkReader},
+  error::{MidiParseError, MidiParseErrorKind},
+  diagnostic::{Diagnostic, DiagnosticKind, Severity},
+  options::{ParseOptions, Strictness},
+  clip_parser::ClipParser,
+  stream_parser::MidiStreamParser,
+  stream_decoder::{StreamDecoder, LiveMessage}
+};
 
-mod parser_state;
+pub(crate) mod parser_state;
+mod chunk_parser;
 mod midi_parser;
-mod midi_header_parser;
-mod midi_track_parser;
-mod midi_event_parser;
+pub(crate) mod midi_header_parser;
+pub(crate) mod midi_track_parser;
+pub(crate) mod midi_event_parser;
 mod error;
-mod midi_track_header_parser;
+mod diagnostic;
+mod options;
+mod clip_parser;
+mod stream_parser;
+mod stream_decoder;
+pub(crate) mod midi_track_header_parser;
 
 pub trait Parser {
   fn parse(buf : &[u8], midi : &mut Midi) -> usize;
@@ -0,0 +1,33 @@
+/// How parser reacts to midi not following the standard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strictness {
+  /// Fails on first problem.
+  #[default]
+  Strict,
+  /// Recovers from problems where possible, every recovery is reported as Diagnostic.
+  /// 
+  /// - junk bytes between chunks are skipped, parser resyncs at next `MTrk`
+  /// - track length is inferred from next `MTrk`, when declared length is wrong
+  /// - data bytes without running status are skipped up to next status byte
+  /// - track is cut at first broken event, events before it are kept
+  /// - missing End Of Track is synthesized
+  Lenient,
+}
+
+/// Options of MidiParser
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+  pub strictness : Strictness,
+}
+
+impl ParseOptions {
+  pub fn strict() -> Self {
+    Self { strictness : Strictness::Strict }
+  }
+
+  pub fn lenient() -> Self {
+    Self { strictness : Strictness::Lenient }
+  }
+
+  pub fn is_lenient(&self) -> bool { self.strictness == Strictness::Lenient }
+}
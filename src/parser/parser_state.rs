@@ -2,6 +2,8 @@ use core::fmt;
 
 use crate::primitive::MXByte;
 
+use super::error::{MidiParseError, MidiParseErrorKind};
+
 /// Parser State stores the ptr to position where parser is running currently
 #[derive(Debug, Clone)]
 pub struct ParserState {
@@ -56,6 +58,34 @@ impl<'a> ParserState {
     self.curr += mxbyte.len();
     mxbyte
   }
+  /// returns the mxbyte from current position in buffer, and moves current position accordingly.
+  /// Returns EndOfBuffer error if the variable length number doesn't end in 4 bytes or before end of state.
+  pub fn checked_mxbyte(&mut self, buf : &'a [u8]) -> Result<MXByte, MidiParseError> {
+    let end = self.end.min(buf.len()).min(self.curr + 4);
+    match buf.get(self.curr .. end).and_then(|bytes| bytes.iter().position(|byte| byte & 0x80 == 0)) {
+      Some(_) => Ok(self.mxbyte(buf)),
+      None => Err(self.end_of_buffer(4))
+    }
+  }
+
+  /// Checks that `len` bytes are left before end of state, otherwise returns EndOfBuffer error.
+  pub fn ensure(&self, len : usize) -> Result<(), MidiParseError> {
+    if self.remaining() >= len { Ok(()) } else { Err(self.end_of_buffer(len)) }
+  }
+
+  /// EndOfBuffer error for `len` bytes missing at current position
+  pub(crate) fn end_of_buffer(&self, len : usize) -> MidiParseError {
+    MidiParseError::new(
+      self.clone(),
+      MidiParseErrorKind::EndOfBuffer,
+      format!("only {} bytes left", self.remaining()),
+      None
+    ).with_expected(format!("{} more bytes", len))
+  }
+
+  /// number of bytes left before end of state
+  pub fn remaining(&self) -> usize { self.end.saturating_sub(self.curr) }
+
   pub fn name(&self) -> String { self.name.to_string() }
   
   pub fn curr(&self) -> usize { self.curr }
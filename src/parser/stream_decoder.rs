@@ -0,0 +1,201 @@
+use std::time::Duration;
+
+use crate::model::core::midi_event::{
+  channel_message::ChannelMessage,
+  sys_event::SysEvent,
+  system_message::{SystemCommon, SystemRealTime}
+};
+
+/// Message decoded from live MIDI byte stream by [`StreamDecoder`].
+#[derive(Debug, Clone)]
+pub enum LiveMessage {
+  Channel(ChannelMessage),
+  /// System Exclusive, data is bytes after `F0` ending with `F7`, like in Standard Midi File.
+  /// Data doesn't end with `F7` when sysex was cut by other status byte,
+  /// or when it was split by sysex limit, then rest follows as `F7` packets.
+  SysEx(SysEvent),
+  /// System Common message (`F1` to `F6`)
+  SystemCommon(SystemCommon),
+  /// System Real-Time message (`F8` to `FF`), `FF` is System Reset on the wire
+  RealTime(SystemRealTime),
+  /// Active Sensing was received, but no byte arrived within timeout, all sounding notes should be turned off.
+  ActiveSensingTimeout,
+}
+
+/// Decodes MIDI wire bytes, as received from serial or USB ports, to messages.
+///
+/// Bytes may be pushed in pieces of any size, messages are emitted as soon as their last byte arrives.
+/// - channel messages use running status, data bytes without status are dropped
+/// - Real-Time bytes (`F8` to `FF`) are emitted at once, even in the middle of other message or sysex
+/// - System Exclusive is collected across pieces until `F7`, or any other status byte but Real-Time
+/// - System Common and System Exclusive clear running status
+/// - System Reset (`FF`) drops message being received and running status
+///
+/// Time of every piece is given by caller, as time since any fixed instant, so Active Sensing
+/// timeout is told without decoder reading the clock, see [`StreamDecoder::poll`].
+#[derive(Debug, Clone)]
+pub struct StreamDecoder {
+  running_status : Option<u8>,
+  /// status of message being received, channel status stays here as running status
+  status : Option<u8>,
+  data : Vec<u8>,
+  /// bytes after `F0` of sysex being received
+  sysex : Option<Vec<u8>>,
+  /// sysex packet being received is continuation of split sysex
+  sysex_continued : bool,
+  sysex_limit : Option<usize>,
+  /// Active Sensing was received and timeout is watched
+  sensing : bool,
+  timeout : Duration,
+  last_received : Duration,
+}
+
+impl Default for StreamDecoder {
+  fn default() -> Self {
+    Self {
+      running_status : None,
+      status : None,
+      data : Vec::new(),
+      sysex : None,
+      sysex_continued : false,
+      sysex_limit : None,
+      sensing : false,
+      timeout : Self::ACTIVE_SENSING_TIMEOUT,
+      last_received : Duration::ZERO,
+    }
+  }
+}
+
+impl StreamDecoder {
+  /// time after last byte, when Active Sensing times out
+  pub const ACTIVE_SENSING_TIMEOUT : Duration = Duration::from_millis(300);
+
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sysex longer than `len` bytes is emitted in packets of `len` bytes, first as `F0` and rest as `F7` packets,
+  /// so long dumps aren't kept in memory.
+  pub fn with_sysex_limit(mut self, len : usize) -> Self {
+    self.sysex_limit = Some(len.max(1));
+    self
+  }
+
+  /// Changes Active Sensing timeout, 300ms by default.
+  pub fn with_active_sensing_timeout(mut self, timeout : Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  /// status applied to data bytes without status
+  pub fn running_status(&self) -> Option<u8> { self.running_status }
+
+  /// true while sysex is being received
+  pub fn in_sysex(&self) -> bool { self.sysex.is_some() }
+
+  /// Drops message being received, running status and Active Sensing, e.g. after port is reopened.
+  pub fn reset(&mut self) {
+    *self = Self { sysex_limit : self.sysex_limit, timeout : self.timeout, last_received : self.last_received, ..Self::default() };
+  }
+
+  /// Returns [`LiveMessage::ActiveSensingTimeout`] once, if no byte arrived within timeout after Active Sensing.
+  /// Should be called periodically, e.g. every 100ms, as stream may stop sending bytes at all.
+  /// Active Sensing is watched again after next `FE`.
+  pub fn poll(&mut self, time : Duration) -> Option<LiveMessage> {
+    if !self.sensing || time.saturating_sub(self.last_received) <= self.timeout { return None; }
+    self.sensing = false;
+    Some(LiveMessage::ActiveSensingTimeout)
+  }
+
+  /// Decodes `bytes` received at `time`, returns messages completed by them.
+  pub fn push(&mut self, bytes : &[u8], time : Duration) -> Vec<LiveMessage> {
+    let mut messages = Vec::new();
+    if bytes.is_empty() { return messages; }
+
+    messages.extend(self.poll(time));
+    self.last_received = time;
+
+    for &byte in bytes {
+      match byte {
+        0xF8 ..= 0xFF => {
+          match byte {
+            0xFE => self.sensing = true,
+            0xFF => self.reset(),
+            _ => {},
+          }
+          messages.extend(SystemRealTime::from_status(byte).map(LiveMessage::RealTime));
+        },
+        0xF0 => {
+          messages.extend(self.end_sysex(false));
+          self.running_status = None;
+          self.status = None;
+          self.sysex = Some(Vec::new());
+        },
+        0xF7 => messages.extend(self.end_sysex(true)),
+        0x80 ..= 0xF6 => {
+          messages.extend(self.end_sysex(false));
+          self.data.clear();
+          self.status = Some(byte);
+          self.running_status = (byte < 0xF0).then_some(byte);
+          messages.extend(self.complete());
+        },
+        data => match self.sysex.as_mut() {
+          Some(sysex) => {
+            sysex.push(data);
+            if matches!(self.sysex_limit, Some(limit) if sysex.len() >= limit) {
+              let data = std::mem::take(sysex);
+              messages.push(LiveMessage::SysEx(SysEvent::new(self.sysex_byte(), data)));
+              self.sysex_continued = true;
+            }
+          },
+          None => {
+            // data bytes without status are dropped
+            let Some(status) = self.status.or(self.running_status) else { continue };
+            self.status = Some(status);
+            self.data.push(data);
+            messages.extend(self.complete());
+          }
+        },
+      }
+    }
+    messages
+  }
+
+  /// Emits message when all its data bytes arrived, channel status is kept for running status.
+  fn complete(&mut self) -> Option<LiveMessage> {
+    let status = self.status?;
+    if self.data.len() < Self::data_len(status) { return None; }
+
+    let data = std::mem::take(&mut self.data);
+    match status {
+      0x80 ..= 0xEF => Some(LiveMessage::Channel(ChannelMessage::from((status, data.as_slice())))),
+      _ => {
+        self.status = None;
+        SystemCommon::decode(&[&[status], data.as_slice()].concat()).map(LiveMessage::SystemCommon)
+      }
+    }
+  }
+
+  /// Emits sysex being received, `terminated` when ended by `F7`.
+  fn end_sysex(&mut self, terminated : bool) -> Option<LiveMessage> {
+    let mut data = self.sysex.take()?;
+    if terminated { data.push(0xF7); }
+    let message = LiveMessage::SysEx(SysEvent::new(self.sysex_byte(), data));
+    self.sysex_continued = false;
+    Some(message)
+  }
+
+  /// `F0` for first packet of sysex, `F7` for continuation
+  fn sysex_byte(&self) -> u8 {
+    if self.sysex_continued { 0xF7 } else { 0xF0 }
+  }
+
+  /// number of data bytes following status
+  fn data_len(status : u8) -> usize {
+    match status {
+      0xC0 ..= 0xDF => 1,
+      0x80 ..= 0xEF => 2,
+      status => SystemCommon::data_len(status).unwrap_or_default(),
+    }
+  }
+}
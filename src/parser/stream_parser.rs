@@ -0,0 +1,140 @@
+use crate::{
+  model::core::{midi::Midi, midi_header::MidiHeader, unknown_chunk::UnknownChunk},
+  utils::{ByteEncodingFormat, functions::number}
+};
+
+use super::{
+  parser_state::ParserState,
+  chunk_parser::ChunkReader,
+  midi_header_parser::MidiHeaderParser,
+  midi_track_parser::MidiTrackParser,
+  error::{MidiParseError, MidiParseErrorKind}
+};
+
+/// Push based midi parser, for bytes arriving in pieces of any size, e.g. from network or decompressing streams.
+///
+/// Bytes are buffered until a chunk is complete, and every complete chunk is parsed at once,
+/// so only the chunk being received is kept as bytes. Errors point at offsets in the whole stream.
+/// RIFF `RMID` container is not unwrapped for streams.
+///
+/// ```
+/// use rmidirs::parser::MidiStreamParser;
+///
+/// # fn main() -> Result<(), rmidirs::parser::MidiParseError> {
+/// # let bytes : Vec<u8> = rmidirs::writer::MidiWriter::new(&Default::default()).to_bytes().unwrap();
+/// let mut parser = MidiStreamParser::new();
+/// for piece in bytes.chunks(3) {
+///   parser.push(piece)?;
+/// }
+/// let midi = parser.finish()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MidiStreamParser {
+  /// bytes of chunk being received
+  pending : Vec<u8>,
+  /// offset of first pending byte in stream
+  position : usize,
+  midi : Option<Midi>,
+}
+
+impl MidiStreamParser {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// header of midi, once header chunk is parsed
+  pub fn header(&self) -> Option<&MidiHeader> {
+    self.midi.as_ref().map(|midi| midi.header())
+  }
+
+  /// number of tracks parsed so far
+  pub fn track_count(&self) -> usize {
+    self.midi.as_ref().map_or(0, |midi| midi.tracks().len())
+  }
+
+  /// number of bytes received so far
+  pub fn position(&self) -> usize {
+    self.position + self.pending.len()
+  }
+
+  /// Adds next `bytes` of stream, and parses every chunk completed by them.
+  /// Fails with error of first chunk which couldn't be parsed, parser shouldn't be used after that.
+  pub fn push(&mut self, bytes : &[u8]) -> Result<(), MidiParseError> {
+    self.pending.extend_from_slice(bytes);
+
+    while self.pending.len() >= 8 {
+      let id = &self.pending[..4];
+      let track = self.track_count();
+      // type is checked before the data arrives, as length of junk can't be trusted
+      match (&self.midi, id) {
+        (None, b"MThd") => {},
+        (None, _) => return Err(MidiParseError::new(
+          ParserState::new(String::from("midi"), self.position, self.position + 8),
+          MidiParseErrorKind::InvalidMidiHeader,
+          format!("MIDI header should start with 'MThd', but got {:?}", id),
+          None
+        ).with_expected(String::from("'MThd'")).with_found(id)),
+        (Some(_), id) if !ChunkReader::is_chunk_type(id) => return Err(MidiParseError::new(
+          ParserState::new(format!("track-{}-err-header-{:?}", track, id.to_vec()), self.position, self.position + 8),
+          MidiParseErrorKind::InvalidMidiTrackHeader,
+          format!("invalid track header found at position {}", self.position),
+          None
+        ).with_found(id)),
+        _ => {},
+      }
+
+      let length = 8 + number(&self.pending[4..8], ByteEncodingFormat::BigEndian) as usize;
+      if self.pending.len() < length { break; }
+
+      let chunk : Vec<u8> = self.pending.drain(..length).collect();
+      self.parse_chunk(&chunk).map_err(|err| err.relative_to(self.position))?;
+      self.position += length;
+    }
+    Ok(())
+  }
+
+  /// Ends the stream, fails when header is missing or stream ends in the middle of chunk.
+  pub fn finish(self) -> Result<Midi, MidiParseError> {
+    let state = ParserState::new(String::from("midi"), self.position, self.position + self.pending.len());
+    // chunk is 8 bytes of type and length followed by data, header chunk is at least 14 bytes
+    let length = match (self.pending.len() >= 8, self.midi.is_some()) {
+      (true, _) => 8 + number(&self.pending[4..8], ByteEncodingFormat::BigEndian) as usize,
+      (false, true) => 8,
+      (false, false) => 14,
+    };
+    match self.midi {
+      Some(midi) if self.pending.is_empty() => Ok(midi),
+      _ => Err(state.end_of_buffer(length)),
+    }
+  }
+
+  /// Parses complete `chunk`, offsets of errors are relative to chunk.
+  fn parse_chunk(&mut self, chunk : &[u8]) -> Result<(), MidiParseError> {
+    let midi = match self.midi.as_mut() {
+      Some(midi) => midi,
+      None => {
+        let header = MidiHeaderParser::parse(chunk, &mut ParserState::new(String::from("midi"), 0, chunk.len()))?;
+        let mut midi = Midi::default();
+        midi.add_header(header);
+        self.midi = Some(midi);
+        return Ok(());
+      }
+    };
+
+    let id = &chunk[..4];
+    if id == b"MTrk" {
+      let track = midi.tracks().len();
+      let mut state = ParserState::new(format!("track-{}", track), 0, chunk.len());
+      state.forward(8);
+      let midi_track = MidiTrackParser::new(midi.header().clone(), state, track).parse(chunk)?;
+      midi.add_track(midi_track);
+    } else {
+      // chunks of unknown type are kept, like in MidiParser
+      let position = midi.tracks().len();
+      midi.add_unknown_chunk(UnknownChunk::new(id.try_into().unwrap(), chunk[8..].to_vec()).with_position(position));
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,70 @@
+use std::{thread, time::{Duration, Instant}};
+
+/// Time source of [`Player`](super::Player).
+pub trait Clock {
+  /// time since any fixed instant
+  fn now(&self) -> Duration;
+
+  /// blocks until [`Clock::now`] reaches `time`
+  fn sleep_until(&mut self, time : Duration);
+}
+
+/// Wall clock, time since it was created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+  start : Instant,
+}
+
+impl Default for SystemClock {
+  fn default() -> Self {
+    Self { start : Instant::now() }
+  }
+}
+
+impl SystemClock {
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+impl Clock for SystemClock {
+  fn now(&self) -> Duration {
+    self.start.elapsed()
+  }
+
+  fn sleep_until(&mut self, time : Duration) {
+    let now = self.now();
+    if time > now { thread::sleep(time - now); }
+  }
+}
+
+/// Clock moved only by caller, sleeping jumps straight to the wake up time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VirtualClock {
+  now : Duration,
+}
+
+impl VirtualClock {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn advance(&mut self, duration : Duration) {
+    self.now += duration;
+  }
+
+  /// Moves clock to `time`, clock never goes back.
+  pub fn set(&mut self, time : Duration) {
+    self.now = self.now.max(time);
+  }
+}
+
+impl Clock for VirtualClock {
+  fn now(&self) -> Duration {
+    self.now
+  }
+
+  fn sleep_until(&mut self, time : Duration) {
+    self.set(time);
+  }
+}
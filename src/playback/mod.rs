@@ -0,0 +1,32 @@
+//! Real time playback of [`Midi`](crate::model::core::midi::Midi).
+//!
+//! - [`Schedule`] : events of all tracks merged in time order, with seconds following the tempo map.
+//! - [`Player`] : walks the schedule and sends every message to [`MidiSink`] when it is due on [`Clock`],
+//!   with play / pause / stop, seek, loop, speed and mute / solo per track.
+//!
+//! Player doesn't own a thread, it's driven by [`Player::update`] or [`Player::run`],
+//! so with [`VirtualClock`] and [`RecordingSink`] playback is deterministic and runs without hardware.
+//!
+//! ```
+//! use std::time::Duration;
+//! use rmidirs::playback::{Player, Schedule, RecordingSink, VirtualClock};
+//!
+//! # let midi = rmidirs::model::core::midi::Midi::default();
+//! let mut player = Player::new(Schedule::from(&midi), RecordingSink::new(), VirtualClock::new());
+//! player.run();
+//! for (at, bytes) in player.sink().bytes() {
+//!   println!("{:?} {:02X?}", at, bytes);
+//! }
+//! ```
+
+pub use self::{
+  schedule::{Schedule, ScheduledEvent},
+  clock::{Clock, SystemClock, VirtualClock},
+  sink::{MidiSink, RecordingSink, WireSink, wire_bytes},
+  player::{Player, PlayState}
+};
+
+mod schedule;
+mod clock;
+mod sink;
+mod player;
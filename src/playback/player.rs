@@ -0,0 +1,393 @@
+use std::{collections::{HashMap, HashSet}, time::Duration};
+
+use crate::{
+  primitive::FloatWord,
+  model::core::midi_event::{MidiMessage, channel_message::ChannelMessage}
+};
+
+use super::{Schedule, Clock, MidiSink};
+
+/// All Notes Off controller, sent to every channel on stop
+const ALL_NOTES_OFF : u8 = 123;
+/// Reset All Controllers, drops controllers chased before it
+const RESET_ALL_CONTROLLERS : u8 = 121;
+/// controllers selecting RPN and NRPN
+const NRPN_LSB : u8 = 98;
+const NRPN_MSB : u8 = 99;
+const RPN_LSB : u8 = 100;
+const RPN_MSB : u8 = 101;
+/// Data Entry controllers, applying to selected RPN or NRPN
+const DATA_ENTRY_MSB : u8 = 6;
+const DATA_ENTRY_LSB : u8 = 38;
+/// Data Increment and Decrement, relative so never chased
+const DATA_INCREMENT : u8 = 96;
+const DATA_DECREMENT : u8 = 97;
+
+/// RPN or NRPN number, as (registered, msb, lsb)
+type Parameter = (bool, Option<u8>, Option<u8>);
+
+/// RPN or NRPN selected on channel, with indices of events selecting it
+#[derive(Debug, Clone, Copy, Default)]
+struct Selection {
+  registered : bool,
+  msb : Option<(u8, usize)>,
+  lsb : Option<(u8, usize)>,
+}
+
+impl Selection {
+  /// switching between RPN and NRPN forgets other half of parameter number
+  fn select(&mut self, controller : u8, value : u8, index : usize) {
+    let registered = controller == RPN_MSB || controller == RPN_LSB;
+    if registered != self.registered {
+      *self = Selection { registered, ..Selection::default() };
+    }
+    if controller == RPN_MSB || controller == NRPN_MSB {
+      self.msb = Some((value, index));
+    } else {
+      self.lsb = Some((value, index));
+    }
+  }
+
+  fn parameter(&self) -> Parameter {
+    (self.registered, self.msb.map(|(value, _)| value), self.lsb.map(|(value, _)| value))
+  }
+
+  fn indices(&self) -> impl Iterator<Item = usize> {
+    self.msb.into_iter().chain(self.lsb).map(|(_, index)| index)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayState {
+  Stopped,
+  Playing,
+  Paused,
+}
+
+/// Plays [`Schedule`] to [`MidiSink`], sending every message when it's due on [`Clock`].
+///
+/// - seek chases last controller, program change, channel pressure and pitch bend of every channel before the new position,
+///   in the order they were played, so Bank Select stays in front of what it applies to.
+///   Data Entry is chased for every RPN and NRPN, each after controllers selecting its parameter
+/// - pause, seek and loop release sounding notes with Note Off, stop also sends All Notes Off to all 16 channels
+/// - speed scales tempo, 2.0 plays twice as fast
+/// - muted tracks don't start notes, but their controllers and programs are still sent, so unmuting sounds right.
+///   When any track is soloed, only soloed tracks start notes
+///
+/// Sink gets time when message was due, rather than when it was sent, so sinks with timestamps stay exact.
+#[derive(Debug)]
+pub struct Player<S : MidiSink, C : Clock> {
+  schedule : Schedule,
+  sink : S,
+  clock : C,
+  state : PlayState,
+  /// index of next event of schedule
+  next : usize,
+  /// song seconds at clock time `anchor`
+  position : FloatWord,
+  anchor : Duration,
+  speed : FloatWord,
+  loop_range : Option<(FloatWord, FloatWord)>,
+  muted : HashSet<usize>,
+  soloed : HashSet<usize>,
+  /// notes started and not released yet, as (track, channel, note)
+  sounding : Vec<(usize, u8, u8)>,
+}
+
+impl<S : MidiSink, C : Clock> Player<S, C> {
+  pub fn new(schedule : Schedule, sink : S, clock : C) -> Self {
+    Self {
+      schedule,
+      sink,
+      clock,
+      state : PlayState::Stopped,
+      next : 0,
+      position : 0.0,
+      anchor : Duration::ZERO,
+      speed : 1.0,
+      loop_range : None,
+      muted : HashSet::new(),
+      soloed : HashSet::new(),
+      sounding : Vec::new(),
+    }
+  }
+
+  pub fn schedule(&self) -> &Schedule { &self.schedule }
+
+  pub fn sink(&self) -> &S { &self.sink }
+
+  pub fn sink_mut(&mut self) -> &mut S { &mut self.sink }
+
+  pub fn clock(&self) -> &C { &self.clock }
+
+  /// e.g. to advance [`VirtualClock`](super::VirtualClock)
+  pub fn clock_mut(&mut self) -> &mut C { &mut self.clock }
+
+  pub fn into_sink(self) -> S { self.sink }
+
+  pub fn state(&self) -> PlayState { self.state }
+
+  pub fn speed(&self) -> FloatWord { self.speed }
+
+  pub fn loop_range(&self) -> Option<(FloatWord, FloatWord)> { self.loop_range }
+
+  /// position in song, in seconds
+  pub fn position(&self) -> FloatWord {
+    match self.state {
+      PlayState::Playing => self.song_time(self.clock.now()),
+      _ => self.position,
+    }
+  }
+
+  /// Starts or resumes playback from current position.
+  pub fn play(&mut self) {
+    if self.state == PlayState::Playing { return; }
+    self.anchor = self.clock.now();
+    self.state = PlayState::Playing;
+  }
+
+  /// Pauses playback, sounding notes are released.
+  pub fn pause(&mut self) {
+    if self.state != PlayState::Playing { return; }
+    let now = self.clock.now();
+    self.position = self.song_time(now);
+    self.anchor = now;
+    self.state = PlayState::Paused;
+    self.release(now, |_| true);
+  }
+
+  /// Stops playback and rewinds to start, sounding notes are released and All Notes Off is sent to every channel.
+  pub fn stop(&mut self) {
+    let now = self.clock.now();
+    self.release(now, |_| true);
+    for channel in 0..16 {
+      let message = ChannelMessage::from((0xB0 | channel, &[ALL_NOTES_OFF, 0][..]));
+      self.sink.send(now, &MidiMessage::ChannelMessage(message));
+    }
+    self.rewind();
+  }
+
+  /// Moves to `seconds` of song, keeping play state.
+  /// Sounding notes are released, and controllers and programs in effect at `seconds` are sent.
+  pub fn seek(&mut self, seconds : FloatWord) {
+    let now = self.clock.now();
+    self.release(now, |_| true);
+    self.jump(now, seconds.max(0.0));
+  }
+
+  /// Plays `start..end` seconds over and over, None plays to the end.
+  /// Events at `end` belong to the next round, empty ranges are ignored.
+  pub fn set_loop(&mut self, range : Option<(FloatWord, FloatWord)>) {
+    self.loop_range = range.filter(|(start, end)| *start >= 0.0 && end > start);
+  }
+
+  /// Scales tempo by `speed`, 1.0 plays in tempo of midi.
+  pub fn set_speed(&mut self, speed : FloatWord) {
+    if !speed.is_finite() || speed <= 0.0 { return; }
+    let now = self.clock.now();
+    if self.state == PlayState::Playing {
+      self.position = self.song_time(now);
+      self.anchor = now;
+    }
+    self.speed = speed;
+  }
+
+  pub fn mute(&mut self, track : usize, muted : bool) {
+    if muted { self.muted.insert(track); } else { self.muted.remove(&track); }
+    self.release_silenced();
+  }
+
+  pub fn solo(&mut self, track : usize, soloed : bool) {
+    if soloed { self.soloed.insert(track); } else { self.soloed.remove(&track); }
+    self.release_silenced();
+  }
+
+  /// true when notes of `track` are played, following mute and solo
+  pub fn is_audible(&self, track : usize) -> bool {
+    match self.soloed.is_empty() {
+      true => !self.muted.contains(&track),
+      false => self.soloed.contains(&track),
+    }
+  }
+
+  /// Sends every message due by now on clock.
+  /// Returns clock time when next message is due, None when not playing or when playback reached the end and stopped.
+  pub fn update(&mut self) -> Option<Duration> {
+    if self.state != PlayState::Playing { return None; }
+    let now = self.clock.now();
+
+    loop {
+      let end = self.loop_range.map(|(_, end)| end);
+      while let Some(event) = self.schedule.events().get(self.next) {
+        let at = self.clock_time(event.seconds());
+        if at > now || matches!(end, Some(end) if event.seconds() >= end) { break; }
+        self.dispatch(at, self.next);
+        self.next += 1;
+      }
+
+      match self.loop_range {
+        Some((start, end)) if self.clock_time(end) <= now => {
+          // next round starts when this one ended, so loops don't drift
+          let at = self.clock_time(end);
+          self.release(at, |_| true);
+          self.jump(at, start);
+        },
+        _ => break,
+      }
+    }
+
+    let next = self.schedule.events().get(self.next).map(|event| event.seconds());
+    match (next, self.loop_range) {
+      (Some(seconds), Some((_, end))) => Some(self.clock_time(seconds.min(end))),
+      (Some(seconds), None) => Some(self.clock_time(seconds)),
+      (None, Some((_, end))) => Some(self.clock_time(end)),
+      // playback ends at End Of Track of longest track
+      (None, None) if self.clock_time(self.schedule.duration()) > now => Some(self.clock_time(self.schedule.duration())),
+      (None, None) => {
+        self.release(now, |_| true);
+        self.rewind();
+        None
+      },
+    }
+  }
+
+  /// Plays until the end, sleeping on clock between messages; with loop set it never returns.
+  pub fn run(&mut self) {
+    self.play();
+    while let Some(at) = self.update() {
+      self.clock.sleep_until(at);
+    }
+  }
+
+  /// song seconds at clock time `now`
+  fn song_time(&self, now : Duration) -> FloatWord {
+    self.position + now.saturating_sub(self.anchor).as_secs_f32() * self.speed
+  }
+
+  /// clock time of song `seconds`, while playing
+  fn clock_time(&self, seconds : FloatWord) -> Duration {
+    self.anchor + Duration::from_secs_f32(((seconds - self.position) / self.speed).max(0.0))
+  }
+
+  fn rewind(&mut self) {
+    self.state = PlayState::Stopped;
+    self.next = 0;
+    self.position = 0.0;
+  }
+
+  /// Moves to song `seconds` at clock time `at`, and chases controllers.
+  fn jump(&mut self, at : Duration, seconds : FloatWord) {
+    self.next = self.schedule.index_at(seconds);
+    self.position = seconds;
+    self.anchor = at;
+    self.chase(at);
+  }
+
+  /// Sends event `index` of schedule, keeping track of sounding notes.
+  fn dispatch(&mut self, at : Duration, index : usize) {
+    let event = &self.schedule.events()[index];
+    let track = event.track();
+    let message = match event.message() {
+      MidiMessage::MetaMessage(_) | MidiMessage::Invalid(_) => return,
+      MidiMessage::ChannelMessage(message) => message,
+      message => return self.sink.send(at, message),
+    };
+
+    match (message.event_channel(), message.get_note_number()) {
+      (Some(channel), Some(note)) => {
+        let key = (track, channel, *note as u8);
+        if matches!(message, ChannelMessage::NoteOn(_)) && matches!(message.get_velocity(), Some(velocity) if *velocity > 0) {
+          if !self.is_audible(track) { return; }
+          self.sounding.push(key);
+        } else {
+          // note off is sent only for notes which were started
+          let Some(i) = self.sounding.iter().position(|sounding| *sounding == key) else { return };
+          self.sounding.remove(i);
+        }
+      },
+      (None, _) => return,
+      _ => {},
+    }
+    self.sink.send(at, event.message());
+  }
+
+  /// Sends Note Off for sounding notes of tracks matching `tracks`.
+  fn release<F : Fn(usize) -> bool>(&mut self, at : Duration, tracks : F) {
+    let mut sounding = std::mem::take(&mut self.sounding);
+    sounding.retain(|&(track, channel, note)| {
+      if !tracks(track) { return true; }
+      let message = ChannelMessage::from((0x80 | channel, &[note, 0][..]));
+      self.sink.send(at, &MidiMessage::ChannelMessage(message));
+      false
+    });
+    self.sounding = sounding;
+  }
+
+  /// Releases notes of tracks which aren't audible anymore.
+  fn release_silenced(&mut self) {
+    let silenced : HashSet<usize> = self.sounding.iter()
+      .map(|(track, _, _)| *track)
+      .filter(|track| !self.is_audible(*track))
+      .collect();
+    self.release(self.clock.now(), |track| silenced.contains(&track));
+  }
+
+  /// Sends last controller, program change, channel pressure and pitch bend of every channel before next event.
+  fn chase(&mut self, at : Duration) {
+    /// kinds of chased messages besides controllers 0 to 119
+    const PROGRAM : u16 = 128;
+    const PRESSURE : u16 = 129;
+    const PITCH_BEND : u16 = 130;
+
+    let mut chased : HashMap<(u8, u16), usize> = HashMap::new();
+    let mut selections : HashMap<u8, Selection> = HashMap::new();
+    // last data entry of every parameter, with selection it was sent after
+    let mut entries : HashMap<(u8, Parameter, u8), (Selection, usize)> = HashMap::new();
+    for (index, event) in self.schedule.events()[..self.next].iter().enumerate() {
+      let MidiMessage::ChannelMessage(message) = event.message() else { continue };
+      let Some(channel) = message.event_channel() else { continue };
+      let kind = match message {
+        ChannelMessage::Controller(_) => {
+          let Some((controller, value)) = message.get_controller() else { continue };
+          match *controller as u8 {
+            RESET_ALL_CONTROLLERS => {
+              chased.retain(|(chased_channel, kind), _| *chased_channel != channel || *kind == PROGRAM);
+              entries.retain(|(chased_channel, _, _), _| *chased_channel != channel);
+              selections.remove(&channel);
+              continue;
+            },
+            // other channel mode messages aren't chased
+            120 ..= 127 => continue,
+            controller @ (NRPN_LSB ..= RPN_MSB) => {
+              selections.entry(channel).or_default().select(controller, *value as u8, index);
+              continue;
+            },
+            controller @ (DATA_ENTRY_MSB | DATA_ENTRY_LSB) => {
+              let selection = selections.get(&channel).copied().unwrap_or_default();
+              entries.insert((channel, selection.parameter(), controller), (selection, index));
+              continue;
+            },
+            DATA_INCREMENT | DATA_DECREMENT => continue,
+            controller => controller as u16,
+          }
+        },
+        ChannelMessage::ProgramChange(_) => PROGRAM,
+        ChannelMessage::ChannelAfterTouch(_) => PRESSURE,
+        ChannelMessage::PitchBend(_) => PITCH_BEND,
+        _ => continue,
+      };
+      chased.insert((channel, kind), index);
+    }
+
+    // selections sent in front of data entry, and last selection left selected
+    let mut indices : Vec<usize> = chased.into_values()
+      .chain(entries.into_values().flat_map(|(selection, index)| selection.indices().chain(Some(index))))
+      .chain(selections.into_values().flat_map(|selection| selection.indices()))
+      .collect();
+    indices.sort_unstable();
+    indices.dedup();
+    for index in indices {
+      self.sink.send(at, self.schedule.events()[index].message());
+    }
+  }
+}
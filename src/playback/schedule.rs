@@ -0,0 +1,79 @@
+use crate::{
+  primitive::FloatWord,
+  model::{
+    core::{midi::Midi, midi_event::MidiMessage},
+    timing::TempoMap
+  }
+};
+
+/// Event of [`Schedule`], with its track, absolute tick and time in seconds.
+#[derive(Debug, Clone)]
+pub struct ScheduledEvent {
+  track : usize,
+  tick : u64,
+  seconds : FloatWord,
+  message : MidiMessage,
+}
+
+impl ScheduledEvent {
+  pub fn track(&self) -> usize { self.track }
+
+  pub fn tick(&self) -> u64 { self.tick }
+
+  /// time from start of midi, following tempo changes
+  pub fn seconds(&self) -> FloatWord { self.seconds }
+
+  pub fn message(&self) -> &MidiMessage { &self.message }
+}
+
+/// Events of all tracks merged in time order, with seconds following [`TempoMap`].
+///
+/// Events at same tick keep order of tracks, and order within their track.
+/// End Of Track events are dropped, other meta events are kept.
+/// Tracks of Format 2 midi are merged too, as if they were played together.
+#[derive(Debug, Clone, Default)]
+pub struct Schedule {
+  events : Vec<ScheduledEvent>,
+  tempo_map : TempoMap,
+  /// seconds of End Of Track of longest track
+  duration : FloatWord,
+}
+
+impl Schedule {
+  pub fn events(&self) -> &[ScheduledEvent] { &self.events }
+
+  pub fn tempo_map(&self) -> &TempoMap { &self.tempo_map }
+
+  /// length of midi in seconds, till End Of Track of longest track
+  pub fn duration(&self) -> FloatWord { self.duration }
+
+  pub fn len(&self) -> usize { self.events.len() }
+
+  pub fn is_empty(&self) -> bool { self.events.is_empty() }
+
+  /// index of first event at or after `seconds`
+  pub fn index_at(&self, seconds : FloatWord) -> usize {
+    self.events.partition_point(|event| event.seconds < seconds)
+  }
+}
+
+impl From<&Midi> for Schedule {
+  fn from(midi : &Midi) -> Self {
+    let tempo_map = TempoMap::from(midi);
+
+    let mut events : Vec<ScheduledEvent> = midi.tracks().iter().enumerate()
+      .flat_map(|(track, midi_track)| midi_track.iter_absolute()
+        .filter(|(_, event)| !event.is_end_of_track())
+        .map(move |(tick, event)| ScheduledEvent { track, tick, seconds : 0.0, message : event.message().clone() }))
+      .collect();
+    // stable sort keeps order of tracks and of events within track
+    events.sort_by_key(|event| event.tick);
+    for event in events.iter_mut() {
+      event.seconds = tempo_map.seconds(event.tick);
+    }
+
+    let end_tick = midi.tracks().iter().map(|track| track.end_tick()).max().unwrap_or_default();
+    let duration = tempo_map.seconds(end_tick);
+    Self { events, tempo_map, duration }
+  }
+}
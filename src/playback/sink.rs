@@ -0,0 +1,82 @@
+use std::{io::{self, Write}, time::Duration};
+
+use crate::model::core::midi_event::MidiMessage;
+
+/// Output of [`Player`](super::Player), e.g. MIDI port, synthesizer or recorder.
+pub trait MidiSink {
+  /// Sends `message` due at clock time `at`.
+  /// Meta and invalid messages are never sent, sysex data is as stored in Standard Midi File.
+  fn send(&mut self, at : Duration, message : &MidiMessage);
+}
+
+/// Bytes of `message` as sent on the wire, empty for meta and invalid messages.
+/// `F0` sysex gets its `F0` status back, `F7` packets are sent as they are.
+pub fn wire_bytes(message : &MidiMessage) -> Vec<u8> {
+  match message {
+    MidiMessage::ChannelMessage(message) => message.clone().into(),
+    MidiMessage::SysMessage(sys_event) if sys_event.event_byte() == 0xF0 => [&[0xF0], sys_event.data()].concat(),
+    MidiMessage::SysMessage(sys_event) => sys_event.data().to_vec(),
+    MidiMessage::SystemCommon(message) => (*message).into(),
+    MidiMessage::SystemRealTime(message) => (*message).into(),
+    MidiMessage::MetaMessage(_) | MidiMessage::Invalid(_) => Vec::new(),
+  }
+}
+
+/// Sink keeping every message with its time, for tests and for recording.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingSink {
+  messages : Vec<(Duration, MidiMessage)>,
+}
+
+impl RecordingSink {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn messages(&self) -> &[(Duration, MidiMessage)] { &self.messages }
+
+  /// messages as wire bytes, easier to compare
+  pub fn bytes(&self) -> Vec<(Duration, Vec<u8>)> {
+    self.messages.iter().map(|(at, message)| (*at, wire_bytes(message))).collect()
+  }
+
+  pub fn clear(&mut self) {
+    self.messages.clear();
+  }
+}
+
+impl MidiSink for RecordingSink {
+  fn send(&mut self, at : Duration, message : &MidiMessage) {
+    self.messages.push((at, message.clone()));
+  }
+}
+
+/// Sink writing wire bytes to serial port, pipe or any other writer, as soon as message is sent.
+///
+/// Write errors don't stop playback, first of them is kept, see [`WireSink::error`].
+#[derive(Debug)]
+pub struct WireSink<W : Write> {
+  writer : W,
+  error : Option<io::Error>,
+}
+
+impl<W : Write> WireSink<W> {
+  pub fn new(writer : W) -> Self {
+    Self { writer, error : None }
+  }
+
+  /// first write error
+  pub fn error(&self) -> Option<&io::Error> { self.error.as_ref() }
+
+  pub fn into_inner(self) -> W { self.writer }
+}
+
+impl<W : Write> MidiSink for WireSink<W> {
+  fn send(&mut self, _at : Duration, message : &MidiMessage) {
+    let bytes = wire_bytes(message);
+    if bytes.is_empty() { return; }
+    if let Err(err) = self.writer.write_all(&bytes).and_then(|_| self.writer.flush()) {
+      self.error.get_or_insert(err);
+    }
+  }
+}
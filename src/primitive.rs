@@ -51,6 +51,12 @@ impl From<&[u8]>  for M2Byte {
   }
 }
 
+impl From<M2Byte> for Vec<u8> {
+  fn from(b2: M2Byte) -> Self {
+    vec![((b2.0 >> 8) & 0xFF) as u8, (b2.0 & 0xFF) as u8]
+  }
+}
+
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct M1Byte(Word);
@@ -143,7 +149,7 @@ impl  From<&[u8]> for MXByte {
     let mut vec : Vec<u8> = vec![0; mxbyte.1];
     let mut num = mxbyte.0;
     for i in (0..mxbyte.1).rev() {
-      vec[i] = ((num & 0xF7) | 0x80) as u8;
+      vec[i] = ((num & 0x7F) | 0x80) as u8;
       num >>= 7;
     } vec[mxbyte.1 - 1] &= 0x7F;
     vec
@@ -223,4 +229,28 @@ impl_midi_dtypes!(m1byte, M1Byte, 0xFF);
 impl_midi_dtypes!(m4bits, M4Bits, 0xF);
 impl_midi_dtypes!(m1bit, M1Bit, 0x1);
 
+/// Serializes midi data types as plain numbers, deserialized numbers are masked like in `From`.
+#[cfg(feature = "serde")]
+macro_rules! impl_serde_for_mtypes {
+  ($($t : tt),*) => {
+    $(
+      impl serde::Serialize for $t {
+        fn serialize<S : serde::Serializer>(&self, serializer : S) -> Result<S::Ok, S::Error> {
+          serializer.serialize_u32(self.0)
+        }
+      }
+
+      impl<'de> serde::Deserialize<'de> for $t {
+        fn deserialize<D : serde::Deserializer<'de>>(deserializer : D) -> Result<Self, D::Error> {
+          <Word as serde::Deserialize>::deserialize(deserializer).map($t::from)
+        }
+      }
+    )*
+  };
+}
+
+// length of MXByte is recomputed from the number, so it is always shortest encoding
+#[cfg(feature = "serde")]
+impl_serde_for_mtypes!(MXByte, M4Byte, M3Byte, M2Byte, M1Byte, M4Bits, M1Bit);
+
 pub use {mxbyte, m4byte, m3byte, m2byte, m1byte, m4bits, m1bit};
@@ -1,8 +1,8 @@
-use std::{marker::PhantomData};
+use std::{marker::PhantomData, borrow::Cow};
 
 use crate::{
   utils::{ByteEncodingFormat}, 
-  model::core::midi::Midi, parser::MidiParser
+  model::core::{midi::Midi, midi_ref::MidiRef}, parser::{MidiParser, MidiParseError}
 };
 
 use super::{FileSrcType, MidiFileReader, Reader};
@@ -17,7 +17,8 @@ impl<'a> MidiFileReader<'a>{
 pub struct Buffer<'a> {
   src : FileSrcType,
   path : Option<&'a str>,
-  contents : Vec<u8>,
+  /// borrowed for in memory buffers, owned for buffers read from local or web files.
+  contents : Cow<'a, [u8]>,
   length : usize,
   iter : usize,
 }
@@ -32,7 +33,7 @@ impl<'a> Buffer<'a> {
       src : FileSrcType::Buffer,
       path : None,
       length : contents.len(),
-      contents : contents.to_vec(),
+      contents : Cow::Borrowed(contents),
       iter : 0,
     }
   }
@@ -40,7 +41,7 @@ impl<'a> Buffer<'a> {
     Self {
       src : FileSrcType::Local,
       path : Some(path),
-      contents,
+      contents : Cow::Owned(contents),
       length,
       iter: 0,
     }
@@ -50,7 +51,7 @@ impl<'a> Buffer<'a> {
     Buffer {
       src : FileSrcType::Web,
       path : Some(path),
-      contents,
+      contents : Cow::Owned(contents),
       length,
       iter: 0,
     }
@@ -66,4 +67,4 @@ impl<'a> Reader for Buffer<'a> {
   fn parse(&self) -> Midi {
       MidiParser::parse(&self.contents).unwrap()
   }
-}
\ No newline at end of file
+}
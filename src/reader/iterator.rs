@@ -0,0 +1,248 @@
+use std::io::{self, Read, ErrorKind};
+
+use crate::{
+  model::core::{midi_header::MidiHeader, midi_event::MidiEvent},
+  parser::{
+    RawEvent, MidiParseError, MidiParseErrorKind, ChunkReader,
+    parser_state::ParserState,
+    midi_header_parser::MidiHeaderParser,
+    midi_track_header_parser::MidiTrackHeaderParser,
+    midi_event_parser::MidiEventParser
+  },
+  utils::{ByteEncodingFormat, functions::number}
+};
+
+/// Pull based event reader over a midi buffer.
+/// 
+/// Yields events of all tracks, track after track, as `(track index, absolute tick, event)` 
+/// without building the complete Midi model. Iteration stops after the first error.
+/// 
+/// `next_raw` yields [`RawEvent`]s that borrow their payload from buffer, 
+/// which is enough for scanning files for statistics without allocating per event.
+/// 
+/// ```no_run
+/// use rmidirs::reader::iterator::EventReader;
+/// 
+/// let buf = std::fs::read("test.mid").unwrap();
+/// let notes = EventReader::new(&buf).unwrap()
+///   .filter_map(|event| event.ok())
+///   .filter(|(_, _, event)| event.is_note_on_event())
+///   .count();
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventReader<'a> {
+  buf : &'a [u8],
+  header : MidiHeader,
+  tracks : Vec<ParserState>,
+  track : usize,
+  event : usize,
+  tick : u64,
+  event_parser : MidiEventParser,
+  failed : bool,
+}
+
+impl<'a> EventReader<'a> {
+  /// Reads the midi header and track headers of buffer, events are read lazily.
+  pub fn new(buf : &'a [u8]) -> Result<Self, MidiParseError> {
+    let mut state = ChunkReader::smf_state(buf).map_err(|err| err.with_context(buf))?;
+
+    let header = MidiHeaderParser::parse(buf, &mut state).map_err(|err| err.with_context(buf))?;
+
+    let tracks = MidiTrackHeaderParser::parse(buf, header.clone(), state.with_name(String::from("track-header-parser")))
+      .map_err(|err| err.with_context(buf))?
+      .iter()
+      .map(|track_parser| track_parser.state().clone())
+      .collect();
+
+    Ok(Self { buf, header, tracks, track : 0, event : 0, tick : 0, event_parser : MidiEventParser::new(), failed : false })
+  }
+
+  pub fn header(&self) -> &MidiHeader { &self.header }
+
+  /// number of tracks found in buffer
+  pub fn track_count(&self) -> usize { self.tracks.len() }
+
+  /// Returns the next event without decoding it.
+  pub fn next_raw(&mut self) -> Option<Result<(usize, u64, RawEvent<'a>), MidiParseError>> {
+    loop {
+      if self.failed { return None; }
+
+      let state = self.tracks.get_mut(self.track)?;
+
+      if state.curr() >= state.end() {
+        self.track += 1;
+        self.event = 0;
+        self.tick = 0;
+        self.event_parser = MidiEventParser::new();
+        continue;
+      }
+
+      return Some(match self.event_parser.parse_raw_event(self.buf, state) {
+        Ok(raw_event) => {
+          self.event += 1;
+          self.tick += raw_event.delta_time() as u64;
+          Ok((self.track, self.tick, raw_event))
+        },
+        Err(err) => {
+          self.failed = true;
+          Err(err.in_track(self.track).at_event(self.event).with_context(self.buf))
+        }
+      });
+    }
+  }
+}
+
+impl<'a> Iterator for EventReader<'a> {
+  type Item = Result<(usize, u64, MidiEvent), MidiParseError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.next_raw().map(|raw_event| raw_event.map(|(track, tick, raw_event)| (track, tick, raw_event.to_event())))
+  }
+}
+
+/// Pull based event reader over any byte stream.
+/// 
+/// Works like [`EventReader`], but only keeps single track chunk in memory at a time,
+/// chunk is read from stream when its first event is requested.
+#[derive(Debug)]
+pub struct StreamEventReader<R : Read> {
+  reader : R,
+  header : MidiHeader,
+  chunk : Vec<u8>,
+  state : ParserState,
+  /// number of bytes read from stream
+  position : usize,
+  track : Option<usize>,
+  event : usize,
+  tick : u64,
+  event_parser : MidiEventParser,
+  failed : bool,
+}
+
+impl<R : Read> StreamEventReader<R> {
+  /// Reads the midi header from stream, tracks are read lazily.
+  /// RIFF `RMID` container is not unwrapped for streams.
+  pub fn new(mut reader : R) -> Result<Self, MidiParseError> {
+    const ENC_FORMAT: ByteEncodingFormat = ByteEncodingFormat::BigEndian;
+
+    let state = ParserState::new(String::from("midi"), 0, 14);
+
+    let mut buf = vec![0; 8];
+    reader.read_exact(&mut buf).map_err(|err| MidiParseError::io(state.clone(), err))?;
+
+    // header data is 6 bytes long, but may be longer in later versions of standard
+    let length = (number(&buf[4..8], ENC_FORMAT) as usize).max(6);
+    read_exactly(&mut reader, &mut buf, length).map_err(|err| MidiParseError::io(state.clone(), err))?;
+
+    let mut state = ParserState::new(String::from("midi"), 0, buf.len());
+    let header = MidiHeaderParser::parse(&buf, &mut state)?;
+
+    Ok(Self {
+      reader,
+      header,
+      chunk : Vec::new(),
+      state,
+      position : buf.len(),
+      track : None,
+      event : 0,
+      tick : 0,
+      event_parser : MidiEventParser::new(),
+      failed : false
+    })
+  }
+
+  pub fn header(&self) -> &MidiHeader { &self.header }
+
+  /// Reads the next track chunk from stream, skipping chunks of unknown type.
+  /// Returns false if stream ended at chunk boundary.
+  fn next_chunk(&mut self) -> Result<bool, MidiParseError> {
+    const ENC_FORMAT: ByteEncodingFormat = ByteEncodingFormat::BigEndian;
+
+    loop {
+      let offset = self.position;
+      let track = self.track.map_or(0, |track| track + 1);
+      let error_state = ParserState::new(format!("track-{}", track), offset, offset);
+
+      let mut head = [0; 8];
+      let mut read = 0;
+      while read < head.len() {
+        match self.reader.read(&mut head[read ..]) {
+          Ok(0) => break,
+          Ok(n) => read += n,
+          Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+          Err(err) => return Err(MidiParseError::io(error_state.clone(), err)),
+        }
+      }
+      if read == 0 { return Ok(false); }
+      if read < head.len() { error_state.ensure(head.len())?; }
+
+      let is_track = &head[..4] == b"MTrk";
+      if !is_track && !ChunkReader::is_chunk_type(&head[..4]) {
+        return Err(MidiParseError::new(
+          error_state.with_name(format!("track-{}-err-header-{:?}", track, head[..4].to_vec())),
+          MidiParseErrorKind::InvalidMidiTrackHeader,
+          format!("invalid track header found at position {}", offset),
+          None
+        ));
+      }
+
+      let length = number(&head[4..8], ENC_FORMAT) as usize;
+      self.chunk.clear();
+      read_exactly(&mut self.reader, &mut self.chunk, length).map_err(|err| MidiParseError::io(error_state.clone(), err))?;
+      self.position += 8 + length;
+
+      if !is_track { continue; }
+
+      // positions of chunk state are relative to chunk, so chunk offset in stream is kept in name
+      self.state = ParserState::new(format!("track-{}@{}", track, offset + 8), 0, length);
+      self.track = Some(track);
+      self.event = 0;
+      self.tick = 0;
+      self.event_parser = MidiEventParser::new();
+      return Ok(true);
+    }
+  }
+}
+
+impl<R : Read> Iterator for StreamEventReader<R> {
+  type Item = Result<(usize, u64, MidiEvent), MidiParseError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if self.failed { return None; }
+
+      if self.track.is_none() || self.state.curr() >= self.state.end() {
+        match self.next_chunk() {
+          Ok(true) => continue,
+          Ok(false) => return None,
+          Err(err) => { self.failed = true; return Some(Err(err)) }
+        }
+      }
+
+      let track = self.track.unwrap_or(0);
+      return Some(match self.event_parser.parse_raw_event(&self.chunk, &mut self.state) {
+        Ok(raw_event) => {
+          self.event += 1;
+          self.tick += raw_event.delta_time() as u64;
+          Ok((track, self.tick, raw_event.to_event()))
+        },
+        Err(err) => {
+          self.failed = true;
+          // positions of chunk state are relative to chunk
+          let chunk_offset = self.position - self.chunk.len();
+          Err(err.in_track(track).at_event(self.event).with_context(&self.chunk).relative_to(chunk_offset))
+        }
+      });
+    }
+  }
+}
+
+/// Appends `length` bytes of `reader` to `buf`.
+/// Buffer grows while bytes arrive, so length read from stream can't force huge allocation.
+fn read_exactly<R : Read>(reader : &mut R, buf : &mut Vec<u8>, length : usize) -> io::Result<()> {
+  let read = reader.take(length as u64).read_to_end(buf)?;
+  if read < length {
+    return Err(io::Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer"));
+  }
+  Ok(())
+}
@@ -1,4 +1,9 @@
-use std::{fs::File, io::Read, ops::Deref, marker::PhantomData};
+use std::{fs::File, io::Read, ops::Deref, marker::PhantomData, path::Path};
+
+use crate::{
+  model::core::midi::Midi,
+  parser::{MidiParser, MidiParseError, parser_state::ParserState}
+};
 
 use super::{MidiFileReader, FileSrcType, buffer::Buffer};
 
@@ -14,6 +19,26 @@ impl<'a> MidiFileReader<'a>  {
     // (length, contents)
     Local(Buffer::from_local(path, contents, length))
   }
+
+  /// Reads and parses many local files, in parallel when `rayon` feature is enabled.
+  /// Results are returned in order of paths.
+  pub fn parse_local_files<P : AsRef<Path> + Sync>(paths : &[P]) -> Vec<Result<Midi, MidiParseError>> {
+    let parse = |path : &P| -> Result<Midi, MidiParseError> {
+      let path = path.as_ref();
+      let contents = std::fs::read(path)
+        .map_err(|err| MidiParseError::io(ParserState::new(path.display().to_string(), 0, 0), err))?;
+      MidiParser::parse(&contents)
+    };
+
+    cfg_if::cfg_if! {
+      if #[cfg(feature = "rayon")] {
+        use rayon::prelude::*;
+        paths.par_iter().map(parse).collect()
+      } else {
+        paths.iter().map(parse).collect()
+      }
+    }
+  }
 }
 
 impl<'a> Local<'a> {
@@ -4,13 +4,17 @@ use std::marker::PhantomData;
 
 use crate::{
   utils::ByteEncodingFormat, 
-  model::core::midi::Midi
+  model::core::{midi::Midi, midi_ref::MidiRef}
 };
 
-use self::{buffer::Buffer};
+use self::{buffer::Buffer, iterator::EventReader};
+
+use crate::parser::{MidiParser, MidiParseError, Diagnostic, ParseOptions};
 
 pub mod buffer;
+pub mod iterator;
 pub mod local;
+pub mod stream;
 pub(crate) mod web;
 
 
@@ -40,6 +44,31 @@ pub trait Reader {
   fn bytes(&self) -> &[u8];
 
   fn parse(&self) -> Midi;
+
+  /// Parses midi, returns error of first part which couldn't be parsed.
+  fn try_parse(&self) -> Result<Midi, MidiParseError> {
+    MidiParser::parse(self.bytes())
+  }
+
+  /// Parses midi without failing on broken tracks, problems are reported as diagnostics.
+  fn parse_lenient(&self) -> Result<(Midi, Vec<Diagnostic>), MidiParseError> {
+    MidiParser::parse_lenient(self.bytes())
+  }
+
+  /// Parses midi following the `options`.
+  fn parse_with(&self, options : &ParseOptions) -> Result<(Midi, Vec<Diagnostic>), MidiParseError> {
+    MidiParser::parse_with(self.bytes(), options)
+  }
+
+  /// Lazily reads events of all tracks, without building the Midi model.
+  fn events(&self) -> Result<EventReader<'_>, MidiParseError> {
+    EventReader::new(self.bytes())
+  }
+
+  /// Borrowed view of midi, decoded lazily without copying the bytes.
+  fn midi_ref(&self) -> Result<MidiRef<'_>, MidiParseError> {
+    MidiRef::parse(self.bytes())
+  }
 }
 
 
@@ -0,0 +1,51 @@
+use std::io::{Read, ErrorKind};
+
+use crate::{
+  model::core::midi::Midi,
+  parser::{MidiStreamParser, MidiParseError, parser_state::ParserState}
+};
+
+use super::MidiFileReader;
+
+/// size of pieces read from stream
+const READ_LEN : usize = 8 * 1024;
+
+impl<'a> MidiFileReader<'a> {
+  /// Parses midi from any byte stream, e.g. HTTP body, S3 object or decompressing reader.
+  /// Chunks are parsed as soon as they're read, see [`MidiStreamParser`].
+  pub fn from_reader<R : Read>(mut reader : R) -> Result<Midi, MidiParseError> {
+    let mut parser = MidiStreamParser::new();
+    let mut buf = vec![0; READ_LEN];
+    loop {
+      match reader.read(&mut buf) {
+        Ok(0) => return parser.finish(),
+        Ok(n) => parser.push(&buf[..n])?,
+        Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+        Err(err) => return Err(io_error(&parser, err)),
+      }
+    }
+  }
+
+  /// Parses midi from any async byte stream, like [`MidiFileReader::from_reader`].
+  #[cfg(feature = "tokio")]
+  pub async fn from_async_reader<R : tokio::io::AsyncRead + Unpin>(mut reader : R) -> Result<Midi, MidiParseError> {
+    use tokio::io::AsyncReadExt;
+
+    let mut parser = MidiStreamParser::new();
+    let mut buf = vec![0; READ_LEN];
+    loop {
+      match reader.read(&mut buf).await {
+        Ok(0) => return parser.finish(),
+        Ok(n) => parser.push(&buf[..n])?,
+        Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+        Err(err) => return Err(io_error(&parser, err)),
+      }
+    }
+  }
+}
+
+/// io error at position of stream reached by `parser`
+fn io_error(parser : &MidiStreamParser, err : std::io::Error) -> MidiParseError {
+  let position = parser.position();
+  MidiParseError::io(ParserState::new(String::from("midi"), position, position), err)
+}
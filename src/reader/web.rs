@@ -1,9 +1,23 @@
+use std::ops::Deref;
+
 use super::{MidiFileReader, buffer::Buffer};
 
 impl<'a> MidiFileReader<'a>  {
-  pub fn web(path : &str) -> MidiFileReader {
-    todo!()
+  /// Reader of midi fetched from `url`, contents are fetched by the caller (e.g. `fetch` in js),
+  /// as blocking requests can't be made from web-assembly.
+  pub fn web(url : &'a str, contents : Vec<u8>) -> Web<'a> {
+    let length = contents.len();
+    Web(Buffer::from_web(url, contents, length))
   }
 }
 
-pub struct Web<'a>(Buffer<'a>);
\ No newline at end of file
+#[derive(Debug)]
+pub struct Web<'a>(Buffer<'a>);
+
+impl<'a> Deref for Web<'a>{
+  type Target = Buffer<'a>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
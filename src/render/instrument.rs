@@ -0,0 +1,227 @@
+use std::f32::consts::PI;
+
+use crate::primitive::FloatWord;
+
+/// Oscillator waveform of built-in patches, saw and square are band limited with polyBLEP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+  Sine,
+  Saw,
+  Square,
+}
+
+impl Waveform {
+  /// Sample at `phase` in `0..1`, `step` is phase increment per sample.
+  pub fn sample(&self, phase : FloatWord, step : FloatWord) -> FloatWord {
+    match self {
+      Waveform::Sine => (2.0 * PI * phase).sin(),
+      Waveform::Saw => 2.0 * phase - 1.0 - poly_blep(phase, step),
+      Waveform::Square => {
+        let square = if phase < 0.5 { 1.0 } else { -1.0 };
+        square + poly_blep(phase, step) - poly_blep((phase + 0.5).fract(), step)
+      },
+    }
+  }
+}
+
+/// correction of step discontinuity at phase 0, smoothed over one sample on both sides
+fn poly_blep(phase : FloatWord, step : FloatWord) -> FloatWord {
+  if phase < step {
+    let t = phase / step;
+    2.0 * t - t * t - 1.0
+  } else if phase > 1.0 - step {
+    let t = (phase - 1.0) / step;
+    t * t + 2.0 * t + 1.0
+  } else {
+    0.0
+  }
+}
+
+/// ADSR envelope, times in seconds and sustain level in `0..=1`.
+/// Envelope with zero sustain ends after decay, like plucked and struck instruments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Envelope {
+  pub attack : FloatWord,
+  pub decay : FloatWord,
+  pub sustain : FloatWord,
+  pub release : FloatWord,
+}
+
+impl Envelope {
+  pub const fn new(attack : FloatWord, decay : FloatWord, sustain : FloatWord, release : FloatWord) -> Self {
+    Self { attack, decay, sustain, release }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+  Attack,
+  Decay,
+  Sustain,
+  Release,
+  Done,
+}
+
+/// Level of [`Envelope`] while note plays, levels move linearly.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EnvelopeState {
+  envelope : Envelope,
+  stage : Stage,
+  level : FloatWord,
+  /// level falling per second in release
+  release_rate : FloatWord,
+}
+
+impl EnvelopeState {
+  pub fn new(envelope : Envelope) -> Self {
+    Self { envelope, stage : Stage::Attack, level : 0.0, release_rate : 0.0 }
+  }
+
+  pub fn release(&mut self) {
+    if self.stage == Stage::Done { return; }
+    self.release_rate = self.level / self.envelope.release.max(0.001);
+    self.stage = Stage::Release;
+  }
+
+  pub fn is_done(&self) -> bool { self.stage == Stage::Done }
+
+  pub fn is_released(&self) -> bool { matches!(self.stage, Stage::Release | Stage::Done) }
+
+  pub fn level(&self) -> FloatWord { self.level }
+
+  /// Moves envelope by `dt` seconds, returns level.
+  pub fn next(&mut self, dt : FloatWord) -> FloatWord {
+    let Envelope { attack, decay, sustain, .. } = self.envelope;
+    match self.stage {
+      Stage::Attack => {
+        self.level = if attack > 0.0 { self.level + dt / attack } else { 1.0 };
+        if self.level >= 1.0 {
+          self.level = 1.0;
+          self.stage = Stage::Decay;
+        }
+      },
+      Stage::Decay => {
+        self.level = if decay > 0.0 { self.level - (1.0 - sustain) * dt / decay } else { sustain };
+        if self.level <= sustain {
+          self.level = sustain;
+          self.stage = if sustain > 0.0 { Stage::Sustain } else { Stage::Done };
+        }
+      },
+      Stage::Sustain => self.level = sustain,
+      Stage::Release => {
+        self.level -= self.release_rate * dt;
+        if self.level <= 0.0 {
+          self.level = 0.0;
+          self.stage = Stage::Done;
+        }
+      },
+      Stage::Done => self.level = 0.0,
+    }
+    self.level
+  }
+}
+
+/// Built-in melodic patch, oscillator with envelope and loudness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Patch {
+  pub waveform : Waveform,
+  pub envelope : Envelope,
+  pub gain : FloatWord,
+}
+
+impl Patch {
+  const fn new(waveform : Waveform, envelope : Envelope, gain : FloatWord) -> Self {
+    Self { waveform, envelope, gain }
+  }
+
+  /// Patch of General MIDI `program`, one per family of 8 programs.
+  pub fn from_program(program : u8) -> Self {
+    PATCHES[(program as usize / 8) % PATCHES.len()]
+  }
+}
+
+/// patches of General MIDI families, piano, chromatic percussion, organ, guitar, bass, strings, ensemble, brass,
+/// reed, pipe, synth lead, synth pad, synth effects, ethnic, percussive and sound effects
+const PATCHES : [Patch; 16] = [
+  Patch::new(Waveform::Sine, Envelope::new(0.005, 1.5, 0.2, 0.3), 1.0),
+  Patch::new(Waveform::Sine, Envelope::new(0.002, 0.8, 0.0, 0.3), 1.0),
+  Patch::new(Waveform::Square, Envelope::new(0.01, 0.0, 1.0, 0.05), 0.5),
+  Patch::new(Waveform::Saw, Envelope::new(0.005, 1.2, 0.1, 0.2), 0.6),
+  Patch::new(Waveform::Saw, Envelope::new(0.005, 0.5, 0.5, 0.1), 0.7),
+  Patch::new(Waveform::Saw, Envelope::new(0.1, 0.2, 0.8, 0.3), 0.5),
+  Patch::new(Waveform::Saw, Envelope::new(0.15, 0.2, 0.8, 0.4), 0.5),
+  Patch::new(Waveform::Saw, Envelope::new(0.05, 0.2, 0.7, 0.2), 0.5),
+  Patch::new(Waveform::Square, Envelope::new(0.03, 0.1, 0.8, 0.15), 0.4),
+  Patch::new(Waveform::Sine, Envelope::new(0.05, 0.1, 0.9, 0.2), 1.0),
+  Patch::new(Waveform::Square, Envelope::new(0.01, 0.1, 0.8, 0.1), 0.4),
+  Patch::new(Waveform::Saw, Envelope::new(0.3, 0.5, 0.7, 0.8), 0.5),
+  Patch::new(Waveform::Sine, Envelope::new(0.2, 0.5, 0.6, 1.0), 1.0),
+  Patch::new(Waveform::Saw, Envelope::new(0.01, 0.6, 0.3, 0.2), 0.6),
+  Patch::new(Waveform::Sine, Envelope::new(0.001, 0.4, 0.0, 0.2), 1.0),
+  Patch::new(Waveform::Square, Envelope::new(0.01, 0.3, 0.5, 0.3), 0.4),
+];
+
+/// Drum of built-in kit, tone sweeping from `tone_start` to `tone_end` Hz mixed with noise, decaying exponentially.
+/// Drums ignore Note Off, as in General MIDI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drum {
+  pub tone_start : FloatWord,
+  pub tone_end : FloatWord,
+  /// seconds of tone sweep
+  pub sweep : FloatWord,
+  pub tone : FloatWord,
+  pub noise : FloatWord,
+  /// noise is high passed, for cymbals and hats
+  pub bright : bool,
+  /// seconds to fall to `1 / e`
+  pub decay : FloatWord,
+}
+
+impl Drum {
+  const fn new(tone_start : FloatWord, tone_end : FloatWord, sweep : FloatWord, tone : FloatWord, noise : FloatWord, bright : bool, decay : FloatWord) -> Self {
+    Self { tone_start, tone_end, sweep, tone, noise, bright, decay }
+  }
+
+  /// Drum of General MIDI percussion `note`, notes without own drum get short click.
+  pub fn from_note(note : u8) -> Self {
+    match note {
+      35 | 36 => Self::new(150.0, 45.0, 0.05, 1.0, 0.05, false, 0.25),
+      38 | 40 => Self::new(220.0, 180.0, 0.02, 0.4, 0.6, false, 0.12),
+      37 | 39 => Self::new(0.0, 0.0, 0.0, 0.0, 0.8, true, 0.05),
+      // toms, low to high
+      41 | 43 | 45 | 47 | 48 | 50 => {
+        let pitch = 80.0 + (note - 41) as FloatWord * 15.0;
+        Self::new(pitch * 1.5, pitch, 0.1, 0.9, 0.1, false, 0.3)
+      },
+      42 | 44 => Self::new(0.0, 0.0, 0.0, 0.0, 0.6, true, 0.04),
+      46 => Self::new(0.0, 0.0, 0.0, 0.0, 0.6, true, 0.25),
+      49 | 52 | 55 | 57 => Self::new(0.0, 0.0, 0.0, 0.0, 0.5, true, 0.9),
+      51 | 53 | 59 => Self::new(3000.0, 3000.0, 0.0, 0.1, 0.35, true, 0.6),
+      _ => Self::new(0.0, 0.0, 0.0, 0.0, 0.5, true, 0.08),
+    }
+  }
+
+  /// tone frequency at `time` seconds after note on
+  pub fn frequency(&self, time : FloatWord) -> FloatWord {
+    if time >= self.sweep || self.sweep <= 0.0 { return self.tone_end; }
+    self.tone_start * (self.tone_end / self.tone_start).powf(time / self.sweep)
+  }
+}
+
+/// Deterministic white noise, xorshift.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Noise(u32);
+
+impl Noise {
+  pub fn new(seed : u32) -> Self {
+    Self(seed | 1)
+  }
+
+  /// sample in `-1..1`
+  pub fn next(&mut self) -> FloatWord {
+    self.0 ^= self.0 << 13;
+    self.0 ^= self.0 >> 17;
+    self.0 ^= self.0 << 5;
+    (self.0 as FloatWord / u32::MAX as FloatWord) * 2.0 - 1.0
+  }
+}
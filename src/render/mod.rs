@@ -0,0 +1,132 @@
+//! Offline rendering of [`Midi`] to PCM audio, with built-in [`Synth`] and optional SoundFont.
+//!
+//! - [`Renderer`] : plays merged, tempo-mapped [`Schedule`] of midi into synth, sample accurate.
+//! - [`Synth`] : polyphonic synthesizer, built-in oscillator patches and drum kit, or [`SoundFont`] samples.
+//! - [`Audio`] : rendered stereo samples, written as 16 bit WAV.
+//!
+//! Rendering doesn't read clock or random state, so same midi and options always give same samples.
+//!
+//! ```
+//! use rmidirs::render::Renderer;
+//!
+//! # let midi = rmidirs::model::core::midi::Midi::default();
+//! let audio = Renderer::new().with_sample_rate(22050).render(&midi);
+//! let wav : Vec<u8> = audio.to_wav();
+//! ```
+
+use crate::{
+  primitive::FloatWord,
+  model::core::midi::Midi,
+  playback::Schedule
+};
+
+pub use self::{
+  synth::Synth,
+  instrument::{Waveform, Envelope, Patch, Drum},
+  soundfont::{SoundFont, SoundFontError},
+  wav::Audio
+};
+
+mod synth;
+mod instrument;
+mod soundfont;
+mod wav;
+
+/// Renders midi to [`Audio`], through [`Synth`].
+///
+/// Audio lasts till End Of Track of longest track, and then while notes fade out, at most `tail` seconds longer.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+  sample_rate : u32,
+  gain : FloatWord,
+  tail : FloatWord,
+  polyphony : usize,
+  soundfont : Option<SoundFont>,
+}
+
+impl Default for Renderer {
+  fn default() -> Self {
+    Self { sample_rate : 44100, gain : 0.5, tail : 2.0, polyphony : Synth::POLYPHONY, soundfont : None }
+  }
+}
+
+impl Renderer {
+  /// 44100 Hz, gain 0.5 and 2 seconds of tail
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_sample_rate(mut self, sample_rate : u32) -> Self {
+    self.sample_rate = sample_rate.max(1);
+    self
+  }
+
+  /// Scales all samples, lower gain leaves more headroom for many voices.
+  pub fn with_gain(mut self, gain : FloatWord) -> Self {
+    self.gain = gain;
+    self
+  }
+
+  /// longest time after end of midi, while notes fade out
+  pub fn with_tail(mut self, seconds : FloatWord) -> Self {
+    self.tail = seconds.max(0.0);
+    self
+  }
+
+  pub fn with_polyphony(mut self, polyphony : usize) -> Self {
+    self.polyphony = polyphony;
+    self
+  }
+
+  /// Plays presets of `soundfont`, notes of missing presets use built-in patches.
+  pub fn with_soundfont(mut self, soundfont : SoundFont) -> Self {
+    self.soundfont = Some(soundfont);
+    self
+  }
+
+  pub fn render(&self, midi : &Midi) -> Audio {
+    self.render_schedule(&Schedule::from(midi))
+  }
+
+  /// Renders `schedule`, every message is applied at the frame nearest to its time.
+  pub fn render_schedule(&self, schedule : &Schedule) -> Audio {
+    let mut synth = Synth::new(self.sample_rate).with_polyphony(self.polyphony);
+    if let Some(soundfont) = &self.soundfont {
+      synth = synth.with_soundfont(soundfont);
+    }
+
+    let mut samples : Vec<FloatWord> = Vec::new();
+    for event in schedule.events() {
+      render_to(&mut synth, self.frame(event.seconds()), &mut samples);
+      synth.handle(event.message());
+    }
+    let end = self.frame(schedule.duration());
+    render_to(&mut synth, end, &mut samples);
+
+    // tail in blocks of 10ms, till voices fade out
+    let tail_end = end + self.frame(self.tail);
+    let block = (self.sample_rate as usize / 100).max(1);
+    while !synth.is_silent() && samples.len() / 2 < tail_end {
+      let frame = (samples.len() / 2 + block).min(tail_end);
+      render_to(&mut synth, frame, &mut samples);
+    }
+
+    for sample in samples.iter_mut() {
+      *sample *= self.gain;
+    }
+    Audio::new(self.sample_rate, samples)
+  }
+
+  /// frame nearest to `seconds`
+  fn frame(&self, seconds : FloatWord) -> usize {
+    (seconds.max(0.0) as f64 * self.sample_rate as f64).round() as usize
+  }
+}
+
+/// Renders `samples` of `synth` till `frame`.
+fn render_to(synth : &mut Synth, frame : usize, samples : &mut Vec<FloatWord>) {
+  let start = samples.len();
+  if frame * 2 <= start { return; }
+  samples.resize(frame * 2, 0.0);
+  synth.render(&mut samples[start..]);
+}
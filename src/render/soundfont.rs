@@ -0,0 +1,341 @@
+use std::{fmt, error};
+
+use crate::primitive::FloatWord;
+
+use super::instrument::Envelope;
+
+/// Error of SoundFont which can't be read.
+#[derive(Debug, Clone)]
+pub struct SoundFontError {
+  message : String,
+}
+
+impl SoundFontError {
+  pub fn new(message : String) -> Self {
+    Self { message }
+  }
+
+  pub fn message(&self) -> &str { &self.message }
+}
+
+impl fmt::Display for SoundFontError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "invalid SoundFont: {}", self.message)
+  }
+}
+
+impl error::Error for SoundFontError {}
+
+/// generators read from zones
+mod generator {
+  pub const START_OFFSET : u16 = 0;
+  pub const END_OFFSET : u16 = 1;
+  pub const LOOP_START_OFFSET : u16 = 2;
+  pub const LOOP_END_OFFSET : u16 = 3;
+  pub const START_COARSE_OFFSET : u16 = 4;
+  pub const END_COARSE_OFFSET : u16 = 12;
+  pub const PAN : u16 = 17;
+  pub const ATTACK : u16 = 34;
+  pub const HOLD : u16 = 35;
+  pub const DECAY : u16 = 36;
+  pub const SUSTAIN : u16 = 37;
+  pub const RELEASE : u16 = 38;
+  pub const INSTRUMENT : u16 = 41;
+  pub const KEY_RANGE : u16 = 43;
+  pub const VELOCITY_RANGE : u16 = 44;
+  pub const LOOP_START_COARSE_OFFSET : u16 = 45;
+  pub const ATTENUATION : u16 = 48;
+  pub const LOOP_END_COARSE_OFFSET : u16 = 50;
+  pub const COARSE_TUNE : u16 = 51;
+  pub const FINE_TUNE : u16 = 52;
+  pub const SAMPLE : u16 = 53;
+  pub const SAMPLE_MODES : u16 = 54;
+  pub const ROOT_KEY : u16 = 58;
+  pub const COUNT : usize = 61;
+}
+
+/// Zone of preset or instrument, generators which are set, and key and velocity ranges.
+#[derive(Debug, Clone)]
+struct Zone {
+  generators : [Option<i16>; generator::COUNT],
+  keys : (u8, u8),
+  velocities : (u8, u8),
+}
+
+impl Zone {
+  fn get(&self, generator : u16) -> Option<i16> {
+    self.generators.get(generator as usize).copied().flatten()
+  }
+
+  fn contains(&self, key : u8, velocity : u8) -> bool {
+    (self.keys.0 ..= self.keys.1).contains(&key) && (self.velocities.0 ..= self.velocities.1).contains(&velocity)
+  }
+}
+
+/// Zones of preset or instrument, global zone applies to all other zones.
+#[derive(Debug, Clone)]
+struct Zones {
+  global : Option<Zone>,
+  zones : Vec<Zone>,
+}
+
+#[derive(Debug, Clone)]
+struct Preset {
+  name : String,
+  bank : u16,
+  program : u16,
+  zones : Zones,
+}
+
+#[derive(Debug, Clone)]
+struct SampleHeader {
+  start : u32,
+  end : u32,
+  loop_start : u32,
+  loop_end : u32,
+  sample_rate : u32,
+  pitch : u8,
+  correction : i8,
+}
+
+/// Sample played for a note, with generators of preset and instrument zones applied.
+#[derive(Debug, Clone)]
+pub(crate) struct Region {
+  /// start, end, loop start and loop end in samples of font
+  pub start : usize,
+  pub end : usize,
+  pub loop_start : usize,
+  pub loop_end : usize,
+  pub looping : bool,
+  /// loop ends when key is released, and rest of sample is played
+  pub until_release : bool,
+  pub sample_rate : u32,
+  pub root_key : u8,
+  /// tuning in cents
+  pub tune : FloatWord,
+  /// gain of initial attenuation
+  pub gain : FloatWord,
+  /// -1 left to 1 right
+  pub pan : FloatWord,
+  pub envelope : Envelope,
+}
+
+/// SoundFont 2 (`.sf2`) samples, for sample playback in [`Synth`](super::Synth).
+///
+/// Read generators are sample addresses, key and velocity ranges, tuning, root key, loop mode,
+/// attenuation, pan and volume envelope; modulators, filters and LFOs are ignored.
+#[derive(Debug, Clone)]
+pub struct SoundFont {
+  presets : Vec<Preset>,
+  instruments : Vec<Zones>,
+  samples : Vec<SampleHeader>,
+  /// 16 bit samples of `smpl` chunk
+  data : Vec<i16>,
+}
+
+impl SoundFont {
+  pub fn parse(bytes : &[u8]) -> Result<Self, SoundFontError> {
+    let body = match chunk(bytes, 0)? {
+      (b"RIFF", body) if body.starts_with(b"sfbk") => &body[4..],
+      _ => return Err(SoundFontError::new(String::from("should start with RIFF 'sfbk'"))),
+    };
+
+    let (mut sdta, mut pdta) = (None, None);
+    let mut offset = 0;
+    while offset + 8 <= body.len() {
+      let (id, data) = chunk(body, offset)?;
+      offset += 8 + data.len() + data.len() % 2;
+      if id != b"LIST" || data.len() < 4 { continue; }
+      match &data[..4] {
+        b"sdta" => sdta = Some(&data[4..]),
+        b"pdta" => pdta = Some(&data[4..]),
+        _ => {},
+      }
+    }
+
+    let sdta = sdta.ok_or_else(|| SoundFontError::new(String::from("missing 'sdta' list")))?;
+    let data = sub_chunk(sdta, b"smpl")?.chunks_exact(2).map(|sample| i16::from_le_bytes([sample[0], sample[1]])).collect();
+
+    let pdta = pdta.ok_or_else(|| SoundFontError::new(String::from("missing 'pdta' list")))?;
+    let records = |id : &[u8; 4], len : usize| -> Result<Vec<&[u8]>, SoundFontError> {
+      let data = sub_chunk(pdta, id)?;
+      if data.len() % len != 0 || data.len() < len {
+        return Err(SoundFontError::new(format!("'{}' should have records of {} bytes", String::from_utf8_lossy(id), len)));
+      }
+      Ok(data.chunks_exact(len).collect())
+    };
+
+    let preset_headers = records(b"phdr", 38)?;
+    let preset_bags = records(b"pbag", 4)?;
+    let preset_generators = records(b"pgen", 4)?;
+    let instrument_headers = records(b"inst", 22)?;
+    let instrument_bags = records(b"ibag", 4)?;
+    let instrument_generators = records(b"igen", 4)?;
+    let sample_headers = records(b"shdr", 46)?;
+
+    // last record of every list is terminal
+    let presets = preset_headers.windows(2).map(|headers| {
+      let bags = (u16_at(headers[0], 24) as usize, u16_at(headers[1], 24) as usize);
+      Ok(Preset {
+        name : name(headers[0]),
+        program : u16_at(headers[0], 20),
+        bank : u16_at(headers[0], 22),
+        zones : zones(&preset_bags, &preset_generators, bags, generator::INSTRUMENT)?,
+      })
+    }).collect::<Result<_, SoundFontError>>()?;
+
+    let instruments = instrument_headers.windows(2).map(|headers| {
+      let bags = (u16_at(headers[0], 20) as usize, u16_at(headers[1], 20) as usize);
+      zones(&instrument_bags, &instrument_generators, bags, generator::SAMPLE)
+    }).collect::<Result<_, SoundFontError>>()?;
+
+    let samples = sample_headers[..sample_headers.len() - 1].iter().map(|header| SampleHeader {
+      start : u32_at(header, 20),
+      end : u32_at(header, 24),
+      loop_start : u32_at(header, 28),
+      loop_end : u32_at(header, 32),
+      sample_rate : u32_at(header, 36),
+      pitch : header[40],
+      correction : header[41] as i8,
+    }).collect();
+
+    Ok(Self { presets, instruments, samples, data })
+  }
+
+  /// (bank, program, name) of every preset
+  pub fn presets(&self) -> Vec<(u16, u16, &str)> {
+    self.presets.iter().map(|preset| (preset.bank, preset.program, preset.name.as_str())).collect()
+  }
+
+  pub fn has_preset(&self, bank : u16, program : u16) -> bool {
+    self.preset(bank, program).is_some()
+  }
+
+  fn preset(&self, bank : u16, program : u16) -> Option<&Preset> {
+    self.presets.iter().find(|preset| preset.bank == bank && preset.program == program)
+  }
+
+  /// sample of font as `-1..1`, 0 out of range
+  pub(crate) fn sample(&self, index : usize) -> FloatWord {
+    self.data.get(index).map_or(0.0, |sample| *sample as FloatWord / 32768.0)
+  }
+
+  /// Regions of preset sounding for `key` played with `velocity`, empty when preset is missing.
+  pub(crate) fn regions(&self, bank : u16, program : u16, key : u8, velocity : u8) -> Vec<Region> {
+    let Some(preset) = self.preset(bank, program) else { return Vec::new() };
+    let mut regions = Vec::new();
+    for preset_zone in preset.zones.zones.iter().filter(|zone| zone.contains(key, velocity)) {
+      let Some(instrument) = preset_zone.get(generator::INSTRUMENT).and_then(|index| self.instruments.get(index as u16 as usize)) else { continue };
+      for zone in instrument.zones.iter().filter(|zone| zone.contains(key, velocity)) {
+        // instrument generators are absolute, preset generators are added to them
+        let absolute = |generator : u16, default : i16| -> i32 {
+          zone.get(generator).or_else(|| instrument.global.as_ref().and_then(|global| global.get(generator))).unwrap_or(default) as i32
+        };
+        let relative = |generator : u16| -> i32 {
+          preset_zone.get(generator).or_else(|| preset.zones.global.as_ref().and_then(|global| global.get(generator))).unwrap_or(0) as i32
+        };
+        let value = |generator : u16, default : i16| absolute(generator, default) + relative(generator);
+
+        let Some(sample) = zone.get(generator::SAMPLE).and_then(|index| self.samples.get(index as u16 as usize)) else { continue };
+        let offset = |fine : u16, coarse : u16| absolute(fine, 0) + absolute(coarse, 0) * 32768;
+        let address = |base : u32, offset : i32| (base as i64 + offset as i64).clamp(0, self.data.len() as i64) as usize;
+        let root_key = match absolute(generator::ROOT_KEY, -1) {
+          key @ 0 ..= 127 => key as u8,
+          _ => sample.pitch.min(127),
+        };
+        let seconds = |generator : u16| 2.0f32.powf(value(generator, -12000) as FloatWord / 1200.0);
+
+        regions.push(Region {
+          start : address(sample.start, offset(generator::START_OFFSET, generator::START_COARSE_OFFSET)),
+          end : address(sample.end, offset(generator::END_OFFSET, generator::END_COARSE_OFFSET)),
+          loop_start : address(sample.loop_start, offset(generator::LOOP_START_OFFSET, generator::LOOP_START_COARSE_OFFSET)),
+          loop_end : address(sample.loop_end, offset(generator::LOOP_END_OFFSET, generator::LOOP_END_COARSE_OFFSET)),
+          looping : matches!(absolute(generator::SAMPLE_MODES, 0) & 3, 1 | 3),
+          until_release : absolute(generator::SAMPLE_MODES, 0) & 3 == 3,
+          sample_rate : sample.sample_rate.max(1),
+          root_key,
+          tune : (value(generator::COARSE_TUNE, 0) * 100 + value(generator::FINE_TUNE, 0) + sample.correction as i32) as FloatWord,
+          gain : 10.0f32.powf(-(value(generator::ATTENUATION, 0).max(0) as FloatWord) / 200.0),
+          pan : (value(generator::PAN, 0) as FloatWord / 500.0).clamp(-1.0, 1.0),
+          envelope : Envelope::new(
+            seconds(generator::ATTACK),
+            // hold is played as part of decay
+            seconds(generator::HOLD) + seconds(generator::DECAY),
+            10.0f32.powf(-(value(generator::SUSTAIN, 0).clamp(0, 1440) as FloatWord) / 200.0),
+            seconds(generator::RELEASE)
+          ),
+        });
+      }
+    }
+    regions
+  }
+}
+
+/// id and data of RIFF chunk at `offset`
+fn chunk(bytes : &[u8], offset : usize) -> Result<(&[u8], &[u8]), SoundFontError> {
+  let header = bytes.get(offset .. offset + 8).ok_or_else(|| SoundFontError::new(format!("chunk header at {} is cut", offset)))?;
+  let length = u32_at(header, 4) as usize;
+  let data = bytes.get(offset + 8 .. offset + 8 + length).ok_or_else(|| SoundFontError::new(format!(
+    "chunk '{}' at {} is cut", String::from_utf8_lossy(&header[..4]), offset
+  )))?;
+  Ok((&header[..4], data))
+}
+
+/// data of chunk `id` inside list
+fn sub_chunk<'a>(list : &'a [u8], id : &[u8; 4]) -> Result<&'a [u8], SoundFontError> {
+  let mut offset = 0;
+  while offset + 8 <= list.len() {
+    let (chunk_id, data) = chunk(list, offset)?;
+    if chunk_id == id { return Ok(data); }
+    offset += 8 + data.len() + data.len() % 2;
+  }
+  Err(SoundFontError::new(format!("missing '{}' chunk", String::from_utf8_lossy(id))))
+}
+
+/// Zones of `bags` range, first zone is global when it doesn't end with `link` generator.
+fn zones(bags : &[&[u8]], generators : &[&[u8]], (first, last) : (usize, usize), link : u16) -> Result<Zones, SoundFontError> {
+  if first > last || last >= bags.len() {
+    return Err(SoundFontError::new(format!("zones {}..{} are out of range", first, last)));
+  }
+
+  let mut zones = Zones { global : None, zones : Vec::new() };
+  for (index, bag) in bags[first..=last].windows(2).enumerate() {
+    let range = u16_at(bag[0], 0) as usize .. u16_at(bag[1], 0) as usize;
+    let records = generators.get(range).ok_or_else(|| SoundFontError::new(String::from("generators are out of range")))?;
+
+    let mut zone = Zone { generators : [None; generator::COUNT], keys : (0, 127), velocities : (0, 127) };
+    let mut linked = false;
+    for record in records {
+      let (operator, amount) = (u16_at(record, 0), &record[2..4]);
+      match operator {
+        generator::KEY_RANGE => zone.keys = (amount[0], amount[1]),
+        generator::VELOCITY_RANGE => zone.velocities = (amount[0], amount[1]),
+        operator if (operator as usize) < generator::COUNT => zone.generators[operator as usize] = Some(i16::from_le_bytes([amount[0], amount[1]])),
+        _ => {},
+      }
+      linked = operator == link;
+    }
+
+    match (linked, index) {
+      (true, _) => zones.zones.push(zone),
+      (false, 0) => zones.global = Some(zone),
+      // zones without link after the first are ignored
+      (false, _) => {},
+    }
+  }
+  Ok(zones)
+}
+
+fn name(record : &[u8]) -> String {
+  let name = &record[..20];
+  let end = name.iter().position(|byte| *byte == 0).unwrap_or(name.len());
+  String::from_utf8_lossy(&name[..end]).into_owned()
+}
+
+fn u16_at(bytes : &[u8], offset : usize) -> u16 {
+  u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes : &[u8], offset : usize) -> u32 {
+  u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
@@ -0,0 +1,321 @@
+use std::f32::consts::PI;
+
+use crate::{
+  primitive::FloatWord,
+  model::core::midi_event::MidiMessage
+};
+
+use super::{
+  instrument::{Waveform, Envelope, EnvelopeState, Patch, Drum, Noise},
+  soundfont::{SoundFont, Region}
+};
+
+/// channel of General MIDI percussion
+const DRUM_CHANNEL : usize = 9;
+/// bank of percussion presets in SoundFont
+const DRUM_BANK : u16 = 128;
+/// envelope of built-in drums, which decay by themselves and are released only by All Sound Off or stealing
+const DRUM_ENVELOPE : Envelope = Envelope::new(0.0, 0.0, 1.0, 0.02);
+
+/// State of channel, set by controllers, program change and pitch bend.
+#[derive(Debug, Clone, Copy)]
+struct Channel {
+  bank : u8,
+  program : u8,
+  volume : u8,
+  expression : u8,
+  pan : u8,
+  sustain : bool,
+  /// -8192 to 8191
+  bend : i16,
+  /// pitch bend range in semitones, set with RPN 0
+  bend_range : FloatWord,
+  /// selected (MSB, LSB) of RPN, `0x7F` when none
+  rpn : (u8, u8),
+}
+
+impl Default for Channel {
+  fn default() -> Self {
+    Self { bank : 0, program : 0, volume : 100, expression : 127, pan : 64, sustain : false, bend : 0, bend_range : 2.0, rpn : (0x7F, 0x7F) }
+  }
+}
+
+impl Channel {
+  /// gain of volume and expression, squared for loudness
+  fn gain(&self) -> FloatWord {
+    let gain = self.volume as FloatWord / 127.0 * self.expression as FloatWord / 127.0;
+    gain * gain
+  }
+
+  /// -1 left to 1 right
+  fn pan(&self) -> FloatWord {
+    (self.pan.max(1) as FloatWord - 64.0) / 63.0
+  }
+
+  fn bend(&self) -> FloatWord {
+    self.bend as FloatWord / 8192.0 * self.bend_range
+  }
+}
+
+#[derive(Debug, Clone)]
+enum Source {
+  Oscillator { waveform : Waveform, phase : FloatWord },
+  Drum { drum : Drum, phase : FloatWord, noise : Noise, last_noise : FloatWord },
+  Sample { region : Region, position : f64 },
+}
+
+#[derive(Debug, Clone)]
+struct Voice {
+  channel : usize,
+  note : u8,
+  /// order of note on, oldest voice is stolen first
+  id : u64,
+  gain : FloatWord,
+  pan : FloatWord,
+  envelope : EnvelopeState,
+  /// Note Off came while sustain pedal was held
+  sustained : bool,
+  /// seconds since note on
+  time : FloatWord,
+  source : Source,
+  ended : bool,
+}
+
+/// Polyphonic synthesizer of MIDI 1.0 channel messages, rendering stereo samples.
+///
+/// Built-in patches are one oscillator with ADSR envelope per General MIDI family, see [`Patch`],
+/// and channel 10 plays built-in drum kit, see [`Drum`].
+/// With SoundFont, presets of font are played instead, channel 10 from bank 128,
+/// and notes of presets missing in font fall back to built-in patches.
+///
+/// Handles Note On / Off with velocity, Program Change, Bank Select, Pitch Bend with RPN 0 range,
+/// Volume, Expression, Pan, Sustain, All Sound Off, Reset All Controllers and All Notes Off.
+/// Rendering is deterministic, same messages give same samples.
+#[derive(Debug, Clone)]
+pub struct Synth<'a> {
+  sample_rate : u32,
+  soundfont : Option<&'a SoundFont>,
+  polyphony : usize,
+  channels : [Channel; 16],
+  voices : Vec<Voice>,
+  next_id : u64,
+}
+
+impl<'a> Synth<'a> {
+  /// default number of voices sounding at once
+  pub const POLYPHONY : usize = 64;
+
+  pub fn new(sample_rate : u32) -> Self {
+    Self {
+      sample_rate : sample_rate.max(1),
+      soundfont : None,
+      polyphony : Self::POLYPHONY,
+      channels : [Channel::default(); 16],
+      voices : Vec::new(),
+      next_id : 0,
+    }
+  }
+
+  pub fn with_soundfont(mut self, soundfont : &'a SoundFont) -> Self {
+    self.soundfont = Some(soundfont);
+    self
+  }
+
+  /// Limits voices sounding at once, oldest voice is stolen when limit is reached.
+  pub fn with_polyphony(mut self, polyphony : usize) -> Self {
+    self.polyphony = polyphony.max(1);
+    self
+  }
+
+  pub fn sample_rate(&self) -> u32 { self.sample_rate }
+
+  /// number of voices sounding
+  pub fn voices(&self) -> usize { self.voices.len() }
+
+  pub fn is_silent(&self) -> bool { self.voices.is_empty() }
+
+  /// Applies channel `message`, other messages are ignored.
+  pub fn handle(&mut self, message : &MidiMessage) {
+    let MidiMessage::ChannelMessage(message) = message else { return };
+    let bytes : Vec<u8> = message.clone().into();
+    if bytes.len() < 2 { return; }
+    let (status, channel) = (bytes[0] & 0xF0, (bytes[0] & 0x0F) as usize);
+    let value = bytes.get(2).copied().unwrap_or_default();
+
+    match status {
+      0x90 if value > 0 => self.note_on(channel, bytes[1], value),
+      0x80 | 0x90 => self.note_off(channel, bytes[1]),
+      0xB0 => self.controller(channel, bytes[1], value),
+      0xC0 => self.channels[channel].program = bytes[1],
+      0xE0 => self.channels[channel].bend = (bytes[1] as i16 | (value as i16) << 7) - 8192,
+      _ => {},
+    }
+  }
+
+  /// Adds next `out.len() / 2` frames of sound to `out`, samples are interleaved left and right.
+  pub fn render(&mut self, out : &mut [FloatWord]) {
+    let dt = 1.0 / self.sample_rate as FloatWord;
+    for voice in self.voices.iter_mut() {
+      let channel = &self.channels[voice.channel];
+      let gain = voice.gain * channel.gain();
+      // equal power pan
+      let angle = ((channel.pan() + voice.pan).clamp(-1.0, 1.0) + 1.0) * PI / 4.0;
+      let (left, right) = (angle.cos() * gain, angle.sin() * gain);
+      let semitones = voice.note as FloatWord + channel.bend();
+
+      for frame in out.chunks_exact_mut(2) {
+        let level = voice.envelope.next(dt);
+        let sample = match &mut voice.source {
+          Source::Oscillator { waveform, phase } => {
+            let step = 440.0 * 2.0f32.powf((semitones - 69.0) / 12.0) * dt;
+            let sample = waveform.sample(*phase, step);
+            *phase = (*phase + step).fract();
+            sample
+          },
+          Source::Drum { drum, phase, noise, last_noise } => {
+            let tone = (2.0 * PI * *phase).sin();
+            *phase = (*phase + drum.frequency(voice.time) * dt).fract();
+            let mut white = noise.next();
+            if drum.bright {
+              (white, *last_noise) = (white - *last_noise, white);
+              white *= 0.5;
+            }
+            let decay = (-voice.time / drum.decay).exp();
+            if decay < 0.001 { voice.ended = true; }
+            (tone * drum.tone + white * drum.noise) * decay
+          },
+          Source::Sample { region, position } => {
+            let index = *position as usize;
+            if index + 1 >= region.end { voice.ended = true; }
+            let (Some(soundfont), false) = (self.soundfont, voice.ended) else { break };
+            let fraction = (*position - index as f64) as FloatWord;
+            let sample = soundfont.sample(index) * (1.0 - fraction) + soundfont.sample(index + 1) * fraction;
+
+            let pitch = semitones - region.root_key as FloatWord + region.tune / 100.0;
+            *position += (2.0f32.powf(pitch / 12.0) * region.sample_rate as FloatWord * dt) as f64;
+            let loops = region.looping && !(region.until_release && voice.envelope.is_released());
+            if loops && region.loop_end > region.loop_start && *position >= region.loop_end as f64 {
+              *position -= (region.loop_end - region.loop_start) as f64;
+            }
+            sample
+          },
+        };
+        frame[0] += sample * level * left;
+        frame[1] += sample * level * right;
+        voice.time += dt;
+        if voice.ended || voice.envelope.is_done() { break; }
+      }
+    }
+    self.voices.retain(|voice| !voice.ended && !voice.envelope.is_done());
+  }
+
+  fn note_on(&mut self, channel : usize, note : u8, velocity : u8) {
+    let velocity_gain = (velocity as FloatWord / 127.0).powi(2);
+    let state = self.channels[channel];
+
+    let regions = match self.soundfont {
+      Some(soundfont) if channel == DRUM_CHANNEL => soundfont.regions(DRUM_BANK, state.program as u16, note, velocity),
+      Some(soundfont) => match soundfont.regions(state.bank as u16, state.program as u16, note, velocity) {
+        regions if regions.is_empty() => soundfont.regions(0, state.program as u16, note, velocity),
+        regions => regions,
+      },
+      None => Vec::new(),
+    };
+
+    let voices : Vec<(FloatWord, FloatWord, Envelope, Source)> = match (regions.is_empty(), channel) {
+      (false, _) => regions.into_iter().map(|region| {
+        (region.gain, region.pan, region.envelope, Source::Sample { position : region.start as f64, region })
+      }).collect(),
+      (true, DRUM_CHANNEL) => {
+        let noise = Noise::new(0x9E37_79B9 ^ (note as u32) << 8);
+        vec![(0.8, 0.0, DRUM_ENVELOPE, Source::Drum { drum : Drum::from_note(note), phase : 0.0, noise, last_noise : 0.0 })]
+      },
+      (true, _) => {
+        let patch = Patch::from_program(state.program);
+        vec![(patch.gain, 0.0, patch.envelope, Source::Oscillator { waveform : patch.waveform, phase : 0.0 })]
+      },
+    };
+
+    for (gain, pan, envelope, source) in voices {
+      self.steal();
+      self.voices.push(Voice {
+        channel,
+        note,
+        id : self.next_id,
+        gain : gain * velocity_gain,
+        pan,
+        envelope : EnvelopeState::new(envelope),
+        sustained : false,
+        time : 0.0,
+        source,
+        ended : false,
+      });
+      self.next_id += 1;
+    }
+  }
+
+  /// Frees voice when polyphony is reached, quietest released voice, or else the oldest.
+  fn steal(&mut self) {
+    if self.voices.len() < self.polyphony { return; }
+    let released = self.voices.iter().enumerate()
+      .filter(|(_, voice)| voice.envelope.is_released())
+      .min_by(|(_, a), (_, b)| a.envelope.level().total_cmp(&b.envelope.level()))
+      .map(|(index, _)| index);
+    let oldest = self.voices.iter().enumerate().min_by_key(|(_, voice)| voice.id).map(|(index, _)| index);
+    if let Some(index) = released.or(oldest) {
+      self.voices.remove(index);
+    }
+  }
+
+  fn note_off(&mut self, channel : usize, note : u8) {
+    let sustain = self.channels[channel].sustain;
+    for voice in self.voices.iter_mut().filter(|voice| voice.channel == channel && voice.note == note && !voice.envelope.is_released()) {
+      // built-in drums ring out
+      if matches!(voice.source, Source::Drum { .. }) { continue; }
+      match sustain {
+        true => voice.sustained = true,
+        false => voice.envelope.release(),
+      }
+    }
+  }
+
+  fn controller(&mut self, channel : usize, controller : u8, value : u8) {
+    let state = &mut self.channels[channel];
+    match controller {
+      0 => state.bank = value,
+      6 if state.rpn == (0, 0) => state.bend_range = value as FloatWord + state.bend_range.fract(),
+      38 if state.rpn == (0, 0) => state.bend_range = state.bend_range.trunc() + value as FloatWord / 100.0,
+      7 => state.volume = value,
+      10 => state.pan = value,
+      11 => state.expression = value,
+      64 => {
+        state.sustain = value >= 64;
+        if !state.sustain { self.release_sustained(channel); }
+      },
+      // NRPN deselects RPN
+      98 | 99 => state.rpn = (0x7F, 0x7F),
+      100 => state.rpn.1 = value,
+      101 => state.rpn.0 = value,
+      // All Sound Off
+      120 => self.voices.retain(|voice| voice.channel != channel),
+      // Reset All Controllers
+      121 => {
+        *state = Channel { bank : state.bank, program : state.program, volume : state.volume, pan : state.pan, bend_range : state.bend_range, ..Channel::default() };
+        self.release_sustained(channel);
+      },
+      // All Notes Off
+      123 => {
+        let notes : Vec<u8> = self.voices.iter().filter(|voice| voice.channel == channel).map(|voice| voice.note).collect();
+        for note in notes { self.note_off(channel, note); }
+      },
+      _ => {},
+    }
+  }
+
+  fn release_sustained(&mut self, channel : usize) {
+    for voice in self.voices.iter_mut().filter(|voice| voice.channel == channel && voice.sustained) {
+      voice.sustained = false;
+      voice.envelope.release();
+    }
+  }
+}
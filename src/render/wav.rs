@@ -0,0 +1,68 @@
+use std::io::{self, Write};
+
+use crate::primitive::FloatWord;
+
+/// Stereo audio, samples interleaved left and right, in `-1..1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Audio {
+  sample_rate : u32,
+  samples : Vec<FloatWord>,
+}
+
+impl Audio {
+  pub const CHANNELS : u16 = 2;
+
+  pub fn new(sample_rate : u32, samples : Vec<FloatWord>) -> Self {
+    Self { sample_rate, samples }
+  }
+
+  pub fn sample_rate(&self) -> u32 { self.sample_rate }
+
+  pub fn samples(&self) -> &[FloatWord] { &self.samples }
+
+  /// number of (left, right) sample pairs
+  pub fn frames(&self) -> usize { self.samples.len() / Self::CHANNELS as usize }
+
+  /// length in seconds
+  pub fn duration(&self) -> FloatWord {
+    self.frames() as FloatWord / self.sample_rate as FloatWord
+  }
+
+  /// largest absolute sample, over 1.0 when audio clips
+  pub fn peak(&self) -> FloatWord {
+    self.samples.iter().fold(0.0, |peak, sample| peak.max(sample.abs()))
+  }
+
+  /// Writes audio as 16 bit PCM WAV file, samples out of `-1..1` are clipped.
+  pub fn write_wav<W : Write>(&self, mut writer : W) -> io::Result<()> {
+    let data_len = self.samples.len() as u32 * 2;
+    let block_align = Self::CHANNELS * 2;
+
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend(b"RIFF");
+    bytes.extend((36 + data_len).to_le_bytes());
+    bytes.extend(b"WAVE");
+    bytes.extend(b"fmt ");
+    bytes.extend(16u32.to_le_bytes());
+    // PCM
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend(Self::CHANNELS.to_le_bytes());
+    bytes.extend(self.sample_rate.to_le_bytes());
+    bytes.extend((self.sample_rate * block_align as u32).to_le_bytes());
+    bytes.extend(block_align.to_le_bytes());
+    bytes.extend(16u16.to_le_bytes());
+    bytes.extend(b"data");
+    bytes.extend(data_len.to_le_bytes());
+    for sample in &self.samples {
+      bytes.extend(((sample.clamp(-1.0, 1.0) * 32767.0).round() as i16).to_le_bytes());
+    }
+    writer.write_all(&bytes)
+  }
+
+  /// audio as 16 bit PCM WAV file
+  pub fn to_wav(&self) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    self.write_wav(&mut bytes).expect("writing to Vec doesn't fail");
+    bytes
+  }
+}
@@ -0,0 +1,235 @@
+use std::str::FromStr;
+
+use crate::model::core::{
+  midi::Midi,
+  midi_header::MidiHeader,
+  midi_track::MidiTrack,
+  unknown_chunk::UnknownChunk,
+  midi_event::{MidiEvent, MidiMessage, delta_time::DeltaTime, meta_message::MetaMessage, sys_event::SysEvent}
+};
+
+use super::{unescape, TextParseError, TEXT_EVENTS};
+
+/// track being assembled, along with its number and tick of last event
+struct OpenTrack {
+  number : usize,
+  track : MidiTrack,
+  tick : u64,
+}
+
+/// Assembles midi from text form, see [`text`](super).
+///
+/// Header must be the first line, every track must start with `Start_track` and end with `End_track`,
+/// and ticks inside track must not decrease.
+pub fn from_text(text : &str) -> Result<Midi, TextParseError> {
+  let mut midi = Midi::default();
+  let mut has_header = false;
+  let mut open_track : Option<OpenTrack> = None;
+  let mut ended = false;
+  let mut line_count = 0;
+
+  for (line_no, line) in text.lines().enumerate() {
+    line_count = line_no + 1;
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') { continue; }
+
+    let error = |message : String| TextParseError::new(line_no + 1, message);
+    if ended { return Err(error(String::from("line after End_of_file"))); }
+
+    let fields = split(line).map_err(error)?;
+    if fields.len() < 3 {
+      return Err(error(format!("expected track, tick and type, found {} field(s)", fields.len())));
+    }
+    let track_no : usize = number(&fields, 0, "track").map_err(error)?;
+    let tick : u64 = number(&fields, 1, "tick").map_err(error)?;
+    let (kind, args) = (fields[2].as_str(), &fields[3..]);
+
+    if !has_header && kind != "Header" {
+      return Err(error(format!("expected Header as first line, found {}", kind)));
+    }
+
+    match kind {
+      "Header" => {
+        if has_header { return Err(error(String::from("Header is given twice"))); }
+        count(args, 3).map_err(error)?;
+        let format : u16 = number(args, 0, "format").map_err(error)?;
+        let ntrk : u16 = number(args, 1, "number of tracks").map_err(error)?;
+        let division : u16 = number(args, 2, "division").map_err(error)?;
+        midi.add_header(MidiHeader::new_raw(&format.to_be_bytes(), &ntrk.to_be_bytes(), &division.to_be_bytes()));
+        has_header = true;
+      },
+      "Unknown_chunk" => {
+        let position : usize = number(args, 0, "position").map_err(error)?;
+        let id = args.get(1).map(|id| unescape(id)).transpose().map_err(error)?.unwrap_or_default();
+        let id : [u8; 4] = id.try_into().map_err(|id : Vec<u8>| error(format!("chunk id must be 4 bytes, found {} bytes", id.len())))?;
+        let data = byte_list(args, 2).map_err(error)?;
+        midi.add_unknown_chunk(UnknownChunk::new(id, data).with_position(position));
+      },
+      "End_of_file" => ended = true,
+      "Start_track" => {
+        if let Some(open_track) = &open_track {
+          return Err(error(format!("Start_track before End_track of track {}", open_track.number)));
+        }
+        let expected = midi.tracks().len() + 1;
+        if track_no != expected {
+          return Err(error(format!("expected track {} to start, found track {}", expected, track_no)));
+        }
+        open_track = Some(OpenTrack { number : track_no, track : MidiTrack::default(), tick : 0 });
+      },
+      _ => {
+        let current = open_track.as_mut()
+          .filter(|open_track| open_track.number == track_no)
+          .ok_or_else(|| error(format!("event of track {} outside its Start_track and End_track", track_no)))?;
+        if tick < current.tick {
+          return Err(error(format!("tick {} is before tick {} of previous event", tick, current.tick)));
+        }
+
+        let message = message(kind, args).map_err(error)?;
+        current.track.add_event(MidiEvent::new(DeltaTime::from((tick - current.tick) as u32), message));
+        current.tick = tick;
+
+        if kind == "End_track" {
+          midi.add_track(open_track.take().unwrap().track);
+        }
+      },
+    }
+  }
+
+  match (has_header, open_track) {
+    (false, _) => Err(TextParseError::new(line_count.max(1), String::from("Header is missing"))),
+    (_, Some(open_track)) => Err(TextParseError::new(line_count, format!("End_track of track {} is missing", open_track.number))),
+    _ => Ok(midi),
+  }
+}
+
+/// message of line with type `kind`, `args` are fields after type
+fn message(kind : &str, args : &[String]) -> Result<MidiMessage, String> {
+  let meta = |meta_type : u8, data : &[u8]| MidiMessage::MetaMessage(MetaMessage::from((0xFF, meta_type, data)));
+
+  if let Some(index) = TEXT_EVENTS.iter().position(|name| *name == kind) {
+    count(args, 1)?;
+    return Ok(meta(index as u8 + 1, &unescape(&args[0])?));
+  }
+
+  let message = match kind {
+    "Note_off_c"           => channel(0x80, args, 2)?,
+    "Note_on_c"            => channel(0x90, args, 2)?,
+    "Poly_aftertouch_c"    => channel(0xA0, args, 2)?,
+    "Control_c"            => channel(0xB0, args, 2)?,
+    "Program_c"            => channel(0xC0, args, 1)?,
+    "Channel_aftertouch_c" => channel(0xD0, args, 1)?,
+    "Pitch_bend_c" => {
+      count(args, 2)?;
+      let value : u16 = number(args, 1, "pitch bend value")?;
+      if value > 0x3FFF { return Err(format!("pitch bend value must be 0 - 16383, found {}", value)); }
+      channel_message(0xE0, number(args, 0, "channel")?, &[(value & 0x7F) as u8, (value >> 7) as u8])?
+    },
+    "Sequence_number" => {
+      count(args, 1)?;
+      meta(0x00, &number::<u16>(args, 0, "sequence number")?.to_be_bytes())
+    },
+    "Channel_prefix" => { count(args, 1)?; meta(0x20, &[number::<u8>(args, 0, "channel")?]) },
+    "MIDI_port"      => { count(args, 1)?; meta(0x21, &[number::<u8>(args, 0, "port")?]) },
+    "End_track"      => { count(args, 0)?; meta(0x2F, &[]) },
+    "Tempo" => {
+      count(args, 1)?;
+      let tempo : u32 = number(args, 0, "tempo")?;
+      if tempo > 0xFFFFFF { return Err(format!("tempo must fit in 3 bytes, found {}", tempo)); }
+      meta(0x51, &tempo.to_be_bytes()[1..])
+    },
+    "SMPTE_offset"   => meta(0x54, &numbers(args, 5, "SMPTE offset")?),
+    "Time_signature" => meta(0x58, &numbers(args, 4, "time signature")?),
+    "Key_signature" => {
+      count(args, 2)?;
+      let key : i8 = number(args, 0, "key")?;
+      let minor = match args[1].as_str() {
+        "major" => 0,
+        "minor" => 1,
+        mode => return Err(format!("expected major or minor, found {}", mode)),
+      };
+      meta(0x59, &[key as u8, minor])
+    },
+    "Sequencer_specific"      => meta(0x7F, &byte_list(args, 0)?),
+    "System_exclusive"        => MidiMessage::SysMessage(SysEvent::new(0xF0, byte_list(args, 0)?)),
+    "System_exclusive_packet" => MidiMessage::from_sys_event(SysEvent::new(0xF7, byte_list(args, 0)?)),
+    _ => return Err(format!("unknown event type {}", kind)),
+  };
+  Ok(message)
+}
+
+/// channel message with status `status`, channel and `len` data bytes in `args`
+fn channel(status : u8, args : &[String], len : usize) -> Result<MidiMessage, String> {
+  count(args, len + 1)?;
+  channel_message(status, number(args, 0, "channel")?, &numbers(&args[1..], len, "data byte")?)
+}
+
+fn channel_message(status : u8, channel : u8, data : &[u8]) -> Result<MidiMessage, String> {
+  if channel > 15 { return Err(format!("channel must be 0 - 15, found {}", channel)); }
+  if let Some(byte) = data.iter().find(|byte| **byte > 0x7F) {
+    return Err(format!("data byte must be 0 - 127, found {}", byte));
+  }
+  Ok(MidiMessage::from((status | channel, data)))
+}
+
+/// checks there are exactly `len` fields
+fn count(args : &[String], len : usize) -> Result<(), String> {
+  match args.len() == len {
+    true => Ok(()),
+    false => Err(format!("expected {} field(s) after type, found {}", len, args.len())),
+  }
+}
+
+fn number<T : FromStr>(fields : &[String], index : usize, name : &str) -> Result<T, String> {
+  let field = fields.get(index).ok_or_else(|| format!("{} is missing", name))?;
+  field.parse().map_err(|_| format!("expected {}, found '{}'", name, field))
+}
+
+/// exactly `len` bytes
+fn numbers(args : &[String], len : usize, name : &str) -> Result<Vec<u8>, String> {
+  count(args, len)?;
+  (0..len).map(|index| number(args, index, name)).collect()
+}
+
+/// length at `index` followed by that many bytes
+fn byte_list(args : &[String], index : usize) -> Result<Vec<u8>, String> {
+  let len : usize = number(args, index, "length")?;
+  let bytes = &args[(index + 1).min(args.len())..];
+  if bytes.len() != len {
+    return Err(format!("length is {}, but {} byte(s) follow", len, bytes.len()));
+  }
+  numbers(bytes, len, "byte")
+}
+
+/// Splits line at commas and trims fields, quotes of quoted fields are removed and `""` inside them becomes `"`.
+fn split(line : &str) -> Result<Vec<String>, String> {
+  let mut fields = Vec::new();
+  let mut chars = line.chars().peekable();
+  loop {
+    while chars.next_if(|char| char.is_whitespace()).is_some() {}
+
+    let mut field = String::new();
+    if chars.next_if_eq(&'"').is_some() {
+      loop {
+        match chars.next() {
+          Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+          Some('"') => break,
+          Some(char) => field.push(char),
+          None => return Err(format!("string {} is not closed", field)),
+        }
+      }
+      while chars.next_if(|char| char.is_whitespace()).is_some() {}
+    } else {
+      while let Some(char) = chars.next_if(|char| *char != ',') {
+        field.push(char);
+      }
+      field.truncate(field.trim_end().len());
+    }
+    fields.push(field);
+
+    match chars.next() {
+      Some(',') => continue,
+      None => return Ok(fields),
+      Some(char) => return Err(format!("expected , after string, found {}", char)),
+    }
+  }
+}
@@ -0,0 +1,98 @@
+use crate::{
+  primitive::M2Byte,
+  model::core::{
+    midi::Midi,
+    midi_event::{MidiMessage, channel_message::ChannelMessage, meta_message::MetaMessage, sys_event::SysEvent}
+  }
+};
+
+use super::{quote, TEXT_EVENTS};
+
+/// Writes midi as text, one line per event, see [`text`](super).
+///
+/// Invalid messages have no encoding, they are written as comments.
+pub fn to_text(midi : &Midi) -> String {
+  let header = midi.header();
+  let format : M2Byte = header.format().into();
+  let division : Vec<u8> = header.division().into();
+  let mut lines = vec![format!("0, 0, Header, {}, {}, {}", *format, midi.tracks().len(), u16::from_be_bytes([division[0], division[1]]))];
+
+  for chunk in midi.unknown_chunks() {
+    let position = chunk.position().min(midi.tracks().len());
+    lines.push(format!("0, 0, Unknown_chunk, {}, {}, {}", position, quote(&chunk.id()), bytes(chunk.data())));
+  }
+
+  for (track_no, track) in midi.tracks().iter().enumerate() {
+    let track_no = track_no + 1;
+    lines.push(format!("{}, 0, Start_track", track_no));
+    for (tick, event) in track.iter_absolute() {
+      lines.push(match fields(event.message()) {
+        Ok(fields) => format!("{}, {}, {}", track_no, tick, fields),
+        Err(reason) => format!("# {}, {}, invalid message: {}", track_no, tick, reason),
+      });
+    }
+  }
+
+  lines.push(String::from("0, 0, End_of_file"));
+  lines.join("\n") + "\n"
+}
+
+/// type and fields of message, reason for invalid messages
+fn fields(message : &MidiMessage) -> Result<String, String> {
+  match message {
+    MidiMessage::ChannelMessage(ChannelMessage::Invalid(reason))
+    | MidiMessage::MetaMessage(MetaMessage::Invalid(reason))
+    | MidiMessage::Invalid(reason) => Err(reason.clone()),
+    MidiMessage::ChannelMessage(message) => Ok(channel_fields(message)),
+    MidiMessage::MetaMessage(message) => Ok(meta_fields(message)),
+    MidiMessage::SysMessage(message) => Ok(sys_fields(message)),
+    // midicsv has no records of system messages, they're written as the F7 packets they're stored in
+    MidiMessage::SystemCommon(message) => Ok(format!("System_exclusive_packet, {}", bytes(&Vec::from(*message)))),
+    MidiMessage::SystemRealTime(message) => Ok(format!("System_exclusive_packet, {}", bytes(&Vec::from(*message)))),
+  }
+}
+
+fn channel_fields(message : &ChannelMessage) -> String {
+  let bytes : Vec<u8> = message.clone().into();
+  let channel = bytes[0] & 0x0F;
+  match bytes[0] & 0xF0 {
+    0x80 => format!("Note_off_c, {}, {}, {}", channel, bytes[1], bytes[2]),
+    0x90 => format!("Note_on_c, {}, {}, {}", channel, bytes[1], bytes[2]),
+    0xA0 => format!("Poly_aftertouch_c, {}, {}, {}", channel, bytes[1], bytes[2]),
+    0xB0 => format!("Control_c, {}, {}, {}", channel, bytes[1], bytes[2]),
+    0xC0 => format!("Program_c, {}, {}", channel, bytes[1]),
+    0xD0 => format!("Channel_aftertouch_c, {}, {}", channel, bytes[1]),
+    _    => format!("Pitch_bend_c, {}, {}", channel, bytes[1] as u16 | (bytes[2] as u16) << 7),
+  }
+}
+
+fn meta_fields(message : &MetaMessage) -> String {
+  let data = message.data();
+  match message.meta_type().unwrap_or_default() {
+    0x00 => format!("Sequence_number, {}", u16::from_be_bytes([data[0], data[1]])),
+    meta_type @ 0x01 ..= 0x07 => format!("{}, {}", TEXT_EVENTS[meta_type as usize - 1], quote(&data)),
+    0x20 => format!("Channel_prefix, {}", data[0]),
+    0x21 => format!("MIDI_port, {}", data[0]),
+    0x2F => String::from("End_track"),
+    0x51 => format!("Tempo, {}", (data[0] as u32) << 16 | (data[1] as u32) << 8 | data[2] as u32),
+    0x54 => format!("SMPTE_offset, {}, {}, {}, {}, {}", data[0], data[1], data[2], data[3], data[4]),
+    0x58 => format!("Time_signature, {}, {}, {}, {}", data[0], data[1], data[2], data[3]),
+    0x59 => format!("Key_signature, {}, {}", data[0] as i8, quote(if data[1] == 1 { b"minor" } else { b"major" })),
+    _    => format!("Sequencer_specific, {}", bytes(&data)),
+  }
+}
+
+fn sys_fields(message : &SysEvent) -> String {
+  match message.event_byte() {
+    0xF0 => format!("System_exclusive, {}", bytes(message.data())),
+    _    => format!("System_exclusive_packet, {}", bytes(message.data())),
+  }
+}
+
+/// length followed by bytes, in decimal
+fn bytes(data : &[u8]) -> String {
+  std::iter::once(data.len().to_string())
+    .chain(data.iter().map(|byte| byte.to_string()))
+    .collect::<Vec<String>>()
+    .join(", ")
+}
@@ -0,0 +1,104 @@
+//! Human readable text form of [`Midi`], one line per event, in the format of `midicsv`.
+//!
+//! ```text
+//! 0, 0, Header, 1, 2, 480
+//! 1, 0, Start_track
+//! 1, 0, Title_t, "Piano"
+//! 1, 0, Tempo, 500000
+//! 1, 0, Note_on_c, 0, 60, 100
+//! 1, 480, Note_off_c, 0, 60, 0
+//! 1, 480, End_track
+//! 0, 0, End_of_file
+//! ```
+//!
+//! Every line is `track, tick, type, fields...`, track 0 holds the header, tracks are numbered from 1
+//! and tick is absolute tick from start of track.
+//! Strings are quoted, `"` is doubled, `\` and non printable bytes are written as `\\` and `\ooo` octal.
+//! Lines starting with `#` and blank lines are ignored, so fixtures can be commented.
+//!
+//! Chunks of unknown type are written as `0, 0, Unknown_chunk, position, "id", length, bytes...`,
+//! an extension to `midicsv` so they survive the round trip.
+
+use std::{fmt, error};
+
+use crate::model::core::midi::Midi;
+
+pub use self::{dump::to_text, assemble::from_text};
+
+mod dump;
+mod assemble;
+
+/// Error in text form, with 1 based line number where it was found.
+#[derive(Debug, Clone)]
+pub struct TextParseError {
+  line : usize,
+  message : String,
+}
+
+impl TextParseError {
+  pub fn new(line : usize, message : String) -> Self {
+    Self { line, message }
+  }
+
+  /// line number, starting at 1
+  pub fn line(&self) -> usize { self.line }
+
+  pub fn message(&self) -> &str { &self.message }
+}
+
+impl fmt::Display for TextParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "line {}: {}", self.line, self.message)
+  }
+}
+
+impl error::Error for TextParseError {}
+
+/// names of text meta events, by meta type 0x01 - 0x07
+const TEXT_EVENTS : [&str; 7] = ["Text_t", "Copyright_t", "Title_t", "Instrument_name_t", "Lyric_t", "Marker_t", "Cue_point_t"];
+
+/// Quotes `bytes` as string, escaping quotes, backslash and non printable bytes.
+fn quote(bytes : &[u8]) -> String {
+  let mut quoted = String::from("\"");
+  for &byte in bytes {
+    match byte {
+      b'"' => quoted.push_str("\"\""),
+      b'\\' => quoted.push_str("\\\\"),
+      0x20 ..= 0x7E => quoted.push(byte as char),
+      _ => quoted.push_str(&format!("\\{:03o}", byte)),
+    }
+  }
+  quoted.push('"');
+  quoted
+}
+
+/// Reverts escapes of [`quote`], quotes are already removed by field splitting.
+fn unescape(text : &str) -> Result<Vec<u8>, String> {
+  let mut bytes = Vec::new();
+  let mut chars = text.bytes().peekable();
+  while let Some(byte) = chars.next() {
+    if byte != b'\\' {
+      bytes.push(byte);
+      continue;
+    }
+    match chars.peek() {
+      Some(b'\\') => { chars.next(); bytes.push(b'\\'); },
+      _ => {
+        let octal : Vec<u8> = (0..3).filter_map(|_| chars.next_if(|digit| (b'0' ..= b'7').contains(digit))).collect();
+        let octal = std::str::from_utf8(&octal).unwrap_or_default();
+        match u8::from_str_radix(octal, 8) {
+          Ok(byte) if octal.len() == 3 => bytes.push(byte),
+          _ => return Err(format!("invalid escape in string \"{}\", expected \\\\ or \\ooo", text)),
+        }
+      }
+    }
+  }
+  Ok(bytes)
+}
+
+impl Midi {
+  /// Text form of midi, see [`text`](crate::text)
+  pub fn to_text(&self) -> String {
+    to_text(self)
+  }
+}
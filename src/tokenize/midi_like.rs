@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use crate::{
+  primitive::{Word, FloatWord},
+  model::note_seq::{NoteSeq, Note}
+};
+
+use super::{Token, Tokenizer, TokenizerConfig, Vocabulary};
+
+/// MIDI-Like tokenization scheme.
+/// 
+/// Every note is written as NOTE_ON and NOTE_OFF tokens, separated by TIME_SHIFT tokens.
+/// VELOCITY token is written before NOTE_ON only when velocity bin changes.
+/// 
+/// e.g. C4 quater note at 120 qbpm, with default config
+/// 
+/// `Velocity(25), NoteOn(60), TimeShift(50), NoteOff(60)`
+#[derive(Debug, Clone)]
+pub struct MidiLike {
+  config : TokenizerConfig,
+  vocabulary : Vocabulary,
+}
+
+impl MidiLike {
+  /// Config is [clamped](TokenizerConfig::clamped) to usable values.
+  pub fn new(config : TokenizerConfig) -> Self {
+    let config = config.clamped();
+    Self { vocabulary : Vocabulary::midi_like(&config), config }
+  }
+
+  fn steps(&self, time : FloatWord) -> Word {
+    (time / self.config.time_shift_resolution).round().max(0.0) as Word
+  }
+}
+
+impl Default for MidiLike {
+  fn default() -> Self {
+    Self::new(TokenizerConfig::default())
+  }
+}
+
+impl Tokenizer for MidiLike {
+  fn config(&self) -> &TokenizerConfig { &self.config }
+
+  fn vocabulary(&self) -> &Vocabulary { &self.vocabulary }
+
+  fn tokenize(&self, note_seq : &NoteSeq) -> Vec<Token> {
+    // (step, is note on, pitch, velocity bin), note offs are sorted before note ons at same step
+    let mut events = Vec::with_capacity(note_seq.notes().len() * 2);
+
+    for note in note_seq.notes().iter().filter(|note| self.config.contains_pitch(note.pitch())) {
+      let start = self.steps(note.start_time());
+      let end = self.steps(note.end_time()).max(start + 1);
+      let velocity = self.config.velocity_bin(note.velocity());
+      events.push((start, true, note.pitch(), velocity));
+      events.push((end, false, note.pitch(), velocity));
+    }
+    events.sort_by_key(|(step, is_on, pitch, _)| (*step, *is_on, *pitch));
+
+    let mut tokens = Vec::with_capacity(events.len() * 2);
+    let mut cursor = 0;
+    let mut last_velocity = None;
+
+    for (step, is_on, pitch, velocity) in events {
+      let mut shift = step - cursor;
+      while shift > 0 {
+        let steps = shift.min(self.config.max_time_shift);
+        tokens.push(Token::TimeShift(steps));
+        shift -= steps;
+      }
+      cursor = step;
+
+      if is_on {
+        if last_velocity != Some(velocity) {
+          tokens.push(Token::Velocity(velocity));
+          last_velocity = Some(velocity);
+        }
+        tokens.push(Token::NoteOn(pitch));
+      } else {
+        tokens.push(Token::NoteOff(pitch));
+      }
+    }
+    tokens
+  }
+
+  fn detokenize(&self, tokens : &[Token]) -> NoteSeq {
+    let mut note_seq = NoteSeq::default();
+
+    let mut time = 0.0;
+    let mut velocity = self.config.bin_velocity(self.config.velocity_bin(64));
+
+    // Keeps start time and velocity of notes that are yet to be closed.
+    let mut timekeeper : HashMap<Word, (FloatWord, Word)> = HashMap::new();
+
+    for token in tokens {
+      match *token {
+        Token::TimeShift(steps) => time += steps as FloatWord * self.config.time_shift_resolution,
+        Token::Velocity(bin) => velocity = self.config.bin_velocity(bin),
+        Token::NoteOn(pitch) => {
+          if let Some((start_time, velocity)) = timekeeper.insert(pitch, (time, velocity)) {
+            note_seq.add_note(Note::new(pitch, velocity, start_time, time));
+          }
+        },
+        Token::NoteOff(pitch) => {
+          if let Some((start_time, velocity)) = timekeeper.remove(&pitch) {
+            note_seq.add_note(Note::new(pitch, velocity, start_time, time));
+          }
+        },
+        _ => {}
+      }
+    }
+
+    // closing notes left open at end of sequence
+    let mut open = timekeeper.into_iter().collect::<Vec<_>>();
+    open.sort_by_key(|(pitch, _)| *pitch);
+    for (pitch, (start_time, velocity)) in open {
+      note_seq.add_note(Note::new(pitch, velocity, start_time, time));
+    }
+    note_seq
+  }
+}
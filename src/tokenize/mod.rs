@@ -0,0 +1,160 @@
+//! Converts [`NoteSeq`] / [`TrackSeq`] to token sequences consumed by sequence models, and back.
+//! 
+//! Two well known schemes are supported
+//! - [`MidiLike`] : NOTE_ON / NOTE_OFF / TIME_SHIFT / VELOCITY events (Oore et al., 2018)
+//! - [`Remi`] : Bar / Position / Velocity / Pitch / Duration events (Huang & Yang, 2020)
+//! 
+//! Each scheme owns a [`Vocabulary`] built from [`TokenizerConfig`], which maps tokens to integer ids.
+
+use crate::{
+  primitive::{Word, FloatWord},
+  model::note_seq::{NoteSeq, TrackSeq}
+};
+
+pub use self::{vocabulary::Vocabulary, midi_like::MidiLike, remi::Remi};
+
+mod vocabulary;
+mod midi_like;
+mod remi;
+
+/// Single token of a tokenized note sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Token {
+  Pad,
+  Bos,
+  Eos,
+  /// MIDI-Like : note with pitch starts
+  NoteOn(Word),
+  /// MIDI-Like : note with pitch ends
+  NoteOff(Word),
+  /// MIDI-Like : moves time forward by number of `time_shift_resolution` steps
+  TimeShift(Word),
+  /// velocity bin applied to following notes
+  Velocity(Word),
+  /// REMI : start of new bar
+  Bar,
+  /// REMI : position inside bar, in `beat_resolution` steps per quarter beat
+  Position(Word),
+  /// REMI : pitch of note
+  Pitch(Word),
+  /// REMI : duration of note, in `beat_resolution` steps per quarter beat
+  Duration(Word),
+}
+
+/// Configuration shared by all tokenization schemes.
+#[derive(Debug, Clone)]
+pub struct TokenizerConfig {
+  /// lowest and highest (inclusive) pitch in vocabulary, notes outside range are dropped.
+  pub pitch_range : (Word, Word),
+  /// number of bins the velocity range 0..128 is divided in.
+  pub velocity_bins : Word,
+  /// length of single TIME_SHIFT step in seconds.
+  pub time_shift_resolution : FloatWord,
+  /// max steps single TIME_SHIFT token can carry, longer shifts are split.
+  pub max_time_shift : Word,
+  /// number of REMI positions per quarter beat.
+  pub beat_resolution : Word,
+  /// max duration, in REMI positions, single Duration token can carry.
+  pub max_duration : Word,
+}
+
+impl Default for TokenizerConfig {
+  fn default() -> Self {
+    Self {
+      pitch_range : (0, 127),
+      velocity_bins : 32,
+      time_shift_resolution : 0.01,
+      max_time_shift : 100,
+      beat_resolution : 4,
+      max_duration : 64,
+    }
+  }
+}
+
+impl TokenizerConfig {
+  /// Returns config with counts and steps clamped to at least 1, and velocity bins to at most 128.
+  /// Non positive resolution falls back to default.
+  pub fn clamped(self) -> Self {
+    let time_shift_resolution = match self.time_shift_resolution {
+      resolution if resolution > 0.0 => resolution,
+      _ => Self::default().time_shift_resolution,
+    };
+    Self {
+      velocity_bins : self.velocity_bins.clamp(1, 128),
+      time_shift_resolution,
+      max_time_shift : self.max_time_shift.max(1),
+      beat_resolution : self.beat_resolution.max(1),
+      max_duration : self.max_duration.max(1),
+      ..self
+    }
+  }
+
+  pub fn contains_pitch(&self, pitch : Word) -> bool {
+    pitch >= self.pitch_range.0 && pitch <= self.pitch_range.1
+  }
+
+  /// Returns velocity bin for MIDI velocity `velocity`.
+  pub fn velocity_bin(&self, velocity : Word) -> Word {
+    (velocity.min(127) * self.velocity_bins / 128).min(self.velocity_bins - 1)
+  }
+
+  /// Returns MIDI velocity at center of velocity bin `bin`.
+  pub fn bin_velocity(&self, bin : Word) -> Word {
+    ((bin * 128 + 64) / self.velocity_bins).clamp(1, 127)
+  }
+}
+
+/// Tokenizer defines behavior common to all tokenization schemes.
+pub trait Tokenizer {
+  fn config(&self) -> &TokenizerConfig;
+
+  fn vocabulary(&self) -> &Vocabulary;
+
+  /// Converts note sequence to tokens.
+  fn tokenize(&self, note_seq : &NoteSeq) -> Vec<Token>;
+
+  /// Converts tokens back to note sequence. 
+  /// Tokens not belonging to scheme are ignored.
+  fn detokenize(&self, tokens : &[Token]) -> NoteSeq;
+
+  /// Converts note sequence to tokens, with bars following time signatures of `meter` rather than its own,
+  /// e.g. conductor track of format 1 midi. Schemes without bars ignore `meter`.
+  fn tokenize_with_meter(&self, note_seq : &NoteSeq, _meter : &NoteSeq) -> Vec<Token> {
+    self.tokenize(note_seq)
+  }
+
+  /// Converts tokens back to note sequence, placed on tempos and time signatures of `meter`.
+  /// Schemes without bars ignore `meter`.
+  fn detokenize_with_meter(&self, tokens : &[Token], _meter : &NoteSeq) -> NoteSeq {
+    self.detokenize(tokens)
+  }
+
+  /// Converts every track of track sequence to tokens.
+  /// Format 1 keeps time signatures in first track, so it's the meter of every track.
+  fn tokenize_tracks(&self, track_seq : &TrackSeq) -> Vec<Vec<Token>> {
+    let tracks = track_seq.tracks();
+    match tracks.first() {
+      Some(conductor) if track_seq.format() == 1 => {
+        tracks.iter().map(|note_seq| self.tokenize_with_meter(note_seq, conductor)).collect()
+      },
+      _ => tracks.iter().map(|note_seq| self.tokenize(note_seq)).collect(),
+    }
+  }
+
+  /// Converts note sequence to token ids of vocabulary.
+  fn encode(&self, note_seq : &NoteSeq) -> Vec<Word> {
+    self.tokenize(note_seq)
+      .iter()
+      .filter_map(|token| self.vocabulary().id(token))
+      .collect()
+  }
+
+  /// Converts token ids of vocabulary back to note sequence. Unknown ids are ignored.
+  fn decode(&self, ids : &[Word]) -> NoteSeq {
+    let tokens = ids
+      .iter()
+      .filter_map(|id| self.vocabulary().token(*id))
+      .collect::<Vec<Token>>();
+    self.detokenize(&tokens)
+  }
+}
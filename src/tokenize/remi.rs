@@ -0,0 +1,186 @@
+use crate::{
+  primitive::{Word, FloatWord},
+  model::note_seq::{NoteSeq, Note}
+};
+
+use super::{Token, Tokenizer, TokenizerConfig, Vocabulary};
+
+/// REMI (REvamped MIDI-derived events) tokenization scheme.
+/// 
+/// Time is written on bar grid, derived from tempos and time signatures of note sequence.
+/// Every note is written as Position (if changed), Velocity, Pitch and Duration tokens,
+/// every new bar starts with Bar token, empty bars included.
+/// 
+/// e.g. C4 quater note on 2nd beat of first bar, with default config
+/// 
+/// `Bar, Position(4), Velocity(25), Pitch(60), Duration(4)`
+#[derive(Debug, Clone)]
+pub struct Remi {
+  config : TokenizerConfig,
+  vocabulary : Vocabulary,
+}
+
+impl Remi {
+  /// Config is [clamped](TokenizerConfig::clamped) to usable values.
+  pub fn new(config : TokenizerConfig) -> Self {
+    let config = config.clamped();
+    Self { vocabulary : Vocabulary::remi(&config), config }
+  }
+
+  /// Quantized position (in `beat_resolution` steps per quater beat) at `time` seconds.
+  fn position(&self, note_seq : &NoteSeq, time : FloatWord) -> Word {
+    (note_seq.beat_at(time) * self.config.beat_resolution as FloatWord).round().max(0.0) as Word
+  }
+}
+
+impl Default for Remi {
+  fn default() -> Self {
+    Self::new(TokenizerConfig::default())
+  }
+}
+
+/// Bar grid of meter note sequence, stores segments of (first position, first bar, positions per bar),
+/// new segment starts with every time signature change.
+struct BarGrid(Vec<(Word, Word, Word)>);
+
+impl BarGrid {
+  fn new(remi : &Remi, meter : &NoteSeq) -> Self {
+    let mut segments : Vec<(Word, Word, Word)> = Vec::new();
+
+    for time_signature in meter.time_signatures() {
+      let (nn, dd) = *time_signature.element();
+      let per_bar = (nn * remi.config.beat_resolution * 4 / dd.max(1)).max(1);
+      let start = remi.position(meter, time_signature.at());
+
+      let bar = match segments.last() {
+        // bars of previous segment, rounded up. Change before start of previous segment,
+        // i.e. second change inside same bar, replaces it
+        Some(&(prev_start, prev_bar, prev_per_bar)) => {
+          let elapsed = start.saturating_sub(prev_start);
+          prev_bar + elapsed / prev_per_bar + Word::from(!elapsed.is_multiple_of(prev_per_bar))
+        },
+        None => 0,
+      };
+      // time signature change inside bar, starts the new bar after current one
+      let start = match segments.last() {
+        Some(&(prev_start, prev_bar, prev_per_bar)) => prev_start + (bar - prev_bar) * prev_per_bar,
+        None => start,
+      };
+      segments.retain(|&(prev_start, _, _)| prev_start < start);
+      segments.push((start, bar, per_bar));
+    }
+    if segments.is_empty() || segments[0].0 > 0 {
+      segments.insert(0, (0, 0, remi.config.beat_resolution * 4));
+    }
+    Self(segments)
+  }
+
+  fn segment(&self, position : Word) -> (Word, Word, Word) {
+    *self.0.iter().rev().find(|(start, _, _)| *start <= position).unwrap_or(&self.0[0])
+  }
+
+  /// Returns the (bar, position in bar) of position.
+  fn locate(&self, position : Word) -> (Word, Word) {
+    let (start, bar, per_bar) = self.segment(position);
+    (bar + (position - start) / per_bar, (position - start) % per_bar)
+  }
+
+  /// Returns position of `bar` start.
+  fn bar_start(&self, bar : Word) -> Word {
+    let (start, first_bar, per_bar) = *self.0.iter().rev().find(|(_, first_bar, _)| *first_bar <= bar).unwrap_or(&self.0[0]);
+    start + (bar - first_bar) * per_bar
+  }
+}
+
+impl Tokenizer for Remi {
+  fn config(&self) -> &TokenizerConfig { &self.config }
+
+  fn vocabulary(&self) -> &Vocabulary { &self.vocabulary }
+
+  fn tokenize(&self, note_seq : &NoteSeq) -> Vec<Token> {
+    self.tokenize_with_meter(note_seq, note_seq)
+  }
+
+  /// Positions of notes follow tempos of `note_seq`, bars follow time signatures of `meter`.
+  fn tokenize_with_meter(&self, note_seq : &NoteSeq, meter : &NoteSeq) -> Vec<Token> {
+    let grid = BarGrid::new(self, meter);
+
+    // (start position, pitch, velocity bin, duration)
+    let mut notes = note_seq.notes()
+      .iter()
+      .filter(|note| self.config.contains_pitch(note.pitch()))
+      .map(|note| {
+        let start = self.position(note_seq, note.start_time());
+        let end = self.position(note_seq, note.end_time());
+        let duration = (end.saturating_sub(start)).clamp(1, self.config.max_duration);
+        (start, note.pitch(), self.config.velocity_bin(note.velocity()), duration)
+      })
+      .collect::<Vec<_>>();
+    notes.sort_by_key(|(start, pitch, _, _)| (*start, *pitch));
+
+    let mut tokens = Vec::with_capacity(notes.len() * 4);
+    let mut current_bar = None;
+    let mut current_position = None;
+
+    for (start, pitch, velocity, duration) in notes {
+      let (bar, position) = grid.locate(start);
+
+      while !matches!(current_bar, Some(current) if current >= bar) {
+        tokens.push(Token::Bar);
+        current_bar = Some(current_bar.map_or(0, |current| current + 1));
+        current_position = None;
+      }
+      if current_position != Some(position) {
+        tokens.push(Token::Position(position));
+        current_position = Some(position);
+      }
+      tokens.push(Token::Velocity(velocity));
+      tokens.push(Token::Pitch(pitch));
+      tokens.push(Token::Duration(duration));
+    }
+    tokens
+  }
+
+  /// Tokens are placed on grid of default note sequence, i.e. 4/4 at 120 qbpm.
+  fn detokenize(&self, tokens : &[Token]) -> NoteSeq {
+    self.detokenize_with_meter(tokens, &NoteSeq::default())
+  }
+
+  fn detokenize_with_meter(&self, tokens : &[Token], meter : &NoteSeq) -> NoteSeq {
+    let mut note_seq = NoteSeq::default();
+    for tempo in meter.tempos() {
+      note_seq.set_tempo(tempo.at(), *tempo.element());
+    }
+    for time_signature in meter.time_signatures() {
+      note_seq.set_time_signature(time_signature.at(), *time_signature.element());
+    }
+    let grid = BarGrid::new(self, &note_seq);
+    let resolution = self.config.beat_resolution as FloatWord;
+
+    let mut bar = None;
+    let mut position = 0;
+    let mut velocity = self.config.bin_velocity(self.config.velocity_bin(64));
+    let mut pitch = None;
+
+    for token in tokens {
+      match *token {
+        Token::Bar => {
+          bar = Some(bar.map_or(0, |bar : Word| bar + 1));
+          position = 0;
+        },
+        Token::Position(at) => position = at,
+        Token::Velocity(bin) => velocity = self.config.bin_velocity(bin),
+        Token::Pitch(at) => pitch = Some(at),
+        Token::Duration(duration) => {
+          let Some(at) = pitch.take() else { continue };
+          let start = grid.bar_start(bar.unwrap_or(0)) + position;
+          let start_time = note_seq.time_at(start as FloatWord / resolution);
+          let end_time = note_seq.time_at((start + duration) as FloatWord / resolution);
+          note_seq.add_note(Note::new(at, velocity, start_time, end_time));
+        },
+        _ => {}
+      }
+    }
+    note_seq
+  }
+}
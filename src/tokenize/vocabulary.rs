@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use crate::primitive::Word;
+
+use super::{Token, TokenizerConfig};
+
+/// Vocabulary maps tokens of scheme to integer ids and back.
+/// 
+/// Ids are assigned in order the tokens are added, special tokens `Pad`, `Bos` and `Eos` always take ids 0, 1 and 2.
+#[derive(Debug, Clone)]
+pub struct Vocabulary {
+  tokens : Vec<Token>,
+  ids : HashMap<Token, Word>,
+}
+
+impl Default for Vocabulary {
+  fn default() -> Self {
+    let mut vocabulary = Self { tokens : Vec::new(), ids : HashMap::new() };
+    vocabulary.extend([Token::Pad, Token::Bos, Token::Eos]);
+    vocabulary
+  }
+}
+
+impl Vocabulary {
+  /// Vocabulary of MIDI-Like scheme
+  pub fn midi_like(config : &TokenizerConfig) -> Self {
+    let mut vocabulary = Self::default();
+    let (low, high) = config.pitch_range;
+    vocabulary.extend((low..=high).map(Token::NoteOn));
+    vocabulary.extend((low..=high).map(Token::NoteOff));
+    vocabulary.extend((1..=config.max_time_shift).map(Token::TimeShift));
+    vocabulary.extend((0..config.velocity_bins).map(Token::Velocity));
+    vocabulary
+  }
+
+  /// Vocabulary of REMI scheme, positions cover a bar of up to 4 whole notes.
+  pub fn remi(config : &TokenizerConfig) -> Self {
+    let mut vocabulary = Self::default();
+    let (low, high) = config.pitch_range;
+    vocabulary.extend([Token::Bar]);
+    vocabulary.extend((0..config.beat_resolution * 16).map(Token::Position));
+    vocabulary.extend((0..config.velocity_bins).map(Token::Velocity));
+    vocabulary.extend((low..=high).map(Token::Pitch));
+    vocabulary.extend((1..=config.max_duration).map(Token::Duration));
+    vocabulary
+  }
+
+  /// Adds tokens to vocabulary, tokens already present keep their id.
+  pub fn extend(&mut self, tokens : impl IntoIterator<Item = Token>) {
+    for token in tokens {
+      if self.ids.contains_key(&token) { continue; }
+      self.ids.insert(token, self.tokens.len() as Word);
+      self.tokens.push(token);
+    }
+  }
+
+  pub fn id(&self, token : &Token) -> Option<Word> {
+    self.ids.get(token).copied()
+  }
+
+  pub fn token(&self, id : Word) -> Option<Token> {
+    self.tokens.get(id as usize).copied()
+  }
+
+  pub fn tokens(&self) -> &Vec<Token> { &self.tokens }
+
+  pub fn len(&self) -> usize { self.tokens.len() }
+
+  pub fn is_empty(&self) -> bool { self.tokens.is_empty() }
+}
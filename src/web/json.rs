@@ -0,0 +1,125 @@
+use serde_json::{json, Value};
+
+use crate::model::{
+  core::{
+    midi::Midi,
+    midi_header::{MidiHeader, MidiDivision},
+    midi_track::MidiTrack,
+    midi_event::{MidiMessage, channel_message::ChannelMessage, meta_message::MetaMessage, system_message::{SystemCommon, SystemRealTime}}
+  },
+  note_seq::NoteSeq
+};
+
+/// `{ format, ntrk, division }`, division is `{ ticksPerQuarter }` or `{ framesPerSecond, ticksPerFrame }`
+pub(crate) fn header(header : &MidiHeader) -> Value {
+  let format : Vec<u8> = header.format().into();
+  let division = match header.division() {
+    MidiDivision::MetricTime(ticks) => json!({ "ticksPerQuarter" : ticks }),
+    MidiDivision::SubDivision((smpte, ticks)) => json!({ "framesPerSecond" : -(smpte as i16), "ticksPerFrame" : ticks }),
+    MidiDivision::Invalid(reason) => json!({ "invalid" : reason }),
+  };
+  json!({ "format" : format[1], "ntrk" : *header.ntrk(), "division" : division })
+}
+
+/// `[{ events, endTick, name }]` for every track, name is `null` without Track Name
+pub(crate) fn tracks(midi : &Midi) -> Value {
+  Value::Array(midi.tracks().iter().map(|track| {
+    let name = track.iter().find_map(|event| match event.message() {
+      MidiMessage::MetaMessage(MetaMessage::TrackName(name)) => Some(name.text()),
+      _ => None,
+    });
+    json!({ "events" : track.len(), "endTick" : track.end_tick(), "name" : name })
+  }).collect())
+}
+
+/// `[{ tick, delta, kind, ... }]` for every event of track, see [`message`]
+pub(crate) fn events(track : &MidiTrack) -> Value {
+  Value::Array(track.iter_absolute().map(|(tick, event)| {
+    let mut value = message(event.message());
+    value["tick"] = json!(tick);
+    value["delta"] = json!(event.delta_time().ticks());
+    value
+  }).collect())
+}
+
+/// Message as object told by `kind`:
+/// - `channel` with `type`, `channel` and `data` bytes after status
+/// - `meta` with `metaType`, `data`, and `text` for text events
+/// - `sysex` with `status` (0xF0 or 0xF7) and `data`
+/// - `system` with `type` and `data`, the wire bytes of System Common or System Real-Time message
+/// - `invalid` with `reason`
+pub(crate) fn message(message : &MidiMessage) -> Value {
+  match message {
+    MidiMessage::ChannelMessage(ChannelMessage::Invalid(reason))
+    | MidiMessage::MetaMessage(MetaMessage::Invalid(reason))
+    | MidiMessage::Invalid(reason) => json!({ "kind" : "invalid", "reason" : reason }),
+    MidiMessage::ChannelMessage(message) => {
+      let bytes : Vec<u8> = message.clone().into();
+      let name = match bytes[0] & 0xF0 {
+        0x80 => "note_off",
+        0x90 => "note_on",
+        0xA0 => "poly_aftertouch",
+        0xB0 => "control_change",
+        0xC0 => "program_change",
+        0xD0 => "channel_aftertouch",
+        _    => "pitch_bend",
+      };
+      json!({ "kind" : "channel", "type" : name, "channel" : bytes[0] & 0x0F, "data" : &bytes[1..] })
+    },
+    MidiMessage::MetaMessage(message) => {
+      let meta_type = message.meta_type().unwrap_or_default();
+      let data = message.data();
+      let mut value = json!({ "kind" : "meta", "metaType" : meta_type });
+      if (0x01..=0x07).contains(&meta_type) {
+        value["text"] = json!(String::from_utf8_lossy(&data));
+      }
+      value["data"] = json!(data);
+      value
+    },
+    MidiMessage::SysMessage(message) => json!({ "kind" : "sysex", "status" : message.event_byte(), "data" : message.data() }),
+    MidiMessage::SystemCommon(message) => {
+      let name = match message {
+        SystemCommon::QuarterFrame { .. } => "quarter_frame",
+        SystemCommon::SongPositionPointer(_) => "song_position_pointer",
+        SystemCommon::SongSelect(_) => "song_select",
+        SystemCommon::TuneRequest => "tune_request",
+        SystemCommon::Undefined(_) => "undefined",
+      };
+      json!({ "kind" : "system", "type" : name, "data" : Vec::from(*message) })
+    },
+    MidiMessage::SystemRealTime(message) => {
+      let name = match message {
+        SystemRealTime::TimingClock => "timing_clock",
+        SystemRealTime::Start => "start",
+        SystemRealTime::Continue => "continue",
+        SystemRealTime::Stop => "stop",
+        SystemRealTime::ActiveSensing => "active_sensing",
+        SystemRealTime::SystemReset => "system_reset",
+        SystemRealTime::Undefined(_) => "undefined",
+      };
+      json!({ "kind" : "system", "type" : name, "data" : Vec::from(*message) })
+    },
+  }
+}
+
+/// `{ totalTime, notes, tempos, timeSignatures, keySignatures }`, times in seconds
+pub(crate) fn note_seq(note_seq : &NoteSeq) -> Value {
+  let notes : Vec<Value> = note_seq.notes().iter().map(|node| {
+    let note = node.element();
+    json!({ "pitch" : note.pitch(), "velocity" : note.velocity(), "startTime" : note.start_time(), "endTime" : note.end_time() })
+  }).collect();
+  let tempos : Vec<Value> = note_seq.tempos().iter().map(|node| json!({ "at" : node.at(), "qbpm" : node.element() })).collect();
+  let time_signatures : Vec<Value> = note_seq.time_signatures().iter().map(|node| {
+    let (numerator, denominator) = node.element();
+    json!({ "at" : node.at(), "numerator" : numerator, "denominator" : denominator })
+  }).collect();
+  let key_signatures : Vec<Value> = note_seq.key_signatures().iter().map(|node| json!({ "at" : node.at(), "key" : node.element() })).collect();
+
+  json!({
+    "totalTime" : note_seq.total_time(),
+    "notes" : notes,
+    "tempos" : tempos,
+    "timeSignatures" : time_signatures,
+    "keySignatures" : key_signatures,
+  })
+}
@@ -1,2 +1,7 @@
+//! Bindings of [`Midi`](crate::model::core::midi::Midi) to js through `wasm-bindgen`, see [`rmidi::rMidi`].
+//!
+//! Tests of bindings are in `tests/web.rs`, they run under node with `wasm-pack test --node`.
 
-pub mod rmidi;
\ No newline at end of file
+pub mod rmidi;
+
+mod json;
@@ -1,13 +1,91 @@
 use wasm_bindgen::prelude::*;
 
+use crate::{
+  model::{core::midi::Midi, note_seq::NoteSeq},
+  parser::MidiParser,
+  writer::MidiWriter
+};
 
+use super::json;
+
+/// Midi exposed to js, parsed from and written to `Uint8Array`.
+///
+/// Header, tracks, events and note sequence are returned as JSON text, to be read with `JSON.parse`.
 #[wasm_bindgen]
 #[allow(non_camel_case_types)]
-pub struct rMidi;
+pub struct rMidi {
+  midi : Midi
+}
 
 #[wasm_bindgen]
 impl rMidi {
+  /// empty midi, without tracks
+  #[allow(clippy::new_without_default)]
   pub fn new() -> rMidi {
-    rMidi
+    rMidi { midi : Midi::default() }
+  }
+
+  /// Parses bytes of Standard Midi File, throws the parse error as string.
+  #[wasm_bindgen(js_name = fromBytes)]
+  pub fn from_bytes(bytes : &[u8]) -> Result<rMidi, JsValue> {
+    MidiParser::parse(bytes)
+      .map(rMidi::from)
+      .map_err(|err| JsValue::from_str(&err.to_string()))
+  }
+
+  /// number of tracks
+  #[wasm_bindgen(js_name = trackCount)]
+  pub fn track_count(&self) -> usize {
+    self.midi.tracks().len()
   }
-}
\ No newline at end of file
+
+  /// `{ format, ntrk, division }`, division is `{ ticksPerQuarter }` or `{ framesPerSecond, ticksPerFrame }`
+  pub fn header(&self) -> String {
+    json::header(self.midi.header()).to_string()
+  }
+
+  /// `[{ events, endTick, name }]`, one object per track
+  pub fn tracks(&self) -> String {
+    json::tracks(&self.midi).to_string()
+  }
+
+  /// `[{ tick, delta, kind, ... }]` of `track`, throws when track doesn't exist.
+  ///
+  /// `kind` is `channel` (with `type`, `channel`, `data`), `meta` (with `metaType`, `data`, `text`),
+  /// `sysex` (with `status`, `data`), `system` (with `type`, `data`) or `invalid` (with `reason`).
+  pub fn events(&self, track : usize) -> Result<String, JsValue> {
+    self.midi.tracks().get(track)
+      .map(|track| json::events(track).to_string())
+      .ok_or_else(|| JsValue::from_str(&format!("midi has no track {}", track)))
+  }
+
+  /// note sequence of `track` (first track when not given), `{ totalTime, notes, tempos, timeSignatures, keySignatures }`,
+  /// throws when track doesn't exist or division of midi is not in ticks per quarter note.
+  #[wasm_bindgen(js_name = toNoteSeq)]
+  pub fn to_note_seq(&self, track : Option<usize>) -> Result<String, JsValue> {
+    let track = track.unwrap_or_default();
+    if self.midi.header().division().metric_time().is_none() {
+      return Err(JsValue::from_str("note sequence needs division in ticks per quarter note, midi has SMPTE or invalid division"));
+    }
+    self.midi.tracks().get(track)
+      .map(|midi_track| json::note_seq(&NoteSeq::from((self.midi.header(), midi_track))).to_string())
+      .ok_or_else(|| JsValue::from_str(&format!("midi has no track {}", track)))
+  }
+
+  /// Encodes midi to bytes of Standard Midi File, throws violations when midi is broken.
+  #[wasm_bindgen(js_name = toBytes)]
+  pub fn to_bytes(&self) -> Result<Vec<u8>, JsValue> {
+    MidiWriter::new(&self.midi).to_bytes()
+      .map_err(|err| JsValue::from_str(&err.to_string()))
+  }
+}
+
+impl From<Midi> for rMidi {
+  fn from(midi : Midi) -> Self {
+    rMidi { midi }
+  }
+}
+
+impl rMidi {
+  pub fn midi(&self) -> &Midi { &self.midi }
+}
@@ -0,0 +1,57 @@
+use std::{io::{self, Write}, fs::File, path::Path};
+
+use crate::model::ump::{Ump, Utility, StreamMessage, clip::{MidiClip, CLIP_FILE_ID}};
+
+/// Writes MidiClip as MIDI Clip File (`SMF2CLIP`).
+///
+/// Every packet of sequence, and Start and End of Clip, is preceded by Delta Clockstamp,
+/// delta times above 20 bits are split over many Delta Clockstamps.
+#[derive(Debug, Clone)]
+pub struct ClipWriter<'a> {
+  clip : &'a MidiClip
+}
+
+impl<'a> ClipWriter<'a> {
+  pub fn new(clip : &'a MidiClip) -> Self {
+    Self { clip }
+  }
+
+  /// Encodes the clip to bytes of MIDI Clip File
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut packets = vec![Ump::Utility(Utility::DeltaClockstampTpq(self.clip.ticks_per_quarter()))];
+    packets.extend(self.clip.header().iter().cloned());
+
+    Self::push_delta(&mut packets, 0);
+    packets.push(Ump::Stream(StreamMessage::StartOfClip));
+    for (delta, ump) in self.clip.events() {
+      Self::push_delta(&mut packets, *delta);
+      packets.push(ump.clone());
+    }
+    Self::push_delta(&mut packets, self.clip.end_delta());
+    packets.push(Ump::Stream(StreamMessage::EndOfClip));
+
+    let mut bytes = CLIP_FILE_ID.to_vec();
+    for ump in &packets {
+      bytes.extend(Vec::<u8>::from(ump));
+    }
+    bytes
+  }
+
+  /// Writes the clip to `writer`
+  pub fn write_to<W : Write>(&self, mut writer : W) -> io::Result<()> {
+    writer.write_all(&self.to_bytes())
+  }
+
+  /// Writes the clip to file at `path`, usually with `.midi2` extension
+  pub fn write<P : AsRef<Path>>(&self, path : P) -> io::Result<()> {
+    self.write_to(File::create(path)?)
+  }
+
+  fn push_delta(packets : &mut Vec<Ump>, mut delta : u32) {
+    while delta > Utility::MAX_DELTA_CLOCKSTAMP {
+      packets.push(Ump::Utility(Utility::DeltaClockstamp(Utility::MAX_DELTA_CLOCKSTAMP)));
+      delta -= Utility::MAX_DELTA_CLOCKSTAMP;
+    }
+    packets.push(Ump::Utility(Utility::DeltaClockstamp(delta)));
+  }
+}
@@ -1,11 +1,69 @@
-struct MidiWriter {
-  file_path : Path
+use std::{io::{self, Write}, fs::File, path::Path};
+
+use crate::model::core::{midi::Midi, validation::ValidationError};
+
+/// Writes Midi as Standard Midi File.
+///
+/// Header is written first, then tracks, chunks of unknown type are written back 
+/// at the position among tracks they were read from.
+/// Midi breaking rules of Standard Midi File is refused, see [`Midi::validate`].
+#[derive(Debug, Clone)]
+pub struct MidiWriter<'a> {
+  midi : &'a Midi
 }
 
+impl<'a> MidiWriter<'a> {
+  pub fn new(midi : &'a Midi) -> Self {
+    Self { midi }
+  }
+
+  /// Encodes the midi to bytes of Standard Midi File
+  pub fn to_bytes(&self) -> Result<Vec<u8>, ValidationError> {
+    self.midi.validate()?;
+    let mut bytes : Vec<u8> = self.midi.header().clone().into();
+
+    let mut unknown_chunks = self.midi.unknown_chunks().iter().peekable();
+
+    for (track_no, track) in self.midi.tracks().iter().enumerate() {
+      while let Some(chunk) = unknown_chunks.next_if(|chunk| chunk.position() <= track_no) {
+        bytes.extend(Vec::<u8>::from(chunk.clone()));
+      }
+      bytes.extend(Vec::<u8>::from(track.clone()));
+    }
+
+    for chunk in unknown_chunks {
+      bytes.extend(Vec::<u8>::from(chunk.clone()));
+    }
 
-impl MidiWriter { 
-  
-  fn to_bytes(&self) -> Vec<u8> { 
+    Ok(bytes)
+  }
+
+  /// Encodes the midi wrapped in RIFF `RMID` container, as in `.rmi` files
+  pub fn to_rmid_bytes(&self) -> Result<Vec<u8>, ValidationError> {
+    let smf = self.to_bytes()?;
+    let padding = smf.len() & 1;
+
+    // RIFF stores the sizes as little endian, chunks are padded to even length
+    Ok([ b"RIFF".to_vec(),
+      ((4 + 8 + smf.len() + padding) as u32).to_le_bytes().to_vec(),
+      b"RMID".to_vec(),
+      b"data".to_vec(),
+      (smf.len() as u32).to_le_bytes().to_vec(),
+      smf,
+      vec![0; padding]
+    ].concat())
+  }
 
+  /// Writes the midi to `writer`, invalid midi fails with [`io::ErrorKind::InvalidData`]
+  pub fn write_to<W : Write>(&self, mut writer : W) -> io::Result<()> {
+    let bytes = self.to_bytes().map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    writer.write_all(&bytes)
   }
-}
\ No newline at end of file
+
+  /// Writes the midi to file at `path`, file is created or truncated.
+  /// Midi is validated first, so invalid midi doesn't leave a truncated file behind.
+  pub fn write<P : AsRef<Path>>(&self, path : P) -> io::Result<()> {
+    let bytes = self.to_bytes().map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    File::create(path)?.write_all(&bytes)
+  }
+}
@@ -0,0 +1,5 @@
+pub use self::{midi_writer::MidiWriter, music_xml_writer::MusicXmlWriter, clip_writer::ClipWriter};
+
+mod midi_writer;
+mod music_xml_writer;
+mod clip_writer;
@@ -0,0 +1,261 @@
+use std::{io::{self, Write}, fs::File, path::Path, fmt::Write as _};
+
+use crate::{
+  primitive::{Word, FloatWord},
+  model::{
+    note_seq::{TrackSeq, NoteSeq},
+    timing::MeasureMap,
+    core::midi_event::meta_message::{KeySignature, TimeSignature}
+  }
+};
+
+/// note types with their length in quarter notes, longest first
+const NOTE_TYPES : [(FloatWord, &str); 7] = [
+  (4.0, "whole"), (2.0, "half"), (1.0, "quarter"), (0.5, "eighth"), (0.25, "16th"), (0.125, "32nd"), (0.0625, "64th")
+];
+
+/// Span of single voice, in divisions, without pitches for rests.
+#[derive(Debug, Clone)]
+struct Span {
+  start : u64,
+  end : u64,
+  pitches : Vec<Word>,
+}
+
+/// Writes TrackSeq as MusicXML (partwise) score, e.g. to open it in MuseScore.
+///
+/// Every track with notes becomes a part, measures follow time signatures of measure map.
+/// Notes are quantized to `1 / divisions` quarter note, notes starting together become chord,
+/// and note overlapping next onset is cut at it, so every part is single voice.
+/// Notes crossing barlines or not fitting single note value are split and tied.
+///
+/// Key signatures are taken from the first track declaring any, usually the conductor track,
+/// and pitches are spelled following the key.
+#[derive(Debug, Clone)]
+pub struct MusicXmlWriter<'a> {
+  track_seq : &'a TrackSeq,
+  measure_map : &'a MeasureMap,
+  divisions : Word,
+}
+
+impl<'a> MusicXmlWriter<'a> {
+  pub fn new(track_seq : &'a TrackSeq, measure_map : &'a MeasureMap) -> Self {
+    Self { track_seq, measure_map, divisions : 4 }
+  }
+
+  /// Sets quantization grid in divisions per quarter note, default 4 i.e. sixteenth notes.
+  /// Rounded down to power of two, between 1 and 16.
+  pub fn with_divisions(mut self, divisions : Word) -> Self {
+    self.divisions = 1 << (Word::BITS - 1 - divisions.clamp(1, 16).leading_zeros());
+    self
+  }
+
+  /// Encodes the score as MusicXML document
+  pub fn to_xml(&self) -> String {
+    let parts : Vec<&NoteSeq> = self.track_seq.tracks().iter().filter(|note_seq| !note_seq.notes().is_empty()).collect();
+    // key changes are placed by position in divisions, as tracks may follow different tempos
+    let key_signatures : Vec<(u64, KeySignature)> = self.track_seq.tracks()
+      .iter()
+      .find(|note_seq| !note_seq.declared_key_signatures().is_empty())
+      .map_or(Vec::new(), |note_seq| note_seq.key_signatures()
+        .iter()
+        .filter_map(|key| Some((self.divisions(note_seq.beat_at(key.at())), KeySignature::from_name(key.element())?)))
+        .collect());
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+    xml.push_str("<!DOCTYPE score-partwise PUBLIC \"-//Recordare//DTD MusicXML 4.0 Partwise//EN\" \"http://www.musicxml.org/dtds/partwise.dtd\">\n");
+    xml.push_str("<score-partwise version=\"4.0\">\n  <part-list>\n");
+    for (index, _) in parts.iter().enumerate() {
+      let _ = writeln!(xml, "    <score-part id=\"P{0}\"><part-name>Track {0}</part-name></score-part>", index + 1);
+    }
+    xml.push_str("  </part-list>\n");
+
+    for (index, note_seq) in parts.iter().enumerate() {
+      let _ = writeln!(xml, "  <part id=\"P{}\">", index + 1);
+      self.write_part(&mut xml, note_seq, &key_signatures);
+      xml.push_str("  </part>\n");
+    }
+    xml.push_str("</score-partwise>\n");
+    xml
+  }
+
+  /// Writes the score to `writer`
+  pub fn write_to<W : Write>(&self, mut writer : W) -> io::Result<()> {
+    writer.write_all(self.to_xml().as_bytes())
+  }
+
+  /// Writes the score to file at `path`, usually with `.musicxml` extension
+  pub fn write<P : AsRef<Path>>(&self, path : P) -> io::Result<()> {
+    self.write_to(File::create(path)?)
+  }
+
+  /// position in divisions of `quarters` quarter notes
+  fn divisions(&self, quarters : FloatWord) -> u64 {
+    (quarters * self.divisions as FloatWord).round().max(0.0) as u64
+  }
+
+  /// position in divisions of absolute `tick`
+  fn tick_divisions(&self, tick : u64) -> u64 {
+    let ticks_per_quarter = self.measure_map.tempo_map().ticks_per_quarter().max(1) as FloatWord;
+    self.divisions(tick as FloatWord / ticks_per_quarter)
+  }
+
+  /// Quantizes notes to single voice of chords and rests, covering time from 0 to last note end.
+  fn spans(&self, note_seq : &NoteSeq) -> Vec<Span> {
+    let mut notes : Vec<(u64, u64, Word)> = note_seq.notes()
+      .iter()
+      .map(|note| {
+        let start = self.divisions(note_seq.beat_at(note.start_time()));
+        let end = self.divisions(note_seq.beat_at(note.end_time())).max(start + 1);
+        (start, end, note.pitch())
+      })
+      .collect();
+    notes.sort();
+
+    let mut spans : Vec<Span> = Vec::new();
+    for (index, (start, end, pitch)) in notes.iter().enumerate() {
+      let next_onset = notes[index..].iter().map(|note| note.0).find(|onset| onset > start);
+      let end = next_onset.map_or(*end, |onset| onset.min(*end));
+      match spans.last_mut() {
+        Some(span) if span.start == *start => {
+          span.end = span.end.max(end);
+          if !span.pitches.contains(pitch) { span.pitches.push(*pitch); }
+        },
+        last => {
+          let rest_start = last.map_or(0, |span| span.end);
+          if rest_start < *start {
+            spans.push(Span { start : rest_start, end : *start, pitches : Vec::new() });
+          }
+          spans.push(Span { start : *start, end, pitches : vec![*pitch] });
+        }
+      }
+    }
+    spans
+  }
+
+  fn write_part(&self, xml : &mut String, note_seq : &NoteSeq, key_signatures : &[(u64, KeySignature)]) {
+    let mut spans = self.spans(note_seq);
+    let end = spans.last().map_or(1, |span| span.end);
+    let low_pitch = note_seq.notes().iter().map(|note| note.pitch() as FloatWord).sum::<FloatWord>() < 60.0 * note_seq.notes().len() as FloatWord;
+
+    let (mut bar, mut key, mut time, mut tempo) = (1, None, None, None);
+    loop {
+      let (bar_start, bar_end) = self.measure_map.bar_range(bar);
+      let (start, stop) = (self.tick_divisions(bar_start), self.tick_divisions(bar_end));
+      if start >= end && bar > 1 { break; }
+      if stop <= start { bar += 1; continue; }
+
+      let _ = writeln!(xml, "    <measure number=\"{}\">", bar);
+
+      let bar_key = key_signatures.iter().rev()
+        .find(|(at, _)| *at <= start)
+        .map_or(KeySignature::new(0, false), |(_, key)| key.clone());
+      let bar_time = self.measure_map.time_signature_at(bar_start);
+      let new_key = (key != Some((bar_key.sf(), bar_key.is_minor()))).then_some(&bar_key);
+      let new_time = (time != Some((bar_time.numerator(), bar_time.denominator()))).then_some(&bar_time);
+      if bar == 1 || new_key.is_some() || new_time.is_some() {
+        self.write_attributes(xml, bar == 1, new_key, new_time, low_pitch);
+      }
+      key = Some((bar_key.sf(), bar_key.is_minor()));
+      time = Some((bar_time.numerator(), bar_time.denominator()));
+
+      let bpm = self.measure_map.tempo_map().tempo_at(bar_start).bpm();
+      if tempo != Some(bpm) {
+        let _ = writeln!(xml, "      <direction placement=\"above\"><direction-type><metronome><beat-unit>quarter</beat-unit><per-minute>{0}</per-minute></metronome></direction-type><sound tempo=\"{0}\"/></direction>", bpm);
+        tempo = Some(bpm);
+      }
+
+      // last bar is filled with rest
+      if let Some(span) = spans.last().filter(|span| span.end < stop && stop >= end) {
+        let rest_start = span.end;
+        spans.push(Span { start : rest_start, end : stop, pitches : Vec::new() });
+      }
+
+      let spans_in_bar : Vec<&Span> = spans.iter().filter(|span| span.start < stop && span.end > start).collect();
+      if spans_in_bar.iter().all(|span| span.pitches.is_empty()) {
+        let _ = writeln!(xml, "      <note><rest measure=\"yes\"/><duration>{}</duration><voice>1</voice></note>", stop - start);
+      } else {
+        for span in spans_in_bar {
+          let (from, to) = (span.start.max(start), span.end.min(stop));
+          self.write_span(xml, span, to - from, span.start < start, span.end > stop, &bar_key);
+        }
+      }
+      xml.push_str("    </measure>\n");
+      bar += 1;
+    }
+  }
+
+  fn write_attributes(&self, xml : &mut String, first : bool, key : Option<&KeySignature>, time : Option<&TimeSignature>, low_pitch : bool) {
+    xml.push_str("      <attributes>");
+    if first {
+      let _ = write!(xml, "<divisions>{}</divisions>", self.divisions);
+    }
+    if let Some(key) = key {
+      let _ = write!(xml, "<key><fifths>{}</fifths><mode>{}</mode></key>", key.sf(), if key.is_minor() { "minor" } else { "major" });
+    }
+    if let Some(time) = time {
+      let _ = write!(xml, "<time><beats>{}</beats><beat-type>{}</beat-type></time>", time.numerator(), time.denominator());
+    }
+    if first {
+      xml.push_str(if low_pitch { "<clef><sign>F</sign><line>4</line></clef>" } else { "<clef><sign>G</sign><line>2</line></clef>" });
+    }
+    xml.push_str("</attributes>\n");
+  }
+
+  /// Writes `length` divisions of span, split in note values, tied to previous / next bar if `tied_from` / `tied_to`.
+  fn write_span(&self, xml : &mut String, span : &Span, length : u64, tied_from : bool, tied_to : bool, key : &KeySignature) {
+    let values = self.note_values(length);
+    for (index, (duration, note_type, dotted)) in values.iter().enumerate() {
+      let stop = !span.pitches.is_empty() && (index > 0 || tied_from);
+      let start = !span.pitches.is_empty() && (index + 1 < values.len() || tied_to);
+
+      if span.pitches.is_empty() {
+        let _ = writeln!(xml, "      <note><rest/><duration>{}</duration><voice>1</voice><type>{}</type>{}</note>",
+          duration, note_type, if *dotted { "<dot/>" } else { "" });
+        continue;
+      }
+
+      for (chord, pitch) in span.pitches.iter().enumerate() {
+        let (step, alter, octave) = key.spell(*pitch);
+        xml.push_str("      <note>");
+        if chord > 0 { xml.push_str("<chord/>"); }
+        let _ = write!(xml, "<pitch><step>{}</step>", step);
+        if alter != 0 { let _ = write!(xml, "<alter>{}</alter>", alter); }
+        let _ = write!(xml, "<octave>{}</octave></pitch><duration>{}</duration>", octave, duration);
+        if stop { xml.push_str("<tie type=\"stop\"/>"); }
+        if start { xml.push_str("<tie type=\"start\"/>"); }
+        let _ = write!(xml, "<voice>1</voice><type>{}</type>", note_type);
+        if *dotted { xml.push_str("<dot/>"); }
+        if stop || start {
+          xml.push_str("<notations>");
+          if stop { xml.push_str("<tied type=\"stop\"/>"); }
+          if start { xml.push_str("<tied type=\"start\"/>"); }
+          xml.push_str("</notations>");
+        }
+        xml.push_str("</note>\n");
+      }
+    }
+  }
+
+  /// Splits `length` divisions in (duration, type, dotted) note values, longest first.
+  fn note_values(&self, mut length : u64) -> Vec<(u64, &'static str, bool)> {
+    let mut candidates = Vec::new();
+    for (quarters, note_type) in NOTE_TYPES {
+      let duration = quarters * self.divisions as FloatWord;
+      if duration.fract() != 0.0 || duration < 1.0 { continue; }
+      if (duration * 1.5).fract() == 0.0 && quarters < 4.0 {
+        candidates.push(((duration * 1.5) as u64, note_type, true));
+      }
+      candidates.push((duration as u64, note_type, false));
+    }
+
+    let mut values = Vec::new();
+    while length > 0 {
+      let value = candidates.iter().find(|(duration, _, _)| *duration <= length).copied().unwrap_or((length, "64th", false));
+      values.push(value);
+      length -= value.0;
+    }
+    values
+  }
+}
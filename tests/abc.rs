@@ -0,0 +1,58 @@
+//! Round trip tests of ABC import and export.
+
+use rmidirs::{abc::{from_abc, AbcTune}, primitive::{Word, FloatWord}};
+
+/// tune read back from ABC exported from its note sequence
+fn round_trip(tune : &AbcTune) -> AbcTune {
+  let abc = tune.to_note_seq().to_abc();
+  from_abc(&abc).unwrap_or_else(|err| panic!("{}\n{}", err, abc))
+}
+
+/// asserts that notes have same pitches, and start and end within `tolerance` quarter notes
+fn assert_notes(notes : &[(FloatWord, FloatWord, Word)], expected : &[(FloatWord, FloatWord, Word)], tolerance : FloatWord) {
+  assert_eq!(notes.len(), expected.len(), "{:?}", notes);
+  for (note, expected) in notes.iter().zip(expected) {
+    assert_eq!(note.2, expected.2, "{:?}", notes);
+    assert!((note.0 - expected.0).abs() <= tolerance && (note.1 - expected.1).abs() <= tolerance, "{:?} != {:?}", note, expected);
+  }
+}
+
+#[test]
+fn repeats_with_endings_are_expanded() {
+  let tune = from_abc("X:1\nT:Repeats\nM:2/4\nL:1/4\nK:C\n|:C D|1 E F:|2 G A|]\n").unwrap();
+  let pitches : Vec<Word> = tune.notes().iter().map(|note| note.2).collect();
+  // C D E F, repeat of C D, second ending G A
+  assert_eq!(pitches, vec![60, 62, 64, 65, 60, 62, 67, 69]);
+  assert_eq!(tune.notes()[7], (7.0, 8.0, 69));
+
+  assert_notes(round_trip(&tune).notes(), tune.notes(), 0.0);
+}
+
+#[test]
+fn tuplets_fit_in_time_of_two() {
+  let tune = from_abc("X:1\nM:2/4\nL:1/8\nK:C\n(3CDE F2 G|]\n").unwrap();
+  let third = 1.0 / 3.0;
+  assert_notes(tune.notes(), &[(0.0, third, 60), (third, 2.0 * third, 62), (2.0 * third, 1.0, 64), (1.0, 2.0, 65), (2.0, 2.5, 67)], 1e-4);
+
+  // export grid is 1/8 of quarter note
+  assert_notes(round_trip(&tune).notes(), tune.notes(), 0.0625);
+}
+
+#[test]
+fn broken_rhythm_dots_first_note() {
+  let tune = from_abc("X:1\nM:2/4\nL:1/8\nK:G\nG>A B<c d2 z2|]\n").unwrap();
+  assert_notes(tune.notes(), &[(0.0, 0.75, 67), (0.75, 1.0, 69), (1.0, 1.25, 71), (1.25, 2.0, 72), (2.0, 3.0, 74)], 1e-4);
+
+  assert_notes(round_trip(&tune).notes(), tune.notes(), 0.0);
+}
+
+#[test]
+fn chords_keep_top_note_in_export() {
+  let tune = from_abc("X:1\nM:3/4\nL:1/4\nK:C\n[CEG]2 [Ec]|]\n").unwrap();
+  let mut notes = tune.notes().to_vec();
+  notes.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap().then(a.2.cmp(&b.2)));
+  assert_notes(&notes, &[(0.0, 2.0, 60), (0.0, 2.0, 64), (0.0, 2.0, 67), (2.0, 3.0, 64), (2.0, 3.0, 72)], 1e-4);
+
+  // export is monophonic
+  assert_notes(round_trip(&tune).notes(), &[(0.0, 2.0, 67), (2.0, 3.0, 72)], 0.0);
+}
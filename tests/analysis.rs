@@ -0,0 +1,83 @@
+//! Tests of chord and key analysis.
+
+use rmidirs::{
+  analysis::{Resolution, chord::{estimate_chords, ChordQuality}, key::{estimate_key, key_mismatches, key_mismatches_in_tracks, Key, Mode}},
+  model::note_seq::{NoteSeq, Note, TrackSeq}
+};
+
+const C_MAJOR_SCALE : [u32; 8] = [60, 62, 64, 65, 67, 69, 71, 72];
+const D_MAJOR_SCALE : [u32; 8] = [62, 64, 66, 67, 69, 71, 73, 74];
+
+/// quarter notes at 120 qbpm
+fn melody(pitches : &[u32]) -> NoteSeq {
+  let mut note_seq = NoteSeq::default();
+  for (beat, pitch) in pitches.iter().enumerate() {
+    note_seq.add_note(Note::new(*pitch, 80, beat as f32 * 0.5, beat as f32 * 0.5 + 0.5));
+  }
+  note_seq
+}
+
+/// notes sounding together for one bar at 120 qbpm
+fn chord(pitches : &[u32]) -> NoteSeq {
+  let mut note_seq = NoteSeq::default();
+  for pitch in pitches {
+    note_seq.add_note(Note::new(*pitch, 80, 0.0, 2.0));
+  }
+  note_seq
+}
+
+#[test]
+fn bass_picks_between_chords_of_same_tones() {
+  let chords = estimate_chords(&chord(&[48, 64, 67, 69]), Resolution::Bar);
+  assert_eq!(chords.len(), 1);
+  assert_eq!((chords[0].root(), chords[0].quality()), (0, ChordQuality::Major6));
+  assert_eq!(chords[0].name(), "C6");
+
+  let chords = estimate_chords(&chord(&[45, 60, 64, 67]), Resolution::Bar);
+  assert_eq!((chords[0].root(), chords[0].quality()), (9, ChordQuality::Minor7));
+  assert_eq!(chords[0].name(), "Am7");
+}
+
+#[test]
+fn key_is_estimated_from_notes() {
+  let estimate = estimate_key(&melody(&D_MAJOR_SCALE)).unwrap();
+  assert_eq!(estimate.key, Key::new(2, Mode::Major));
+  assert!(estimate_key(&NoteSeq::default()).is_none());
+}
+
+#[test]
+fn declared_key_is_compared_with_notes() {
+  // assumed C major isn't compared
+  let mut note_seq = melody(&D_MAJOR_SCALE);
+  assert!(key_mismatches(&note_seq).is_empty());
+
+  note_seq.set_key_signature(0.0, String::from("c-major"));
+  let mismatches = key_mismatches(&note_seq);
+  assert_eq!(mismatches.len(), 1);
+  assert_eq!(mismatches[0].declared, Key::new(0, Mode::Major));
+  assert_eq!(mismatches[0].estimated.key, Key::new(2, Mode::Major));
+  assert!(!mismatches[0].relative);
+}
+
+#[test]
+fn key_mismatches_merge_conductor_track() {
+  // 60 bpm in conductor, C major then Eb major from beat 8, notes in C major then D major
+  let mut text = String::from(
+    "0, 0, Header, 1, 2, 480\n1, 0, Start_track\n1, 0, Tempo, 1000000\n1, 0, Key_signature, 0, \"major\"\n\
+     1, 3840, Key_signature, -3, \"major\"\n1, 3840, End_track\n2, 0, Start_track\n"
+  );
+  for (beat, pitch) in C_MAJOR_SCALE.iter().chain(&D_MAJOR_SCALE).enumerate() {
+    text.push_str(&format!("2, {}, Note_on_c, 0, {}, 100\n2, {}, Note_off_c, 0, {}, 0\n", beat * 480, pitch, beat * 480 + 480, pitch));
+  }
+  text.push_str("2, 7680, End_track\n0, 0, End_of_file\n");
+  let track_seq = TrackSeq::from(rmidirs::text::from_text(&text).unwrap());
+
+  // note track alone declares nothing
+  assert!(key_mismatches(&track_seq.tracks()[1]).is_empty());
+
+  let mismatches = key_mismatches_in_tracks(&track_seq);
+  assert_eq!(mismatches.len(), 1);
+  assert_eq!(mismatches[0].at(), 8.0);
+  assert_eq!(mismatches[0].declared, Key::new(3, Mode::Major));
+  assert_eq!(mismatches[0].estimated.key, Key::new(2, Mode::Major));
+}
@@ -0,0 +1,61 @@
+//! Tests of parse errors.
+
+use rmidirs::parser::{MidiParser, DiagnosticKind, Severity};
+
+/// format 1 midi of tracks with `events`
+fn midi(tracks : &[&[u8]]) -> Vec<u8> {
+  let mut bytes = b"MThd\x00\x00\x00\x06\x00\x01".to_vec();
+  bytes.extend((tracks.len() as u16).to_be_bytes());
+  bytes.extend(b"\x01\xE0");
+  for events in tracks {
+    bytes.extend(b"MTrk");
+    bytes.extend((events.len() as u32).to_be_bytes());
+    bytes.extend(*events);
+  }
+  bytes
+}
+
+const END_OF_TRACK : &[u8] = b"\x00\xFF\x2F\x00";
+/// note on, then text meta event longer than the track
+const BROKEN : &[u8] = b"\x00\x90\x3C\x50\x60\xFF\x01\x20ab";
+
+#[test]
+fn error_of_any_track_fails_parse() {
+  let bytes = midi(&[END_OF_TRACK, BROKEN]);
+  let err = MidiParser::parse(&bytes).unwrap_err();
+  assert_eq!(err.track(), Some(1));
+  assert_eq!(err.event(), Some(1));
+
+  // lenient parse keeps the events before the failure
+  let (midi, diagnostics) = MidiParser::parse_lenient(&bytes).unwrap();
+  assert_eq!(midi.tracks().len(), 2);
+  let events : Vec<_> = midi.tracks()[1].iter().collect();
+  assert_eq!(events.len(), 2);
+  assert!(events[0].is_note_on_event() && events[1].is_end_of_track());
+
+  let error = diagnostics.iter().find(|diagnostic| diagnostic.kind() == DiagnosticKind::TrackError).unwrap();
+  assert_eq!((error.track(), error.severity()), (Some(1), Severity::Error));
+  assert_eq!(error.error().and_then(|err| err.event()), Some(1));
+  assert!(diagnostics.iter().any(|diagnostic| diagnostic.kind() == DiagnosticKind::MissingEndOfTrack));
+}
+
+#[test]
+fn error_points_at_failing_byte() {
+  // 0xF4 isn't a valid status byte in track
+  let bytes = midi(&[END_OF_TRACK, b"\x00\x90\x3C\x50\x60\xF4\x00\x00"]);
+  let err = MidiParser::parse(&bytes).unwrap_err();
+  assert_eq!(err.offset(), 39);
+  assert_eq!(bytes[err.offset()], 0xF4);
+  assert_eq!((err.track(), err.event()), (Some(1), Some(1)));
+  assert_eq!(err.expected(), Some("channel, meta or sysex status byte"));
+  assert_eq!(err.found(), Some(&[0xF4][..]));
+
+  let (start, context) = err.context().unwrap();
+  assert_eq!(&bytes[start .. start + context.len()], context);
+  let hexdump = err.hexdump().unwrap();
+  assert_eq!(hexdump.lines().last(), Some("00000020: 00 08 00 90 3c 50 60 [f4] 00 00"));
+
+  let message = format!("{:#}", err);
+  assert!(message.contains("at byte 39 (track 1, event 1)"), "{}", message);
+  assert!(message.ends_with(&hexdump));
+}
@@ -0,0 +1,71 @@
+//! Tests of lazy event readers.
+
+use std::io;
+
+use rmidirs::{
+  parser::MidiParser,
+  reader::iterator::{EventReader, StreamEventReader},
+  writer::MidiWriter
+};
+
+const SONG : &str = "\
+0, 0, Header, 1, 2, 96
+1, 0, Start_track
+1, 0, Tempo, 500000
+1, 0, End_track
+2, 0, Start_track
+2, 0, Note_on_c, 0, 60, 100
+2, 96, Note_on_c, 0, 60, 0
+2, 96, Note_on_c, 0, 62, 100
+2, 200, Note_off_c, 0, 62, 0
+2, 200, End_track
+0, 0, End_of_file
+";
+
+type Events = Vec<(usize, u64, String)>;
+
+fn bytes() -> Vec<u8> {
+  MidiWriter::new(&rmidirs::text::from_text(SONG).unwrap()).to_bytes().unwrap()
+}
+
+/// events of fully parsed midi, as (track, tick, event debug)
+fn parsed(bytes : &[u8]) -> Events {
+  MidiParser::parse(bytes).unwrap().tracks().iter().enumerate()
+    .flat_map(|(track, midi_track)| midi_track.iter_absolute().map(move |(tick, event)| (track, tick, format!("{:?}", event))))
+    .collect()
+}
+
+#[test]
+fn readers_yield_events_of_parsed_midi() {
+  let bytes = bytes();
+  let expected = parsed(&bytes);
+  assert_eq!(expected.len(), 7);
+
+  let reader = EventReader::new(&bytes).unwrap();
+  assert_eq!(reader.track_count(), 2);
+  let events : Events = reader.map(|event| event.map(|(track, tick, event)| (track, tick, format!("{:?}", event)))).collect::<Result<_, _>>().unwrap();
+  assert_eq!(events, expected);
+
+  let events : Events = StreamEventReader::new(io::Cursor::new(&bytes)).unwrap()
+    .map(|event| event.map(|(track, tick, event)| (track, tick, format!("{:?}", event))))
+    .collect::<Result<_, _>>()
+    .unwrap();
+  assert_eq!(events, expected);
+}
+
+#[test]
+fn readers_stop_after_first_error() {
+  let mut bytes = bytes();
+  // last track is cut in middle of note off
+  bytes.truncate(bytes.len() - 6);
+
+  let events : Vec<_> = EventReader::new(&bytes).unwrap().collect();
+  assert!(events.last().unwrap().is_err());
+  assert_eq!(events.iter().filter(|event| event.is_err()).count(), 1);
+  // 5 events before the cut, then the error
+  assert_eq!(events.len(), 6);
+
+  let events : Vec<_> = StreamEventReader::new(io::Cursor::new(&bytes)).unwrap().collect();
+  assert!(events.last().unwrap().is_err());
+  assert_eq!(events.iter().filter(|event| event.is_err()).count(), 1);
+}
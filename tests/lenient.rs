@@ -0,0 +1,29 @@
+//! Tests of recoveries made by lenient parser.
+
+use rmidirs::parser::{MidiParser, DiagnosticKind};
+
+/// single track midi of `events`
+fn midi(events : &[u8]) -> Vec<u8> {
+  let mut bytes = b"MThd\x00\x00\x00\x06\x00\x00\x00\x01\x01\xE0MTrk".to_vec();
+  bytes.extend((events.len() as u32).to_be_bytes());
+  bytes.extend(events);
+  bytes
+}
+
+#[test]
+fn stray_data_bytes_are_skipped() {
+  // first event is data byte 0x40, with no running status to apply
+  let bytes = midi(b"\x00\x40\x00\x90\x3C\x50\x60\x80\x3C\x00\x00\xFF\x2F\x00");
+  assert!(MidiParser::parse(&bytes).is_err());
+
+  let (midi, diagnostics) = MidiParser::parse_lenient(&bytes).unwrap();
+  assert_eq!(diagnostics.len(), 1);
+  assert_eq!(diagnostics[0].kind(), DiagnosticKind::StrayDataSkipped);
+  assert_eq!(diagnostics[0].track(), Some(0));
+  assert_eq!(diagnostics[0].offset(), 22 + 1);
+
+  let events : Vec<_> = midi.tracks()[0].iter().collect();
+  assert_eq!(events.len(), 3);
+  assert!(events[0].is_note_on_event());
+  assert!(events[2].is_end_of_track());
+}
@@ -0,0 +1,59 @@
+//! Tests of borrowed midi view.
+
+use rmidirs::{
+  model::core::midi_ref::MidiRef,
+  parser::MidiParser,
+  writer::MidiWriter
+};
+
+const SONG : &str = "\
+0, 0, Header, 1, 2, 96
+1, 0, Start_track
+1, 0, Title_t, \"Lead\"
+1, 0, Tempo, 600000
+1, 0, End_track
+2, 0, Start_track
+2, 0, Control_c, 2, 7, 90
+2, 0, Note_on_c, 2, 60, 100
+2, 96, Note_on_c, 2, 60, 0
+2, 96, System_exclusive, 3, 126, 9, 247
+2, 192, Note_off_c, 2, 64, 20
+2, 192, End_track
+0, 0, End_of_file
+";
+
+#[test]
+fn borrowed_events_match_owned_events() {
+  let bytes = MidiWriter::new(&rmidirs::text::from_text(SONG).unwrap()).to_bytes().unwrap();
+  let midi = MidiParser::parse(&bytes).unwrap();
+  let midi_ref = MidiRef::parse(&bytes).unwrap();
+  assert_eq!(midi_ref.tracks().len(), midi.tracks().len());
+
+  for (track_ref, track) in midi_ref.tracks().iter().zip(midi.tracks()) {
+    // track bytes borrow the buffer
+    let range = bytes.as_ptr_range();
+    assert!(range.contains(&track_ref.bytes().as_ptr()));
+
+    let borrowed : Vec<(u64, String)> = track_ref.events().map(|event| {
+      let event = event.unwrap();
+      (event.tick(), format!("{:?}", event.to_event()))
+    }).collect();
+    let owned : Vec<(u64, String)> = track.iter_absolute().map(|(tick, event)| (tick, format!("{:?}", event))).collect();
+    assert_eq!(borrowed, owned);
+  }
+
+  let events : Vec<_> = midi_ref.track(1).unwrap().events().map(Result::unwrap).collect();
+  assert_eq!(events[0].controller(), Some((7, 90)));
+  assert_eq!((events[1].channel(), events[1].note(), events[1].velocity()), (Some(2), Some(60), Some(100)));
+  assert!(events[1].is_note_on_event());
+  // note on with velocity 0 is note off
+  assert!(events[2].is_note_off_event() && !events[2].is_note_on_event());
+  assert_eq!(events[3].sysex(), Some(&[126, 9, 247][..]));
+  assert!(events[4].is_note_off_event());
+
+  let events : Vec<_> = midi_ref.track(0).unwrap().events().map(Result::unwrap).collect();
+  assert_eq!(events[0].text(), Some("Lead"));
+  assert_eq!(events[1].tempo().map(|tempo| tempo.micro_secs()), Some(600000.0));
+
+  assert_eq!(rmidirs::text::to_text(&midi_ref.to_midi().unwrap()), rmidirs::text::to_text(&midi));
+}
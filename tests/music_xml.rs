@@ -0,0 +1,34 @@
+//! Tests of MusicXML writer.
+
+use rmidirs::{
+  model::{note_seq::TrackSeq, timing::MeasureMap},
+  writer::MusicXmlWriter
+};
+
+/// `<measure>` elements of `xml`, first is measure 1
+fn measures(xml : &str) -> Vec<&str> {
+  xml.split("<measure ").skip(1).collect()
+}
+
+#[test]
+fn key_change_is_placed_in_its_bar() {
+  // 60 bpm, D major from bar 5 in conductor track, quarter notes of 10 bars in second track
+  let mut text = String::from(
+    "0, 0, Header, 1, 2, 480\n1, 0, Start_track\n1, 0, Tempo, 1000000\n1, 7680, Key_signature, 2, \"major\"\n1, 7680, End_track\n2, 0, Start_track\n"
+  );
+  for beat in 0..40 {
+    text.push_str(&format!("2, {}, Note_on_c, 0, 62, 100\n2, {}, Note_off_c, 0, 62, 0\n", beat * 480, beat * 480 + 480));
+  }
+  text.push_str("2, 19200, End_track\n0, 0, End_of_file\n");
+  let midi = rmidirs::text::from_text(&text).unwrap();
+
+  let measure_map = MeasureMap::from(&midi);
+  let track_seq = TrackSeq::from(midi);
+  let xml = MusicXmlWriter::new(&track_seq, &measure_map).to_xml();
+  let measures = measures(&xml);
+
+  assert_eq!(measures.len(), 10);
+  assert!(measures[0].contains("<fifths>0</fifths>"));
+  assert!(measures[4].contains("<fifths>2</fifths>"), "{}", measures[4]);
+  assert!(measures.iter().enumerate().all(|(index, measure)| index == 0 || index == 4 || !measure.contains("<key>")));
+}
@@ -0,0 +1,64 @@
+//! Tests of batch and track parsing, which run in parallel with `rayon` feature.
+
+use rmidirs::parser::{MidiParser, DiagnosticKind};
+
+/// format 1 midi of tracks with `events`
+fn midi(tracks : &[&[u8]]) -> Vec<u8> {
+  let mut bytes = b"MThd\x00\x00\x00\x06\x00\x01".to_vec();
+  bytes.extend((tracks.len() as u16).to_be_bytes());
+  bytes.extend(b"\x01\xE0");
+  for events in tracks {
+    bytes.extend(b"MTrk");
+    bytes.extend((events.len() as u32).to_be_bytes());
+    bytes.extend(*events);
+  }
+  bytes
+}
+
+/// track of single note with `pitch`
+fn note(pitch : u8) -> Vec<u8> {
+  vec![0x00, 0x90, pitch, 0x50, 0x60, 0x80, pitch, 0x00, 0x00, 0xFF, 0x2F, 0x00]
+}
+
+#[test]
+fn parse_many_keeps_order_of_buffers() {
+  let bufs : Vec<Vec<u8>> = (0..32u8)
+    .map(|n| match n {
+      7 => b"MThd".to_vec(),
+      n => midi(&vec![&note(40 + n)[..]; n as usize % 4 + 1]),
+    })
+    .collect();
+
+  let results = MidiParser::parse_many(&bufs);
+  assert_eq!(results.len(), 32);
+  for (n, result) in results.iter().enumerate() {
+    match n {
+      7 => assert!(result.is_err()),
+      n => {
+        let midi = result.as_ref().unwrap();
+        assert_eq!(midi.tracks().len(), n % 4 + 1);
+        let first = midi.tracks()[0].iter().next().unwrap();
+        assert_eq!(first.get_note_number().map(|note| *note as usize), Some(40 + n));
+      },
+    }
+  }
+}
+
+#[test]
+fn lenient_diagnostics_are_in_track_order() {
+  let stray = [&[0x00, 0x40][..], &note(60)].concat();
+  let unterminated = &note(62)[..8];
+  let complete = note(64);
+  let tracks : Vec<&[u8]> = vec![&stray, unterminated, &complete, &stray, unterminated];
+  let (midi, diagnostics) = MidiParser::parse_lenient(&midi(&tracks)).unwrap();
+  assert_eq!(midi.tracks().len(), 5);
+
+  let found : Vec<(Option<usize>, DiagnosticKind)> = diagnostics.iter().map(|diagnostic| (diagnostic.track(), diagnostic.kind())).collect();
+  assert_eq!(found, vec![
+    (Some(0), DiagnosticKind::StrayDataSkipped),
+    (Some(1), DiagnosticKind::MissingEndOfTrack),
+    (Some(3), DiagnosticKind::StrayDataSkipped),
+    (Some(4), DiagnosticKind::MissingEndOfTrack),
+  ]);
+  assert!(diagnostics.windows(2).all(|pair| pair[0].offset() < pair[1].offset()));
+}
@@ -0,0 +1,187 @@
+//! Tests of playback, on virtual clock.
+
+use std::time::Duration;
+
+use rmidirs::playback::{Player, PlayState, Schedule, RecordingSink, VirtualClock, Clock};
+
+/// two beats of piano on channel 0 and drums on channel 9, in 120 bpm, so every beat is 0.5 seconds
+const SONG : &str = "\
+0, 0, Header, 1, 2, 480
+1, 0, Start_track
+1, 0, Tempo, 500000
+1, 0, Program_c, 0, 5
+1, 0, Control_c, 0, 7, 100
+1, 0, Note_on_c, 0, 60, 100
+1, 480, Note_off_c, 0, 60, 0
+1, 960, Control_c, 0, 7, 80
+1, 960, Note_on_c, 0, 62, 100
+1, 1440, Note_off_c, 0, 62, 0
+1, 1920, End_track
+2, 0, Start_track
+2, 0, Note_on_c, 9, 36, 100
+2, 240, Note_on_c, 9, 36, 0
+2, 960, Note_on_c, 9, 38, 100
+2, 1200, Note_on_c, 9, 38, 0
+2, 1920, End_track
+0, 0, End_of_file
+";
+
+fn player() -> Player<RecordingSink, VirtualClock> {
+  let midi = rmidirs::text::from_text(SONG).unwrap();
+  Player::new(Schedule::from(&midi), RecordingSink::new(), VirtualClock::new())
+}
+
+/// messages sent so far, as (milliseconds, bytes)
+fn sent(player : &Player<RecordingSink, VirtualClock>) -> Vec<(u128, Vec<u8>)> {
+  player.sink().bytes().into_iter().map(|(at, bytes)| (at.as_millis(), bytes)).collect()
+}
+
+#[test]
+fn schedule_merges_tracks() {
+  let schedule = player().schedule().clone();
+  let ticks : Vec<(u64, usize)> = schedule.events().iter().map(|event| (event.tick(), event.track())).collect();
+  assert_eq!(ticks, vec![(0, 0), (0, 0), (0, 0), (0, 0), (0, 1), (240, 1), (480, 0), (960, 0), (960, 0), (960, 1), (1200, 1), (1440, 0)]);
+  assert_eq!(schedule.duration(), 2.0);
+  assert_eq!(schedule.index_at(1.0), 7);
+}
+
+#[test]
+fn run_sends_messages_on_time() {
+  let mut player = player();
+  player.run();
+  assert_eq!(sent(&player), vec![
+    (0, vec![0xC0, 5]),
+    (0, vec![0xB0, 7, 100]),
+    (0, vec![0x90, 60, 100]),
+    (0, vec![0x99, 36, 100]),
+    (250, vec![0x99, 36, 0]),
+    (500, vec![0x80, 60, 0]),
+    (1000, vec![0xB0, 7, 80]),
+    (1000, vec![0x90, 62, 100]),
+    (1000, vec![0x99, 38, 100]),
+    (1250, vec![0x99, 38, 0]),
+    (1500, vec![0x80, 62, 0]),
+  ]);
+  assert_eq!(player.clock().now(), Duration::from_secs(2));
+  assert_eq!(player.state(), PlayState::Stopped);
+}
+
+#[test]
+fn speed_scales_tempo() {
+  let mut player = player();
+  player.set_speed(2.0);
+  player.run();
+  let times : Vec<u128> = sent(&player).into_iter().map(|(at, _)| at).collect();
+  assert_eq!(times, vec![0, 0, 0, 0, 125, 250, 500, 500, 500, 625, 750]);
+}
+
+#[test]
+fn seek_chases_controllers_and_programs() {
+  let mut player = player();
+  player.seek(1.2);
+  assert_eq!(sent(&player), vec![(0, vec![0xC0, 5]), (0, vec![0xB0, 7, 80])]);
+
+  // notes started before seek position aren't released again
+  player.run();
+  assert_eq!(sent(&player).len(), 2);
+}
+
+#[test]
+fn pause_releases_and_stop_sends_all_notes_off() {
+  let mut player = player();
+  player.play();
+  player.update();
+  player.clock_mut().advance(Duration::from_millis(100));
+  player.pause();
+  assert_eq!(player.position(), 0.1);
+  assert_eq!(&sent(&player)[4..], &[(100, vec![0x80, 60, 0]), (100, vec![0x89, 36, 0])]);
+
+  // resumes from paused position
+  player.sink_mut().clear();
+  player.clock_mut().advance(Duration::from_secs(10));
+  player.play();
+  assert_eq!(player.update().map(|at| at.as_millis()), Some(10_250));
+
+  player.stop();
+  let sent = sent(&player);
+  assert_eq!(sent.len(), 16);
+  assert!(sent.iter().enumerate().all(|(channel, (_, bytes))| *bytes == vec![0xB0 | channel as u8, 123, 0]));
+  assert_eq!(player.state(), PlayState::Stopped);
+  assert_eq!(player.position(), 0.0);
+}
+
+#[test]
+fn loop_repeats_range() {
+  let mut player = player();
+  player.set_loop(Some((0.0, 1.0)));
+  player.play();
+  let until = Duration::from_millis(2500);
+  while player.clock().now() < until {
+    let at = player.update().unwrap();
+    player.clock_mut().sleep_until(at.min(until));
+  }
+
+  let piano : Vec<(u128, Vec<u8>)> = sent(&player).into_iter().filter(|(_, bytes)| bytes[0] & 0x0F == 0).collect();
+  assert_eq!(piano, vec![
+    (0, vec![0xC0, 5]),
+    (0, vec![0xB0, 7, 100]),
+    (0, vec![0x90, 60, 100]),
+    (500, vec![0x80, 60, 0]),
+    (1000, vec![0xC0, 5]),
+    (1000, vec![0xB0, 7, 100]),
+    (1000, vec![0x90, 60, 100]),
+    (1500, vec![0x80, 60, 0]),
+    (2000, vec![0xC0, 5]),
+    (2000, vec![0xB0, 7, 100]),
+    (2000, vec![0x90, 60, 100]),
+  ]);
+}
+
+#[test]
+fn mute_and_solo_tracks() {
+  let mut player = player();
+  player.play();
+  player.update();
+  // muting releases sounding notes of track
+  player.mute(1, true);
+  assert_eq!(sent(&player).last(), Some(&(0, vec![0x89, 36, 0])));
+  let muted_at = sent(&player).len();
+  player.run();
+  assert!(sent(&player)[muted_at..].iter().all(|(_, bytes)| bytes[0] & 0x0F != 9));
+
+  let mut player = self::player();
+  player.solo(1, true);
+  player.run();
+  let piano : Vec<Vec<u8>> = sent(&player).into_iter().map(|(_, bytes)| bytes).filter(|bytes| bytes[0] & 0x0F == 0).collect();
+  // controllers and programs of silent tracks are still sent
+  assert_eq!(piano, vec![vec![0xC0, 5], vec![0xB0, 7, 100], vec![0xB0, 7, 80]]);
+}
+
+#[test]
+fn seek_chases_data_entry_of_every_parameter() {
+  // bend range, fine tune, then null RPN
+  let midi = rmidirs::text::from_text("\
+0, 0, Header, 0, 1, 480
+1, 0, Start_track
+1, 0, Control_c, 0, 101, 0
+1, 0, Control_c, 0, 100, 0
+1, 0, Control_c, 0, 6, 12
+1, 0, Control_c, 0, 100, 1
+1, 0, Control_c, 0, 6, 64
+1, 0, Control_c, 0, 96, 0
+1, 0, Control_c, 0, 101, 127
+1, 0, Control_c, 0, 100, 127
+1, 960, Note_on_c, 0, 60, 100
+1, 1440, Note_off_c, 0, 60, 0
+1, 1920, End_track
+0, 0, End_of_file
+").unwrap();
+  let mut player = Player::new(Schedule::from(&midi), RecordingSink::new(), VirtualClock::new());
+  player.seek(1.2);
+  let sent : Vec<Vec<u8>> = sent(&player).into_iter().map(|(_, bytes)| bytes).collect();
+  assert_eq!(sent, vec![
+    vec![0xB0, 101, 0], vec![0xB0, 100, 0], vec![0xB0, 6, 12],
+    vec![0xB0, 100, 1], vec![0xB0, 6, 64],
+    vec![0xB0, 101, 127], vec![0xB0, 100, 127],
+  ]);
+}
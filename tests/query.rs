@@ -0,0 +1,68 @@
+//! Tests of event queries and filtering.
+
+use rmidirs::model::timing::TempoMap;
+
+const SONG : &str = "\
+0, 0, Header, 1, 2, 96
+1, 0, Start_track
+1, 0, Tempo, 500000
+1, 0, Note_on_c, 0, 48, 100
+1, 384, Note_off_c, 0, 48, 0
+1, 384, End_track
+2, 0, Start_track
+2, 0, Control_c, 1, 64, 127
+2, 0, Note_on_c, 1, 60, 100
+2, 96, Note_off_c, 1, 60, 0
+2, 96, Control_c, 1, 64, 0
+2, 192, Note_on_c, 1, 72, 100
+2, 288, Note_off_c, 1, 72, 0
+2, 288, End_track
+0, 0, End_of_file
+";
+
+/// (track, tick, note number) of events
+fn notes<'a>(events : impl Iterator<Item = rmidirs::model::core::query::TimedEvent<'a>>) -> Vec<(usize, u64, u32)> {
+  events.map(|event| (event.track(), event.tick(), event.get_note_number().map_or(0, |note| *note))).collect()
+}
+
+#[test]
+fn queries_combine_filters() {
+  let midi = rmidirs::text::from_text(SONG).unwrap();
+  assert_eq!(notes(midi.events().notes()), vec![(0, 0, 48), (0, 384, 48), (1, 0, 60), (1, 96, 60), (1, 192, 72), (1, 288, 72)]);
+  assert_eq!(notes(midi.events().channel(1).note_ons().pitches(61..)), vec![(1, 192, 72)]);
+  assert_eq!(midi.events().controllers(64).count(), 2);
+  assert_eq!(midi.events().meta(0x51).count(), 1);
+
+  // 120 bpm at 96 ticks per quarter, so second quarter is 0.5 to 1.0 seconds
+  let tempo_map = TempoMap::from(&midi);
+  assert_eq!(notes(midi.events().notes().between_seconds(&tempo_map, 0.5, 1.0)), vec![(1, 96, 60)]);
+}
+
+#[test]
+fn query_collects_to_track_with_recomputed_delta_times() {
+  let midi = rmidirs::text::from_text(SONG).unwrap();
+  let track = midi.events().channel(1).notes().between(90, 300).to_track();
+  let mut merged = rmidirs::text::from_text("0, 0, Header, 0, 0, 96\n0, 0, End_of_file\n").unwrap();
+  merged.add_track(track);
+  assert_eq!(rmidirs::text::to_text(&merged), "\
+0, 0, Header, 0, 1, 96
+1, 0, Start_track
+1, 96, Note_off_c, 1, 60, 0
+1, 192, Note_on_c, 1, 72, 100
+1, 288, Note_off_c, 1, 72, 0
+1, 288, End_track
+0, 0, End_of_file
+");
+}
+
+#[test]
+fn removed_events_keep_ticks_of_others() {
+  let mut midi = rmidirs::text::from_text(SONG).unwrap();
+  assert_eq!(midi.remove_where(|event| event.get_controller().is_some()), 2);
+  assert_eq!(notes(midi.events().track(1)), vec![(1, 0, 60), (1, 96, 60), (1, 192, 72), (1, 288, 72), (1, 288, 0)]);
+
+  // End Of Track is always kept
+  midi.retain(|_| false);
+  assert!(midi.tracks().iter().all(|track| track.len() == 1));
+  assert_eq!(midi.tracks()[0].end_tick(), 384);
+}
@@ -0,0 +1,181 @@
+//! Tests of offline rendering.
+
+use rmidirs::{
+  model::core::midi::Midi,
+  render::{Renderer, SoundFont}
+};
+
+const SAMPLE_RATE : u32 = 44100;
+
+/// midi of one track in 120 bpm, every beat is 0.5 seconds, `events` are midicsv lines of track 1
+fn song(events : &str) -> Midi {
+  let text = format!(
+    "0, 0, Header, 0, 1, 480\n1, 0, Start_track\n1, 0, Tempo, 500000\n{}\n1, 1920, End_track\n0, 0, End_of_file\n",
+    events.trim()
+  );
+  rmidirs::text::from_text(&text).unwrap()
+}
+
+/// frequency in `start..end` seconds of left channel, from rising zero crossings
+fn frequency(samples : &[f32], start : f32, end : f32) -> f32 {
+  let frames = (start * SAMPLE_RATE as f32) as usize .. (end * SAMPLE_RATE as f32) as usize;
+  let left : Vec<f32> = samples.chunks_exact(2).map(|frame| frame[0]).collect();
+  let crossings = left[frames].windows(2).filter(|pair| pair[0] <= 0.0 && pair[1] > 0.0).count();
+  crossings as f32 / (end - start)
+}
+
+/// loudest sample of channel `side` in `start..end` seconds
+fn peak(samples : &[f32], side : usize, start : f32, end : f32) -> f32 {
+  samples.chunks_exact(2)
+    .skip((start * SAMPLE_RATE as f32) as usize)
+    .take(((end - start) * SAMPLE_RATE as f32) as usize)
+    .fold(0.0, |peak, frame| peak.max(frame[side].abs()))
+}
+
+/// pipe family plays sine
+const SINE : &str = "1, 0, Program_c, 0, 72";
+
+#[test]
+fn render_is_deterministic_wav() {
+  let midi = song("
+    1, 0, Note_on_c, 0, 60, 100
+    1, 0, Note_on_c, 9, 36, 100
+    1, 480, Note_off_c, 0, 60, 0
+  ");
+  let renderer = Renderer::new().with_tail(1.0);
+  let wav = renderer.render(&midi).to_wav();
+  assert_eq!(wav, renderer.render(&midi).to_wav());
+
+  assert_eq!(&wav[..4], b"RIFF");
+  assert_eq!(&wav[8..16], b"WAVEfmt ");
+  assert_eq!(u16::from_le_bytes([wav[22], wav[23]]), 2);
+  assert_eq!(u32::from_le_bytes([wav[24], wav[25], wav[26], wav[27]]), SAMPLE_RATE);
+  assert_eq!(&wav[36..40], b"data");
+  // two seconds of midi, notes fade out before tail ends
+  let frames = u32::from_le_bytes([wav[40], wav[41], wav[42], wav[43]]) as usize / 4;
+  assert!(frames >= 2 * SAMPLE_RATE as usize && frames < 3 * SAMPLE_RATE as usize, "{} frames", frames);
+}
+
+#[test]
+fn notes_sound_at_their_pitch() {
+  let midi = song(&format!("{}
+    1, 480, Note_on_c, 0, 69, 100
+    1, 960, Note_off_c, 0, 69, 0
+    1, 960, Pitch_bend_c, 0, 16383
+    1, 960, Note_on_c, 0, 69, 100
+    1, 1440, Note_off_c, 0, 69, 0
+  ", SINE));
+  let audio = Renderer::new().render(&midi);
+  let samples = audio.samples();
+
+  assert_eq!(peak(samples, 0, 0.0, 0.5), 0.0);
+  assert!((frequency(samples, 0.6, 0.9) - 440.0).abs() < 10.0);
+  // bend up by whole range of 2 semitones
+  assert!((frequency(samples, 1.1, 1.4) - 493.9).abs() < 10.0);
+  assert!(audio.peak() <= 1.0);
+}
+
+#[test]
+fn pan_and_volume_controllers() {
+  let midi = song(&format!("{}
+    1, 0, Control_c, 0, 10, 0
+    1, 0, Note_on_c, 0, 69, 100
+    1, 480, Control_c, 0, 7, 0
+    1, 960, Note_off_c, 0, 69, 0
+  ", SINE));
+  let samples = Renderer::new().render(&midi).samples().to_vec();
+
+  // hard left
+  assert!(peak(&samples, 0, 0.1, 0.4) > 0.1);
+  assert!(peak(&samples, 1, 0.1, 0.4) < 0.001);
+  // volume 0
+  assert_eq!(peak(&samples, 0, 0.6, 0.9), 0.0);
+}
+
+#[test]
+fn sustain_holds_released_notes() {
+  let midi = song(&format!("{}
+    1, 0, Control_c, 0, 64, 127
+    1, 0, Note_on_c, 0, 69, 100
+    1, 240, Note_off_c, 0, 69, 0
+    1, 960, Control_c, 0, 64, 0
+  ", SINE));
+  let samples = Renderer::new().render(&midi).samples().to_vec();
+
+  assert!(peak(&samples, 0, 0.8, 0.95) > 0.1);
+  assert!(peak(&samples, 0, 1.5, 2.0) < 0.001);
+}
+
+#[test]
+fn drums_ring_out_after_note_off() {
+  let midi = song("
+    1, 0, Note_on_c, 9, 38, 100
+    1, 10, Note_off_c, 9, 38, 0
+  ");
+  let samples = Renderer::new().render(&midi).samples().to_vec();
+  assert!(peak(&samples, 0, 0.05, 0.1) > 0.01);
+  assert!(peak(&samples, 0, 1.5, 2.0) < 0.001);
+}
+
+fn chunk(id : &[u8], data : &[u8]) -> Vec<u8> {
+  let mut chunk = id.to_vec();
+  chunk.extend((data.len() as u32).to_le_bytes());
+  chunk.extend(data);
+  if data.len() % 2 == 1 { chunk.push(0); }
+  chunk
+}
+
+fn list(kind : &[u8], chunks : &[Vec<u8>]) -> Vec<u8> {
+  chunk(b"LIST", &[kind, &chunks.concat()].concat())
+}
+
+fn record(name : &str, len : usize, tail : &[u8]) -> Vec<u8> {
+  let mut record = name.as_bytes().to_vec();
+  record.resize(len - tail.len(), 0);
+  record.extend(tail);
+  record
+}
+
+/// font with preset 0 of bank 0, looped sine of 100 samples per period, at root key 69
+fn soundfont() -> SoundFont {
+  let samples : Vec<u8> = (0..1046)
+    .map(|i| if i < 1000 { ((i as f32 / 100.0 * std::f32::consts::TAU).sin() * 16000.0) as i16 } else { 0 })
+    .flat_map(|sample : i16| sample.to_le_bytes())
+    .collect();
+
+  let u16s = |values : &[u16]| -> Vec<u8> { values.iter().flat_map(|value| value.to_le_bytes()).collect() };
+  let u32s = |values : &[u32]| -> Vec<u8> { values.iter().flat_map(|value| value.to_le_bytes()).collect() };
+  let sample_header = [u32s(&[0, 1000, 100, 900, 44100]), vec![69, 0], u16s(&[0, 1])].concat();
+
+  let pdta = list(b"pdta", &[
+    chunk(b"phdr", &[record("Sine", 38, &u16s(&[0, 0, 0, 0, 0, 0, 0, 0, 0])), record("EOP", 38, &u16s(&[0, 0, 1, 0, 0, 0, 0, 0, 0]))].concat()),
+    chunk(b"pbag", &u16s(&[0, 0, 1, 0])),
+    chunk(b"pmod", &[0; 10]),
+    chunk(b"pgen", &u16s(&[41, 0, 0, 0])),
+    chunk(b"inst", &[record("Sine", 22, &u16s(&[0])), record("EOI", 22, &u16s(&[1]))].concat()),
+    chunk(b"ibag", &u16s(&[0, 0, 2, 0])),
+    chunk(b"imod", &[0; 10]),
+    chunk(b"igen", &u16s(&[54, 1, 53, 0, 0, 0])),
+    chunk(b"shdr", &[record("Sine", 46, &sample_header), record("EOS", 46, &[])].concat()),
+  ]);
+  let body = [b"sfbk".to_vec(), list(b"INFO", &[chunk(b"ifil", &u16s(&[2, 1]))]), list(b"sdta", &[chunk(b"smpl", &samples)]), pdta].concat();
+  SoundFont::parse(&chunk(b"RIFF", &body)).unwrap()
+}
+
+#[test]
+fn soundfont_samples_are_played() {
+  let soundfont = soundfont();
+  assert_eq!(soundfont.presets(), vec![(0, 0, "Sine")]);
+
+  // preset 0 is in font, loop of 441 Hz sine is transposed an octave up
+  let midi = song("
+    1, 0, Note_on_c, 0, 81, 100
+    1, 960, Note_off_c, 0, 81, 0
+  ");
+  let samples = Renderer::new().with_soundfont(soundfont).render(&midi).samples().to_vec();
+  assert!(peak(&samples, 0, 0.1, 0.9) > 0.03);
+  assert!((frequency(&samples, 0.1, 0.9) - 882.0).abs() < 10.0);
+  assert!(peak(&samples, 0, 1.1, 2.0) < 0.001);
+
+  assert!(SoundFont::parse(b"RIFF\x04\x00\x00\x00WAVE").is_err());
+}
@@ -0,0 +1,37 @@
+//! Tests of serde support, run with `--features serde`.
+#![cfg(feature = "serde")]
+
+use rmidirs::{
+  model::{core::midi::Midi, note_seq::TrackSeq},
+  parser::MidiParser,
+  writer::MidiWriter
+};
+
+const TEST_MID : &[u8] = include_bytes!("../midis/test.mid");
+
+#[test]
+fn midi_round_trips_through_json() {
+  let mut bytes = TEST_MID[..14].to_vec();
+  bytes.extend(b"XFIH\x00\x00\x00\x03abc");
+  bytes.extend(&TEST_MID[14..]);
+  let midi = MidiParser::parse(&bytes).unwrap();
+
+  let json = serde_json::to_string(&midi).unwrap();
+  let decoded : Midi = serde_json::from_str(&json).unwrap();
+  assert_eq!(decoded.unknown_chunks().len(), 1);
+  assert_eq!(MidiWriter::new(&decoded).to_bytes().unwrap(), MidiWriter::new(&midi).to_bytes().unwrap());
+}
+
+#[test]
+fn track_seq_round_trips_through_json() {
+  let track_seq = TrackSeq::from(MidiParser::parse(TEST_MID).unwrap());
+  let json = serde_json::to_string(&track_seq).unwrap();
+  let decoded : TrackSeq = serde_json::from_str(&json).unwrap();
+  assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+  assert_eq!(decoded.tracks()[0].notes().len(), track_seq.tracks()[0].notes().len());
+
+  // json written before start_key_declared was added still reads
+  let old = json.replace(",\"start_key_declared\":true", "");
+  assert_ne!(old, json);
+  assert!(serde_json::from_str::<TrackSeq>(&old).is_ok());
+}
@@ -0,0 +1,136 @@
+//! Tests of stream readers, over in-memory streams.
+
+use std::io::{self, Read};
+
+use rmidirs::{
+  parser::{MidiParser, MidiParseErrorKind, MidiStreamParser},
+  reader::MidiFileReader
+};
+
+const TEST_MID : &[u8] = include_bytes!("../midis/test.mid");
+
+/// reader which gives at most `piece` bytes per read, and is interrupted before every read
+struct Trickle<'a> {
+  bytes : &'a [u8],
+  piece : usize,
+  interrupted : bool,
+}
+
+impl<'a> Trickle<'a> {
+  fn new(bytes : &'a [u8], piece : usize) -> Self {
+    Self { bytes, piece, interrupted : false }
+  }
+}
+
+impl<'a> Read for Trickle<'a> {
+  fn read(&mut self, buf : &mut [u8]) -> io::Result<usize> {
+    self.interrupted = !self.interrupted;
+    if self.interrupted { return Err(io::ErrorKind::Interrupted.into()); }
+    let n = self.piece.min(buf.len()).min(self.bytes.len());
+    buf[..n].copy_from_slice(&self.bytes[..n]);
+    self.bytes = &self.bytes[n..];
+    Ok(n)
+  }
+}
+
+/// test.mid with unknown chunk before its track
+fn with_unknown_chunk() -> Vec<u8> {
+  let mut bytes = TEST_MID[..14].to_vec();
+  bytes.extend(b"XFIH\x00\x00\x00\x03abc");
+  bytes.extend(&TEST_MID[14..]);
+  bytes
+}
+
+#[test]
+fn from_reader_matches_buffer_parser() {
+  let expected = rmidirs::text::to_text(&MidiParser::parse(TEST_MID).unwrap());
+  for piece in [1, 3, 7, 8, 100, TEST_MID.len()] {
+    let midi = MidiFileReader::from_reader(Trickle::new(TEST_MID, piece)).unwrap();
+    assert_eq!(rmidirs::text::to_text(&midi), expected, "piece of {} bytes", piece);
+  }
+}
+
+#[test]
+fn unknown_chunks_are_kept() {
+  let bytes = with_unknown_chunk();
+  let midi = MidiFileReader::from_reader(io::Cursor::new(&bytes)).unwrap();
+  assert_eq!(midi.tracks().len(), 1);
+  assert_eq!(midi.unknown_chunks().len(), 1);
+  assert_eq!(&midi.unknown_chunks()[0].id(), b"XFIH");
+  assert_eq!(midi.unknown_chunks()[0].data(), b"abc");
+}
+
+#[test]
+fn chunks_are_parsed_as_they_arrive() {
+  let mut parser = MidiStreamParser::new();
+  parser.push(&TEST_MID[..10]).unwrap();
+  assert!(parser.header().is_none());
+  parser.push(&TEST_MID[10..20]).unwrap();
+  assert_eq!(parser.header().map(|header| *header.ntrk()), Some(1));
+  assert_eq!(parser.track_count(), 0);
+  parser.push(&TEST_MID[20..]).unwrap();
+  assert_eq!(parser.track_count(), 1);
+  assert_eq!(parser.position(), TEST_MID.len());
+}
+
+#[test]
+fn truncated_stream_fails() {
+  let err = MidiFileReader::from_reader(&TEST_MID[..TEST_MID.len() - 1]).unwrap_err();
+  assert!(matches!(err.kind(), MidiParseErrorKind::EndOfBuffer));
+  assert_eq!(err.offset(), 14);
+
+  let err = MidiFileReader::from_reader(&TEST_MID[..5]).unwrap_err();
+  assert!(matches!(err.kind(), MidiParseErrorKind::EndOfBuffer));
+}
+
+#[test]
+fn junk_fails_before_its_length_arrives() {
+  let mut parser = MidiStreamParser::new();
+  let err = parser.push(b"RIFF\xFF\xFF\xFF\xFF").unwrap_err();
+  assert!(matches!(err.kind(), MidiParseErrorKind::InvalidMidiHeader));
+
+  let mut parser = MidiStreamParser::new();
+  parser.push(&TEST_MID[..14]).unwrap();
+  let err = parser.push(b"\x00\x01\x02\x03\xFF\xFF\xFF\xFF").unwrap_err();
+  assert!(matches!(err.kind(), MidiParseErrorKind::InvalidMidiTrackHeader));
+  assert_eq!(err.offset(), 14);
+}
+
+#[test]
+fn errors_point_at_stream_offset() {
+  // status byte of first event, after its delta time, is made undefined 0xF4
+  let mut bytes = with_unknown_chunk();
+  let first_event = 14 + 11 + 8;
+  bytes[first_event + 1] = 0xF4;
+
+  let expected = MidiParser::parse(&bytes).unwrap_err();
+  let err = MidiFileReader::from_reader(Trickle::new(&bytes, 5)).unwrap_err();
+  assert_eq!(err.offset(), expected.offset());
+  assert_eq!(err.track(), Some(0));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(flavor = "current_thread")]
+async fn from_async_reader_matches_buffer_parser() {
+  use tokio::io::AsyncReadExt;
+
+  let expected = rmidirs::text::to_text(&MidiParser::parse(TEST_MID).unwrap());
+  let (first, rest) = TEST_MID.split_at(17);
+  let midi = MidiFileReader::from_async_reader(AsyncReadExt::chain(first, rest)).await.unwrap();
+  assert_eq!(rmidirs::text::to_text(&midi), expected);
+
+  let err = MidiFileReader::from_async_reader(&TEST_MID[..20]).await.unwrap_err();
+  assert!(matches!(err.kind(), MidiParseErrorKind::EndOfBuffer));
+}
+
+#[test]
+fn huge_chunk_length_fails_without_allocating_it() {
+  use rmidirs::reader::iterator::StreamEventReader;
+
+  let mut bytes = TEST_MID[..14].to_vec();
+  bytes.extend(b"MTrk\xFF\xFF\xFF\xF0\x00\xFF\x2F\x00");
+  let mut reader = StreamEventReader::new(io::Cursor::new(&bytes)).unwrap();
+  let err = reader.next().unwrap().unwrap_err();
+  assert!(matches!(err.kind(), MidiParseErrorKind::Io));
+  assert!(reader.next().is_none());
+}
@@ -0,0 +1,96 @@
+//! Tests of decoding live MIDI wire bytes.
+
+use std::time::Duration;
+
+use rmidirs::{
+  parser::{StreamDecoder, LiveMessage},
+  model::core::midi_event::system_message::SystemRealTime
+};
+
+const AT : Duration = Duration::ZERO;
+
+/// messages as text, channel and sysex messages as their bytes
+fn describe(messages : Vec<LiveMessage>) -> Vec<String> {
+  messages.into_iter().map(|message| match message {
+    LiveMessage::Channel(message) => format!("{:02X?}", Vec::<u8>::from(message)),
+    LiveMessage::SysEx(sysex) => format!("{:02X} {:02X?}", sysex.event_byte(), sysex.data()),
+    LiveMessage::SystemCommon(message) => format!("{:?}", message),
+    LiveMessage::RealTime(message) => format!("{:?}", message),
+    LiveMessage::ActiveSensingTimeout => String::from("timeout"),
+  }).collect()
+}
+
+#[test]
+fn running_status_applies_to_data_bytes() {
+  let mut decoder = StreamDecoder::new();
+  let messages = describe(decoder.push(&[0x90, 0x3C, 0x64, 0x40, 0x64, 0x3C, 0x00, 0xC1, 0x05, 0x06], AT));
+  assert_eq!(messages, vec![
+    "[90, 3C, 64]", "[90, 40, 64]", "[90, 3C, 00]", "[C1, 05]", "[C1, 06]",
+  ]);
+  assert_eq!(decoder.running_status(), Some(0xC1));
+
+  // data bytes without status are dropped, running status is cleared by system common
+  let mut decoder = StreamDecoder::new();
+  assert!(decoder.push(&[0x3C, 0x64], AT).is_empty());
+  decoder.push(&[0xB0, 0x07, 0x64, 0xF6], AT);
+  assert_eq!(decoder.running_status(), None);
+  assert!(decoder.push(&[0x07, 0x10], AT).is_empty());
+}
+
+#[test]
+fn real_time_bytes_pass_through_messages() {
+  let mut decoder = StreamDecoder::new();
+  let messages = describe(decoder.push(&[0x90, 0xF8, 0x3C, 0xFA, 0x64], AT));
+  assert_eq!(messages, vec!["TimingClock", "Start", "[90, 3C, 64]"]);
+
+  let messages = describe(decoder.push(&[0xF0, 0x7E, 0xF8, 0x09, 0xF7], AT));
+  assert_eq!(messages, vec!["TimingClock", "F0 [7E, 09, F7]"]);
+  // sysex cancels running status, real time doesn't
+  assert_eq!(decoder.running_status(), None);
+}
+
+#[test]
+fn sysex_is_collected_across_pushes() {
+  let mut decoder = StreamDecoder::new();
+  assert!(decoder.push(&[0xF0, 0x43, 0x10], AT).is_empty());
+  assert!(decoder.in_sysex());
+  assert!(decoder.push(&[0x4C, 0x00], AT).is_empty());
+  assert_eq!(describe(decoder.push(&[0x7F, 0xF7, 0x80, 0x3C, 0x40], AT)), vec!["F0 [43, 10, 4C, 00, 7F, F7]", "[80, 3C, 40]"]);
+  assert!(!decoder.in_sysex());
+
+  // sysex cut by other status byte is emitted without F7
+  assert_eq!(describe(decoder.push(&[0xF0, 0x01, 0x02, 0x90, 0x3C, 0x64], AT)), vec!["F0 [01, 02]", "[90, 3C, 64]"]);
+}
+
+#[test]
+fn sysex_limit_splits_in_packets() {
+  let mut decoder = StreamDecoder::new().with_sysex_limit(3);
+  let mut messages = decoder.push(&[0xF0, 0x01, 0x02, 0x03, 0x04], AT);
+  messages.extend(decoder.push(&[0x05, 0x06, 0x07, 0xF7], AT));
+  assert_eq!(describe(messages), vec!["F0 [01, 02, 03]", "F7 [04, 05, 06]", "F7 [07, F7]"]);
+
+  // next sysex starts with F0 again
+  assert_eq!(describe(decoder.push(&[0xF0, 0x01, 0xF7], AT)), vec!["F0 [01, F7]"]);
+}
+
+#[test]
+fn active_sensing_times_out() {
+  let ms = Duration::from_millis;
+  let mut decoder = StreamDecoder::new();
+  // no timeout before first Active Sensing
+  assert!(decoder.poll(ms(1000)).is_none());
+
+  let messages = decoder.push(&[0xFE], ms(1000));
+  assert!(matches!(messages[..], [LiveMessage::RealTime(SystemRealTime::ActiveSensing)]));
+  assert!(decoder.poll(ms(1300)).is_none());
+  decoder.push(&[0xF8], ms(1200));
+  assert!(decoder.poll(ms(1450)).is_none());
+  assert!(matches!(decoder.poll(ms(1501)), Some(LiveMessage::ActiveSensingTimeout)));
+  // reported once
+  assert!(decoder.poll(ms(2000)).is_none());
+
+  // timeout found when bytes arrive late
+  let mut decoder = StreamDecoder::new().with_active_sensing_timeout(ms(100));
+  decoder.push(&[0xFE], ms(0));
+  assert_eq!(describe(decoder.push(&[0x90, 0x3C, 0x64], ms(150))), vec!["timeout", "[90, 3C, 64]"]);
+}
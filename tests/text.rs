@@ -0,0 +1,46 @@
+//! Tests of text dump and assembler.
+
+use rmidirs::{parser::MidiParser, writer::MidiWriter};
+
+const TEST_MID : &[u8] = include_bytes!("../midis/test.mid");
+
+const SONG : &str = "\
+0, 0, Header, 1, 2, 480
+1, 0, Start_track
+1, 0, Title_t, \"Song, \"\"live\"\"\"
+1, 0, Tempo, 500000
+1, 0, Time_signature, 3, 2, 24, 8
+1, 0, Key_signature, -3, \"minor\"
+1, 0, End_track
+2, 0, Start_track
+2, 0, Program_c, 1, 5
+2, 0, Note_on_c, 1, 60, 100
+2, 240, Pitch_bend_c, 1, 16383
+2, 480, Note_off_c, 1, 60, 0
+2, 480, System_exclusive, 4, 126, 127, 9, 247
+2, 960, End_track
+0, 0, End_of_file
+";
+
+#[test]
+fn text_round_trips() {
+  let midi = rmidirs::text::from_text(SONG).unwrap();
+  assert_eq!(rmidirs::text::to_text(&midi), SONG);
+
+  // bytes written from assembled text are the bytes dumped
+  let bytes = MidiWriter::new(&midi).to_bytes().unwrap();
+  assert_eq!(rmidirs::text::to_text(&MidiParser::parse(&bytes).unwrap()), SONG);
+
+  let text = rmidirs::text::to_text(&MidiParser::parse(TEST_MID).unwrap());
+  assert_eq!(rmidirs::text::to_text(&rmidirs::text::from_text(&text).unwrap()), text);
+}
+
+#[test]
+fn data_bytes_above_127_are_rejected() {
+  let text = SONG.replace("Note_on_c, 1, 60, 100", "Note_on_c, 1, 60, 200");
+  let err = rmidirs::text::from_text(&text).unwrap_err();
+  assert_eq!(err.line(), 10);
+  assert!(err.message().contains("200"), "{}", err.message());
+
+  assert!(rmidirs::text::from_text(&SONG.replace("Program_c, 1, 5", "Program_c, 1, 128")).is_err());
+}
@@ -0,0 +1,43 @@
+//! Tests of tempo and measure maps.
+
+use rmidirs::model::timing::{TempoMap, MeasureMap, MeasurePosition};
+
+/// 3/4 at 120 bpm, 60 bpm from bar 2, 6/8 from middle of bar 2
+const SONG : &str = "\
+0, 0, Header, 1, 1, 480
+1, 0, Start_track
+1, 0, Tempo, 500000
+1, 0, Time_signature, 3, 2, 24, 8
+1, 1440, Tempo, 1000000
+1, 2400, Time_signature, 6, 3, 24, 8
+1, 4000, End_track
+0, 0, End_of_file
+";
+
+#[test]
+fn ticks_convert_to_seconds_and_back() {
+  let tempo_map = TempoMap::from(&rmidirs::text::from_text(SONG).unwrap());
+  assert_eq!(tempo_map.seconds(960), 1.0);
+  assert_eq!(tempo_map.seconds(1920), 2.5);
+  for tick in [0, 480, 1440, 1920, 4000] {
+    assert_eq!(tempo_map.tick(tempo_map.seconds(tick)), tick);
+  }
+}
+
+#[test]
+fn time_signature_change_inside_bar_starts_new_bar() {
+  let measure_map = MeasureMap::from(&rmidirs::text::from_text(SONG).unwrap());
+  assert_eq!(measure_map.position(1440), MeasurePosition::new(2, 1, 0));
+  assert_eq!(measure_map.position(1930), MeasurePosition::new(2, 2, 10));
+  // bar 2 is cut short, eighth note beats of 6/8 follow
+  assert_eq!(measure_map.position(2400), MeasurePosition::new(3, 1, 0));
+  assert_eq!(measure_map.position(2640), MeasurePosition::new(3, 2, 0));
+  assert_eq!(measure_map.downbeats(4000), vec![0, 1440, 2400, 3840]);
+  assert_eq!(measure_map.position_at(2.5), MeasurePosition::new(2, 2, 0));
+
+  for tick in [0, 700, 1440, 2399, 2400, 3900] {
+    assert_eq!(measure_map.tick(&measure_map.position(tick)), tick);
+  }
+  // beats overflowing the bar are carried
+  assert_eq!(measure_map.tick(&MeasurePosition::new(1, 4, 0)), 1440);
+}
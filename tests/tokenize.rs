@@ -0,0 +1,112 @@
+//! Tests of tokenization schemes.
+
+use rmidirs::{
+  model::note_seq::{NoteSeq, Note, TrackSeq},
+  tokenize::{MidiLike, Remi, Token, Tokenizer, TokenizerConfig}
+};
+
+#[test]
+fn remi_time_signature_changes_inside_one_bar() {
+  // 120 qbpm, so every quarter beat is 0.5 seconds
+  let mut note_seq = NoteSeq::default();
+  note_seq.set_time_signature(0.5, (3, 4));
+  note_seq.set_time_signature(1.0, (2, 4));
+  note_seq.add_note(Note::new(60, 64, 2.0, 2.5));
+  note_seq.add_note(Note::new(62, 64, 3.0, 3.5));
+
+  // both changes start with 2nd bar, last one wins
+  let tokens = Remi::default().tokenize(&note_seq);
+  assert_eq!(tokens, vec![
+    Token::Bar, Token::Bar, Token::Position(0), Token::Velocity(16), Token::Pitch(60), Token::Duration(4),
+    Token::Bar, Token::Position(0), Token::Velocity(16), Token::Pitch(62), Token::Duration(4),
+  ]);
+}
+
+#[test]
+fn zero_config_values_are_clamped() {
+  let config = TokenizerConfig { velocity_bins : 0, max_time_shift : 0, beat_resolution : 0, max_duration : 0, ..TokenizerConfig::default() };
+  let mut note_seq = NoteSeq::default();
+  note_seq.add_note(Note::new(60, 100, 0.0, 0.03));
+
+  let midi_like = MidiLike::new(config.clone());
+  assert_eq!(midi_like.config().velocity_bins, 1);
+  assert_eq!(midi_like.tokenize(&note_seq), vec![
+    Token::Velocity(0), Token::NoteOn(60), Token::TimeShift(1), Token::TimeShift(1), Token::TimeShift(1), Token::NoteOff(60),
+  ]);
+  assert_eq!(Remi::new(config).tokenize(&note_seq), vec![Token::Bar, Token::Position(0), Token::Velocity(0), Token::Pitch(60), Token::Duration(1)]);
+}
+
+#[test]
+fn format_1_tracks_use_conductor_time_signatures() {
+  let midi = rmidirs::text::from_text("\
+0, 0, Header, 1, 2, 480
+1, 0, Start_track
+1, 0, Time_signature, 3, 2, 24, 8
+1, 0, End_track
+2, 0, Start_track
+2, 1440, Note_on_c, 0, 60, 100
+2, 1920, Note_off_c, 0, 60, 0
+2, 1920, End_track
+0, 0, End_of_file
+").unwrap();
+  let tokens = Remi::default().tokenize_tracks(&TrackSeq::from(midi));
+  assert_eq!(tokens[0], vec![]);
+  // 4th beat starts second bar of 3/4
+  assert_eq!(tokens[1], vec![Token::Bar, Token::Bar, Token::Position(0), Token::Velocity(25), Token::Pitch(60), Token::Duration(4)]);
+}
+
+/// notes as (pitch, velocity, start, end), in order of start
+fn notes(note_seq : &NoteSeq) -> Vec<(u32, u32, f32, f32)> {
+  let mut notes : Vec<_> = note_seq.notes().iter()
+    .map(|note| (note.pitch(), note.velocity(), note.start_time(), note.end_time()))
+    .collect();
+  notes.sort_by(|a, b| a.2.total_cmp(&b.2).then(a.0.cmp(&b.0)));
+  notes
+}
+
+/// chord, then note after rest longer than single TIME_SHIFT, all on grid of both schemes
+fn song() -> NoteSeq {
+  let mut note_seq = NoteSeq::default();
+  note_seq.add_note(Note::new(60, 100, 0.0, 0.5));
+  note_seq.add_note(Note::new(64, 100, 0.0, 0.5));
+  note_seq.add_note(Note::new(67, 36, 0.5, 1.0));
+  note_seq.add_note(Note::new(72, 100, 4.0, 4.25));
+  note_seq
+}
+
+#[test]
+fn midi_like_round_trip() {
+  let midi_like = MidiLike::default();
+  let note_seq = song();
+  let tokens = midi_like.tokenize(&note_seq);
+  // 3 seconds of rest is split in 100 step shifts
+  assert_eq!(tokens.iter().filter(|token| **token == Token::TimeShift(100)).count(), 3);
+
+  // velocities come back as centers of their bins
+  let expected = vec![(60, 102, 0.0, 0.5), (64, 102, 0.0, 0.5), (67, 38, 0.5, 1.0), (72, 102, 4.0, 4.25)];
+  assert_eq!(notes(&midi_like.detokenize(&tokens)), expected);
+  assert_eq!(notes(&midi_like.decode(&midi_like.encode(&note_seq))), expected);
+}
+
+#[test]
+fn remi_round_trip() {
+  let remi = Remi::default();
+  let mut note_seq = song();
+  // 2nd bar in 3/4, so last note is on 2nd beat of 3rd bar
+  note_seq.set_time_signature(2.0, (3, 4));
+  let tokens = remi.tokenize(&note_seq);
+  assert_eq!(&tokens[tokens.len() - 6..], &[
+    Token::Bar, Token::Bar, Token::Position(4), Token::Velocity(25), Token::Pitch(72), Token::Duration(2),
+  ]);
+
+  let expected = vec![(60, 102, 0.0, 0.5), (64, 102, 0.0, 0.5), (67, 38, 0.5, 1.0), (72, 102, 4.0, 4.25)];
+  let decoded = remi.detokenize_with_meter(&tokens, &note_seq);
+  assert_eq!(notes(&decoded), expected);
+  assert_eq!(decoded.time_signatures().len(), 2);
+  assert_eq!(remi.tokenize(&decoded), tokens);
+
+  // ids round trip same as tokens, on default grid without meter
+  let ids = remi.encode(&note_seq);
+  assert_eq!(ids.len(), tokens.len());
+  assert_eq!(remi.tokenize(&remi.decode(&ids)).len(), tokens.len());
+}
@@ -0,0 +1,63 @@
+//! Tests of track iteration and editing.
+
+use rmidirs::{
+  model::core::midi_event::{MidiMessage, channel_message::ChannelMessage},
+  parser::MidiParser,
+  writer::MidiWriter
+};
+
+/// track chunk with single note of `pitch`
+fn track_chunk(pitch : u8) -> Vec<u8> {
+  let mut bytes = b"MTrk\x00\x00\x00\x0C".to_vec();
+  bytes.extend([0x00, 0x90, pitch, 0x40, 0x60, 0x80, pitch, 0x00, 0x00, 0xFF, 0x2F, 0x00]);
+  bytes
+}
+
+/// pitch of first event of every track
+fn first_pitches(midi : &rmidirs::model::core::midi::Midi) -> Vec<u8> {
+  midi.tracks().iter().map(|track| match track.iter().next().map(|event| event.message()) {
+    Some(MidiMessage::ChannelMessage(message @ ChannelMessage::NoteOn(_))) => message.get_note_number().map_or(0, |note| *note as u8),
+    _ => 0,
+  }).collect()
+}
+
+#[test]
+fn reorder_keeps_unknown_chunks_in_front_of_their_tracks() {
+  let mut bytes = b"MThd\x00\x00\x00\x06\x00\x01\x00\x02\x01\xE0".to_vec();
+  bytes.extend(b"XAAA\x00\x00\x00\x01a");
+  bytes.extend(track_chunk(60));
+  bytes.extend(b"XBBB\x00\x00\x00\x01b");
+  bytes.extend(track_chunk(62));
+  bytes.extend(b"XEND\x00\x00\x00\x01e");
+  let mut midi = MidiParser::parse(&bytes).unwrap();
+
+  midi.reorder_tracks(&[1, 0]);
+  let chunks : Vec<(String, usize)> = midi.unknown_chunks().iter().map(|chunk| (chunk.name(), chunk.position())).collect();
+  assert_eq!(chunks, vec![(String::from("XBBB"), 0), (String::from("XAAA"), 1), (String::from("XEND"), 2)]);
+
+  let written = MidiWriter::new(&midi).to_bytes().unwrap();
+  let mut expected = bytes[..14].to_vec();
+  expected.extend(b"XBBB\x00\x00\x00\x01b");
+  expected.extend(track_chunk(62));
+  expected.extend(b"XAAA\x00\x00\x00\x01a");
+  expected.extend(track_chunk(60));
+  expected.extend(b"XEND\x00\x00\x00\x01e");
+  assert_eq!(written, expected);
+  assert_eq!(first_pitches(&MidiParser::parse(&written).unwrap()), vec![62, 60]);
+}
+
+#[test]
+fn iterators_yield_every_event() {
+  let midi = MidiParser::parse(&[b"MThd\x00\x00\x00\x06\x00\x00\x00\x01\x01\xE0".to_vec(), track_chunk(60)].concat()).unwrap();
+  let track = &midi.tracks()[0];
+  assert_eq!(track.len(), 3);
+  assert_eq!(track.iter().len(), 3);
+  assert_eq!(track.iter().count(), 3);
+  // last event is End Of Track
+  assert!(track.iter().next_back().unwrap().is_end_of_track());
+  assert!(track.iter().rev().nth(2).unwrap().is_note_on_event());
+
+  let ticks : Vec<u64> = track.iter_absolute().map(|(tick, _)| tick).collect();
+  assert_eq!(ticks, vec![0, 96, 96]);
+  assert_eq!(track.end_tick(), 96);
+}
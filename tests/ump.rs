@@ -0,0 +1,142 @@
+//! Tests of Universal MIDI Packets, MIDI 1.0 to 2.0 scaling and MIDI Clip File.
+
+use rmidirs::{
+  model::{
+    core::midi_event::channel_message::ChannelMessage,
+    ump::{
+      Ump, Utility, Midi1ChannelVoice, Midi2ChannelVoice, Midi2Message, SysEx7, SysEx8, FlexData, StreamMessage,
+      translate::{scale_up, scale_down},
+      clip::MidiClip
+    }
+  },
+  parser::ClipParser,
+  writer::ClipWriter
+};
+
+/// one packet of every message type
+fn packets() -> Vec<Ump> {
+  let mut packets = vec![
+    Ump::Utility(Utility::NoOp),
+    Ump::Utility(Utility::JrTimestamp(0x1234)),
+    Ump::Utility(Utility::DeltaClockstamp(0xABCDE)),
+    Ump::System { group : 2, status : 0xF2, data : [0x10, 0x20] },
+    Ump::Midi1ChannelVoice(Midi1ChannelVoice::new(3, ChannelMessage::from((0x91, &[60u8, 100][..])))),
+    Ump::Midi2ChannelVoice(Midi2ChannelVoice::new(4, 5, Midi2Message::NoteOn { note : 60, velocity : 0x8000, attribute_type : 3, attribute : 0x1234 })),
+    Ump::Midi2ChannelVoice(Midi2ChannelVoice::new(4, 5, Midi2Message::RegisteredController { bank : 0, index : 1, value : 0x8000_0000 })),
+    Ump::Midi2ChannelVoice(Midi2ChannelVoice::new(4, 5, Midi2Message::ProgramChange { program : 8, bank : Some((1, 2)) })),
+    Ump::FlexData(FlexData::tempo(0, 50_000_000)),
+    Ump::Stream(StreamMessage::StartOfClip),
+    Ump::Unknown(vec![0x6000_0000]),
+  ];
+  packets.extend(SysEx7::packets(1, &(0..20).collect::<Vec<u8>>()).into_iter().map(Ump::SysEx7));
+  packets.extend(SysEx8::packets(1, 7, &(0..30).collect::<Vec<u8>>()).into_iter().map(Ump::SysEx8));
+  packets
+}
+
+fn words(packets : &[Ump]) -> Vec<u32> {
+  packets.iter().flat_map(|ump| ump.words()).collect()
+}
+
+#[test]
+fn packets_round_trip_through_words() {
+  let packets = packets();
+  let words = words(&packets);
+  let parsed = Ump::parse_all(&words).unwrap();
+  assert_eq!(parsed.len(), packets.len());
+  for (parsed, packet) in parsed.iter().zip(&packets) {
+    assert_eq!(parsed.message_type(), packet.message_type());
+    assert_eq!(parsed.group(), packet.group());
+    assert_eq!(parsed.words(), packet.words());
+  }
+
+  let sysex : Vec<SysEx7> = parsed.into_iter().filter_map(|ump| match ump { Ump::SysEx7(sysex) => Some(sysex), _ => None }).collect();
+  assert_eq!(SysEx7::join(&sysex), (0..20).collect::<Vec<u8>>());
+}
+
+#[test]
+fn midi2_note_on_words() {
+  // group 0, channel 0, note 60, velocity 0x8000, no attribute
+  let words = [0x4090_3C00, 0x8000_0000];
+  let (ump, len) = Ump::from_words(&words).unwrap();
+  assert_eq!(len, 2);
+  match &ump {
+    Ump::Midi2ChannelVoice(message) => assert_eq!(message.message(), &Midi2Message::NoteOn { note : 60, velocity : 0x8000, attribute_type : 0, attribute : 0 }),
+    other => panic!("{:?}", other),
+  }
+  assert_eq!(ump.words(), words.to_vec());
+}
+
+#[test]
+fn truncated_packet_fails_at_its_offset() {
+  let mut words = words(&[Ump::Utility(Utility::NoOp), Ump::FlexData(FlexData::tempo(0, 50_000_000))]);
+  words.pop();
+  let err = Ump::parse_all(&words).unwrap_err();
+  assert_eq!(err.offset(), 1);
+}
+
+#[test]
+fn scaling_follows_min_center_max() {
+  // examples of default translation in UMP specification
+  assert_eq!(scale_up(0x00, 7, 16), 0x0000);
+  assert_eq!(scale_up(0x40, 7, 16), 0x8000);
+  assert_eq!(scale_up(0x7F, 7, 16), 0xFFFF);
+  assert_eq!(scale_up(0x00, 7, 32), 0x0000_0000);
+  assert_eq!(scale_up(0x40, 7, 32), 0x8000_0000);
+  assert_eq!(scale_up(0x7F, 7, 32), 0xFFFF_FFFF);
+  assert_eq!(scale_up(0x2000, 14, 32), 0x8000_0000);
+  assert_eq!(scale_up(0x3FFF, 14, 32), 0xFFFF_FFFF);
+  // values below center are shifted, above center low bits repeat
+  assert_eq!(scale_up(0x20, 7, 16), 0x4000);
+  assert_eq!(scale_up(0x41, 7, 16), 0x8208);
+
+  assert_eq!(scale_down(0xFFFF, 16, 7), 0x7F);
+  assert_eq!(scale_down(0x8000_0000, 32, 14), 0x2000);
+
+  for value in 0 .. 0x80 {
+    assert_eq!(scale_down(scale_up(value, 7, 32), 32, 7), value);
+    assert!(value == 0 || scale_up(value, 7, 16) > scale_up(value - 1, 7, 16));
+  }
+  for value in 0 .. 0x4000 {
+    assert_eq!(scale_down(scale_up(value, 14, 32), 32, 14), value);
+  }
+}
+
+#[test]
+fn clip_round_trips_through_file() {
+  let mut clip = MidiClip::new(960);
+  for ump in FlexData::texts(0, None, 0x01, 0x01, "project name") {
+    clip.add_header(Ump::FlexData(ump));
+  }
+  clip.add_event(0, Ump::FlexData(FlexData::tempo(0, 50_000_000)));
+  clip.add_event(0, Ump::Midi2ChannelVoice(Midi2ChannelVoice::new(0, 0, Midi2Message::NoteOn { note : 60, velocity : 0xFFFF, attribute_type : 0, attribute : 0 })));
+  // longer than single Delta Clockstamp
+  clip.add_event(0x12_3456, Ump::Midi2ChannelVoice(Midi2ChannelVoice::new(0, 0, Midi2Message::NoteOff { note : 60, velocity : 0, attribute_type : 0, attribute : 0 })));
+  clip.set_end_delta(960);
+
+  let bytes = ClipWriter::new(&clip).to_bytes();
+  assert_eq!(&bytes[..8], b"SMF2CLIP");
+  // Delta Clockstamp Ticks Per Quarter Note comes first
+  assert_eq!(&bytes[8..12], &[0x00, 0x30, 0x03, 0xC0]);
+
+  let parsed = ClipParser::parse(&bytes).unwrap();
+  assert_eq!(parsed.ticks_per_quarter(), 960);
+  assert_eq!(words(parsed.header()), words(clip.header()));
+  assert_eq!(parsed.events().len(), 3);
+  for ((delta, ump), (expected_delta, expected)) in parsed.events().iter().zip(clip.events()) {
+    assert_eq!(delta, expected_delta);
+    assert_eq!(ump.words(), expected.words());
+  }
+  assert_eq!(parsed.end_delta(), 960);
+  assert_eq!(parsed.length(), clip.length());
+  assert_eq!(ClipWriter::new(&parsed).to_bytes(), bytes);
+}
+
+#[test]
+fn clip_without_end_fails() {
+  let bytes = ClipWriter::new(&MidiClip::default()).to_bytes();
+  assert!(ClipParser::parse(&bytes).is_ok());
+  // End of Clip and its Delta Clockstamp are cut
+  let err = ClipParser::parse(&bytes[..bytes.len() - 20]).unwrap_err();
+  assert_eq!(err.message(), "End of Clip is missing");
+  assert!(ClipParser::parse(b"SMF1CLIP").is_err());
+}
@@ -0,0 +1,79 @@
+//! Tests of js bindings, run under node with `wasm-pack test --node`.
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen_test::*;
+
+use rmidirs::web::rmidi::rMidi;
+
+const TEST_MID : &[u8] = include_bytes!("../midis/test.mid");
+
+fn parse(json : &str) -> serde_json::Value {
+  serde_json::from_str(json).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn parses_header_and_tracks() {
+  let midi = rMidi::from_bytes(TEST_MID).unwrap();
+  let header = parse(&midi.header());
+  assert_eq!(header["format"], 1);
+  assert_eq!(header["ntrk"], 1);
+  assert_eq!(header["division"]["ticksPerQuarter"], 480);
+
+  let tracks = parse(&midi.tracks());
+  assert_eq!(tracks.as_array().unwrap().len(), midi.track_count());
+  assert_eq!(tracks[0]["events"], 28);
+}
+
+#[wasm_bindgen_test]
+fn events_have_absolute_ticks() {
+  let midi = rMidi::from_bytes(TEST_MID).unwrap();
+  let events = parse(&midi.events(0).unwrap());
+  let events = events.as_array().unwrap();
+  assert_eq!(events[0]["kind"], "meta");
+  assert_eq!(events[0]["metaType"], 3);
+  assert_eq!(events.last().unwrap()["metaType"], 0x2F);
+
+  let mut tick = 0;
+  for event in events {
+    tick += event["delta"].as_u64().unwrap();
+    assert_eq!(event["tick"].as_u64().unwrap(), tick);
+  }
+  assert!(events.iter().any(|event| event["type"] == "note_on"));
+}
+
+#[wasm_bindgen_test]
+fn missing_track_throws() {
+  let midi = rMidi::from_bytes(TEST_MID).unwrap();
+  assert!(midi.events(1).is_err());
+  assert!(midi.to_note_seq(Some(1)).is_err());
+}
+
+#[wasm_bindgen_test]
+fn smpte_note_seq_throws() {
+  // 25 frames per second, 40 ticks per frame
+  let midi = rMidi::from_bytes(b"MThd\x00\x00\x00\x06\x00\x00\x00\x01\xE7\x28MTrk\x00\x00\x00\x04\x00\xFF\x2F\x00").unwrap();
+  assert!(midi.to_note_seq(None).is_err());
+}
+
+#[wasm_bindgen_test]
+fn invalid_bytes_throw() {
+  assert!(rMidi::from_bytes(b"MThd").is_err());
+}
+
+#[wasm_bindgen_test]
+fn to_note_seq() {
+  let midi = rMidi::from_bytes(TEST_MID).unwrap();
+  let note_seq = parse(&midi.to_note_seq(None).unwrap());
+  let notes = note_seq["notes"].as_array().unwrap();
+  assert!(!notes.is_empty());
+  assert_eq!(notes[0]["pitch"], 71);
+  assert_eq!(notes[0]["startTime"], 0.0);
+}
+
+#[wasm_bindgen_test]
+fn to_bytes_round_trips() {
+  let midi = rMidi::from_bytes(TEST_MID).unwrap();
+  let written = rMidi::from_bytes(&midi.to_bytes().unwrap()).unwrap();
+  assert_eq!(written.header(), midi.header());
+  assert_eq!(written.events(0).unwrap(), midi.events(0).unwrap());
+}
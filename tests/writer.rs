@@ -0,0 +1,61 @@
+//! Tests of midi encoding.
+
+use rmidirs::{
+  model::core::{
+    midi_builder::MidiBuilder,
+    midi_header::{MidiFormat, MidiDivision},
+    midi_track::MidiTrack,
+    midi_event::{MidiEvent, MidiMessage, delta_time::DeltaTime, channel_message::ChannelMessage, meta_message::MetaMessage},
+    validation::ViolationKind
+  },
+  parser::MidiParser
+};
+
+fn event(delta_time : u32, message : MidiMessage) -> MidiEvent {
+  MidiEvent::new(DeltaTime::from(delta_time), message)
+}
+
+fn channel(bytes : &[u8]) -> MidiMessage {
+  MidiMessage::ChannelMessage(ChannelMessage::from((bytes[0], &bytes[1..])))
+}
+
+#[test]
+fn dropped_message_keeps_its_delta_time() {
+  let mut track = MidiTrack::default();
+  track.add_event(event(0, channel(&[0x90, 60, 100])));
+  track.add_event(event(100, MidiMessage::MetaMessage(MetaMessage::Invalid(String::from("unknown")))));
+  track.add_event(event(100, channel(&[0x80, 60, 0])));
+  track.add_event(event(0, MidiMessage::MetaMessage(MetaMessage::EndOfTrack)));
+
+  let mut bytes = b"MThd\x00\x00\x00\x06\x00\x00\x00\x01\x01\xE0".to_vec();
+  bytes.extend(Vec::<u8>::from(track));
+  let midi = MidiParser::parse(&bytes).unwrap();
+
+  let ticks : Vec<u64> = midi.tracks()[0].iter_absolute().map(|(tick, _)| tick).collect();
+  assert_eq!(ticks, vec![0, 200, 200]);
+}
+
+#[test]
+fn invalid_division_is_rejected() {
+  let mut track = MidiTrack::default();
+  track.add_event(event(0, MidiMessage::MetaMessage(MetaMessage::EndOfTrack)));
+
+  let err = MidiBuilder::new(MidiFormat::MultiTracks, MidiDivision::Invalid(String::from("unknown")))
+    .track(track)
+    .build()
+    .unwrap_err();
+  assert_eq!(err.violations().len(), 1);
+  assert_eq!(err.violations()[0].kind(), ViolationKind::InvalidDivision);
+}
+
+#[test]
+fn invalid_meta_message_is_rejected() {
+  let mut track = MidiTrack::default();
+  track.add_event(event(0, MidiMessage::MetaMessage(MetaMessage::Invalid(String::from("unknown")))));
+  track.add_event(event(0, MidiMessage::MetaMessage(MetaMessage::EndOfTrack)));
+
+  let err = MidiBuilder::default().track(track).build().unwrap_err();
+  assert_eq!(err.violations().len(), 1);
+  assert_eq!(err.violations()[0].kind(), ViolationKind::InvalidMessage);
+  assert_eq!(err.violations()[0].event(), Some(0));
+}